@@ -0,0 +1,142 @@
+use crate::prelude::*;
+
+/// Connection of a bot, bots in tests talk to a server in the same process.
+pub enum BotTransport {
+    Netcode(NetcodeClientTransport),
+    Memory(MemoryClientTransport),
+}
+
+impl BotTransport {
+    pub fn update(
+        &mut self,
+        duration: Duration,
+        client: &mut RenetClient,
+    ) -> Result<(), NetcodeTransportError> {
+        match self {
+            BotTransport::Netcode(transport) => transport.update(duration, client),
+            BotTransport::Memory(transport) => {
+                transport.update(client);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn send_packets(&mut self, client: &mut RenetClient) -> Result<(), NetcodeTransportError> {
+        match self {
+            BotTransport::Netcode(transport) => transport.send_packets(client),
+            BotTransport::Memory(transport) => {
+                transport.send_packets(client);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn disconnect(&mut self) {
+        match self {
+            BotTransport::Netcode(transport) => transport.disconnect(),
+            BotTransport::Memory(transport) => transport.disconnect(),
+        }
+    }
+}
+
+/// A headless client with its own connection to the server.
+#[derive(Component)]
+pub struct Bot {
+    pub index: usize,
+    pub behaviour: bot_settings::Behaviour,
+    pub client: RenetClient,
+    pub transport: BotTransport,
+    /// Fresh for every run, so bots always join as new players.
+    pub identity: PlayerIdentity,
+    /// Position as last computed by the bot, seeded by the server on spawn and corrections.
+    pub position: Vec3,
+    /// Where the server spawned the bot, `None` until the bot joined.
+    pub spawn_point: Option<Vec3>,
+    pub vertical_velocity: f32,
+    /// Chunks around the spawn that walking bots collide with.
+    pub terrain: ChunkManager,
+    pub movement_sequence: u32,
+    pub connected: bool,
+    pub last_action: Option<Instant>,
+    pub action_count: u64,
+    /// Chunk positions that were requested but not received yet, with the time of the request.
+    pub pending_chunks: HashMap<[i32; 3], Instant>,
+    /// Whether the block next to the bot is currently placed, used by the build behaviour.
+    pub block_placed: bool,
+}
+
+impl Bot {
+    pub fn connect(
+        index: usize,
+        behaviour: bot_settings::Behaviour,
+        settings: &bot_settings::BotSettings,
+    ) -> std::io::Result<Self> {
+        let client_id = rand::random::<u64>();
+        let authentication = ClientAuthentication::Unsecure {
+            server_addr: settings.server_address,
+            client_id,
+            user_data: None,
+            protocol_id: 0,
+        };
+        let socket = UdpSocket::bind(settings.local_bind_address())?;
+        let current_time = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap();
+        let transport = NetcodeClientTransport::new(current_time, authentication, socket)?;
+
+        Ok(Self::new(
+            index,
+            behaviour,
+            BotTransport::Netcode(transport),
+        ))
+    }
+
+    pub fn new(index: usize, behaviour: bot_settings::Behaviour, transport: BotTransport) -> Self {
+        Self {
+            index,
+            behaviour,
+            client: RenetClient::new(connection_config()),
+            transport,
+            identity: PlayerIdentity::random(),
+            position: Vec3::ZERO,
+            spawn_point: None,
+            vertical_velocity: 0.0,
+            terrain: ChunkManager::new(),
+            movement_sequence: 0,
+            connected: false,
+            last_action: None,
+            action_count: 0,
+            pending_chunks: HashMap::new(),
+            block_placed: false,
+        }
+    }
+
+    pub fn send(&mut self, channel: DefaultChannel, message: &NetworkingMessage) {
+        self.client
+            .send_message(channel, bincode::serialize(message).unwrap());
+    }
+
+    /// Records the chunk positions as requested and sends the batch request.
+    pub fn request_chunks(&mut self, positions: Vec<Vec3>) {
+        let now = Instant::now();
+        for position in positions.iter() {
+            self.pending_chunks.insert(chunk_key(*position), now);
+        }
+
+        self.send(
+            DefaultChannel::ReliableUnordered,
+            &NetworkingMessage::ChunkBatchRequest(positions),
+        );
+    }
+
+    /// Returns how long ago the chunk was requested, if this bot requested it.
+    pub fn complete_chunk_request(&mut self, position: Vec3) -> Option<Duration> {
+        self.pending_chunks
+            .remove(&chunk_key(position))
+            .map(|requested_at| requested_at.elapsed())
+    }
+}
+
+fn chunk_key(position: Vec3) -> [i32; 3] {
+    position.round().as_ivec3().to_array()
+}
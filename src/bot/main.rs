@@ -0,0 +1,56 @@
+use bevy::app::{PluginGroup, ScheduleRunnerPlugin};
+use bevy::ecs::schedule::IntoSystemConfigs;
+use bevy::log::LogPlugin;
+
+use crate::prelude::*;
+
+pub mod prelude;
+
+mod components;
+mod resources;
+mod settings;
+mod systems;
+
+const TICK_RATE: f64 = 60.0;
+
+fn main() {
+    let settings = match bot_settings::BotSettings::from_args(std::env::args().skip(1)) {
+        Ok(Some(settings)) => settings,
+        Ok(None) => {
+            println!("{}", bot_settings::USAGE);
+            return;
+        }
+        Err(error) => {
+            eprintln!("Invalid bot configuration: {}", error);
+            std::process::exit(1);
+        }
+    };
+
+    let mut app = App::new();
+    app.add_plugins(
+        MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(
+            1.0 / TICK_RATE,
+        ))),
+    );
+    app.add_plugins(LogPlugin::default());
+
+    info!("Starting bots with {:?}", settings);
+    app.insert_resource(settings);
+    app.insert_resource(bot_resources::BotStats::default());
+
+    app.add_systems(Startup, bot_systems::spawn_bots_system);
+    app.add_systems(
+        Update,
+        (
+            bot_systems::receive_messages_system,
+            bot_systems::run_behaviours_system,
+            bot_systems::send_packets_system,
+            bot_systems::sample_network_info_system,
+            bot_systems::exit_after_duration_system,
+        )
+            .chain(),
+    );
+    app.add_systems(Last, bot_systems::print_summary_on_exit_system);
+
+    app.run();
+}
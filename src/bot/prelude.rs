@@ -0,0 +1,26 @@
+// std crates
+pub use std::collections::HashMap;
+pub use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs, UdpSocket};
+pub use std::time::{Duration, Instant, SystemTime};
+
+// bevy crates
+pub use bevy::app::{App, AppExit, Last, Plugin, Startup, Update};
+pub use bevy::ecs::{component::*, event::*, query::*, system::*};
+pub use bevy::log::{debug, error, info, warn};
+pub use bevy::math::{Quat, Vec3};
+pub use bevy::time::Time;
+pub use bevy::MinimalPlugins;
+
+// networking crates
+pub use bevy_renet::netcode::{
+    ClientAuthentication, NetcodeClientTransport, NetcodeTransportError,
+};
+pub use renet::{ClientId, DefaultChannel, RenetClient};
+
+// my crates
+pub use rsmc::*;
+
+pub use crate::components as bot_components;
+pub use crate::resources as bot_resources;
+pub use crate::settings as bot_settings;
+pub use crate::systems as bot_systems;
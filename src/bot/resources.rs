@@ -0,0 +1,103 @@
+use std::fmt::Write;
+
+use crate::prelude::*;
+
+/// Measurements collected over the whole run, printed when the bots exit.
+#[derive(Resource, Default)]
+pub struct BotStats {
+    pub chunk_latencies: Vec<Duration>,
+    pub server_diagnostics: Vec<ServerDiagnostics>,
+    pub bytes_sent_per_second: Vec<f64>,
+    pub bytes_received_per_second: Vec<f64>,
+    pub round_trip_times: Vec<f64>,
+    pub messages_received: u64,
+    pub actions: u64,
+    pub disconnects: u64,
+}
+
+impl BotStats {
+    pub fn summary(&self, settings: &bot_settings::BotSettings, elapsed: Duration) -> String {
+        let mut summary = String::new();
+
+        let _ = writeln!(
+            summary,
+            "Ran {} bot(s) with behaviour '{}' against {} for {:.1}s",
+            settings.count,
+            settings.behaviour,
+            settings.server_address,
+            elapsed.as_secs_f32()
+        );
+        let _ = writeln!(
+            summary,
+            "  actions: {}, messages received: {}, disconnects: {}",
+            self.actions, self.messages_received, self.disconnects
+        );
+
+        let latencies_ms: Vec<f64> = self
+            .chunk_latencies
+            .iter()
+            .map(|latency| latency.as_secs_f64() * 1000.0)
+            .collect();
+        let _ = writeln!(
+            summary,
+            "  chunk latency (ms): {} over {} chunk(s)",
+            describe(&latencies_ms),
+            latencies_ms.len()
+        );
+
+        let _ = writeln!(
+            summary,
+            "  bandwidth per bot (bytes/s): sent {}, received {}",
+            describe(&self.bytes_sent_per_second),
+            describe(&self.bytes_received_per_second)
+        );
+        let _ = writeln!(
+            summary,
+            "  round trip time (ms): {}",
+            describe(&self.round_trip_times)
+        );
+
+        let average_tick_times: Vec<f64> = self
+            .server_diagnostics
+            .iter()
+            .map(|diagnostics| diagnostics.average_tick_time as f64)
+            .collect();
+        let max_tick_time = self
+            .server_diagnostics
+            .iter()
+            .map(|diagnostics| diagnostics.max_tick_time)
+            .fold(0.0, f32::max);
+        let _ = write!(
+            summary,
+            "  server tick time (ms): {}, worst tick {:.2}",
+            describe(&average_tick_times),
+            max_tick_time
+        );
+
+        summary
+    }
+}
+
+/// Formats min, average and max of the samples.
+fn describe(samples: &[f64]) -> String {
+    if samples.is_empty() {
+        return "no samples".to_string();
+    }
+
+    let min = samples.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = samples.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let average = samples.iter().sum::<f64>() / samples.len() as f64;
+
+    format!("min {:.2} / avg {:.2} / max {:.2}", min, average, max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(describe(&[]), "no samples");
+        assert_eq!(describe(&[1.0, 2.0, 6.0]), "min 1.00 / avg 3.00 / max 6.00");
+    }
+}
@@ -0,0 +1,214 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::prelude::*;
+
+pub const DEFAULT_SERVER_ADDR: &str = "127.0.0.1:5000";
+
+pub const USAGE: &str = "Usage: bot [OPTIONS]
+
+Options:
+  --server <ADDR>        Server to connect to (default: 127.0.0.1:5000)
+  --count <COUNT>        Number of bots to connect (default: 1)
+  --behaviour <NAME>     walk, chunks, build, chat or mixed (default: mixed)
+  --duration <SECONDS>   Time until the bots disconnect (default: 60)
+  --interval <SECONDS>   Time between scripted actions of a bot (default: 1)
+  --help                 Print this message";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Behaviour {
+    /// Walks in a circle on the ground around the spawn, sending a player update every tick.
+    Walk,
+    /// Requests batches of chunks around a random position.
+    Chunks,
+    /// Places and breaks blocks next to the spawn.
+    Build,
+    /// Sends chat messages.
+    Chat,
+    /// Cycles through all other behaviours, one per bot.
+    Mixed,
+}
+
+impl Behaviour {
+    const SCRIPTED: [Behaviour; 4] = [
+        Behaviour::Walk,
+        Behaviour::Chunks,
+        Behaviour::Build,
+        Behaviour::Chat,
+    ];
+
+    /// Behaviour the bot with the given index runs.
+    pub fn for_bot(self, index: usize) -> Behaviour {
+        match self {
+            Behaviour::Mixed => Self::SCRIPTED[index % Self::SCRIPTED.len()],
+            behaviour => behaviour,
+        }
+    }
+}
+
+impl FromStr for Behaviour {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "walk" => Ok(Behaviour::Walk),
+            "chunks" => Ok(Behaviour::Chunks),
+            "build" => Ok(Behaviour::Build),
+            "chat" => Ok(Behaviour::Chat),
+            "mixed" => Ok(Behaviour::Mixed),
+            _ => Err(format!("unknown behaviour '{}'", value)),
+        }
+    }
+}
+
+impl fmt::Display for Behaviour {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Behaviour::Walk => "walk",
+            Behaviour::Chunks => "chunks",
+            Behaviour::Build => "build",
+            Behaviour::Chat => "chat",
+            Behaviour::Mixed => "mixed",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct BotSettings {
+    pub server_address: SocketAddr,
+    pub count: usize,
+    pub behaviour: Behaviour,
+    pub duration: Duration,
+    pub action_interval: Duration,
+}
+
+impl Default for BotSettings {
+    fn default() -> Self {
+        Self {
+            server_address: DEFAULT_SERVER_ADDR.parse().unwrap(),
+            count: 1,
+            behaviour: Behaviour::Mixed,
+            duration: Duration::from_secs(60),
+            action_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+impl BotSettings {
+    /// Parses the command line, returns `Ok(None)` when the usage was requested.
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<Option<Self>, String> {
+        let mut settings = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(argument) = args.next() {
+            if argument == "--help" || argument == "-h" {
+                return Ok(None);
+            }
+
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for '{}'", argument))?;
+
+            match argument.as_str() {
+                "--server" | "-s" => {
+                    settings.server_address = value
+                        .to_socket_addrs()
+                        .map_err(|error| format!("could not resolve '{}': {}", value, error))?
+                        .next()
+                        .ok_or_else(|| format!("'{}' did not resolve to an address", value))?;
+                }
+                "--count" => settings.count = parse_value(&argument, &value)?,
+                "--behaviour" => settings.behaviour = value.parse()?,
+                "--duration" => {
+                    settings.duration = Duration::from_secs_f64(parse_seconds(&argument, &value)?)
+                }
+                "--interval" => {
+                    settings.action_interval =
+                        Duration::from_secs_f64(parse_seconds(&argument, &value)?)
+                }
+                _ => return Err(format!("unknown argument '{}'", argument)),
+            }
+        }
+
+        if settings.count == 0 {
+            return Err("count must be at least 1".to_string());
+        }
+
+        Ok(Some(settings))
+    }
+
+    /// Local address the bot sockets bind to, matching the address family of the server.
+    pub fn local_bind_address(&self) -> SocketAddr {
+        match self.server_address {
+            SocketAddr::V4(_) => SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)),
+            SocketAddr::V6(_) => SocketAddr::from((Ipv6Addr::UNSPECIFIED, 0)),
+        }
+    }
+}
+
+fn parse_value<T: FromStr>(argument: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value '{}' for '{}'", value, argument))
+}
+
+fn parse_seconds(argument: &str, value: &str) -> Result<f64, String> {
+    let seconds: f64 = parse_value(argument, value)?;
+
+    match seconds.is_finite() && seconds > 0.0 {
+        true => Ok(seconds),
+        false => Err(format!(
+            "'{}' must be a positive number of seconds",
+            argument
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_from_args() {
+        let settings = BotSettings::from_args(args(&[
+            "--count",
+            "8",
+            "--behaviour",
+            "chunks",
+            "--duration",
+            "2.5",
+        ]))
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(settings.count, 8);
+        assert_eq!(settings.behaviour, Behaviour::Chunks);
+        assert_eq!(settings.duration, Duration::from_millis(2500));
+        assert_eq!(
+            settings.server_address,
+            BotSettings::default().server_address
+        );
+    }
+
+    #[test]
+    fn test_from_args_errors() {
+        assert!(BotSettings::from_args(args(&["--count"])).is_err());
+        assert!(BotSettings::from_args(args(&["--count", "0"])).is_err());
+        assert!(BotSettings::from_args(args(&["--behaviour", "dance"])).is_err());
+        assert!(BotSettings::from_args(args(&["--duration", "-1"])).is_err());
+        assert_eq!(BotSettings::from_args(args(&["--help"])), Ok(None));
+    }
+
+    #[test]
+    fn test_mixed_behaviour_cycles() {
+        assert_eq!(Behaviour::Mixed.for_bot(0), Behaviour::Walk);
+        assert_eq!(Behaviour::Mixed.for_bot(5), Behaviour::Chunks);
+        assert_eq!(Behaviour::Chat.for_bot(1), Behaviour::Chat);
+    }
+}
@@ -0,0 +1,407 @@
+use crate::prelude::*;
+
+/// Walking speed in blocks per second, well below the server speed limit.
+const WALK_SPEED: f32 = 4.0;
+/// How fast the walking direction turns in radians per second, bots walk circles of
+/// `WALK_SPEED / WALK_TURN_RATE` blocks radius.
+const WALK_TURN_RATE: f32 = 0.5;
+const GRAVITY: f32 = 20.0;
+/// Jump speed when walking against a block, enough to climb one block.
+const JUMP_SPEED: f32 = 8.0;
+/// Chunks around the bot that requests are centered on, within the default server view distance.
+const CHUNK_REQUEST_RANGE: i32 = 6;
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+pub fn spawn_bots_system(mut commands: Commands, settings: Res<bot_settings::BotSettings>) {
+    for index in 0..settings.count {
+        let behaviour = settings.behaviour.for_bot(index);
+
+        match bot_components::Bot::connect(index, behaviour, &settings) {
+            Ok(bot) => {
+                info!("Spawned bot #{} with behaviour '{}'", index, behaviour);
+                commands.spawn(bot);
+            }
+            Err(error) => error!("Could not create bot #{}: {}", index, error),
+        }
+    }
+}
+
+pub fn receive_messages_system(
+    time: Res<Time>,
+    mut bots: Query<&mut bot_components::Bot>,
+    mut stats: ResMut<bot_resources::BotStats>,
+) {
+    for mut bot in bots.iter_mut() {
+        let bot = &mut *bot;
+
+        bot.client.update(time.delta());
+        if let Err(error) = bot.transport.update(time.delta(), &mut bot.client) {
+            warn!("Bot #{} transport error: {}", bot.index, error);
+        }
+
+        if bot.client.is_connected() && !bot.connected {
+            info!("Bot #{} connected", bot.index);
+            bot.connected = true;
+
+            // Saves are keyed by name, a bot of an earlier run would own the plain name
+            let profile = PlayerProfile {
+                name: format!("bot{}-{}", bot.index, &bot.identity.to_hex()[..4]),
+                ..PlayerProfile::default()
+            };
+            bot.send(
+                DefaultChannel::ReliableOrdered,
+                &NetworkingMessage::JoinGame(profile, bot.identity),
+            );
+        } else if bot.client.is_disconnected() && bot.connected {
+            warn!("Bot #{} was disconnected", bot.index);
+            bot.connected = false;
+            stats.disconnects += 1;
+        }
+
+        for channel in [
+            DefaultChannel::ReliableOrdered,
+            DefaultChannel::ReliableUnordered,
+            DefaultChannel::Unreliable,
+        ] {
+            while let Some(message) = bot.client.receive_message(channel) {
+                stats.messages_received += 1;
+
+                match bincode::deserialize(&message) {
+                    Ok(NetworkingMessage::ChunkBatchResponse(chunks)) => {
+                        for chunk in chunks {
+                            if let Some(latency) = bot.complete_chunk_request(chunk.position) {
+                                stats.chunk_latencies.push(latency);
+                            }
+                            if bot.behaviour == bot_settings::Behaviour::Walk {
+                                bot.terrain.insert_chunk(chunk);
+                            }
+                        }
+                    }
+                    Ok(NetworkingMessage::PlayerSpawn { position, .. }) => {
+                        bot.position = position;
+                        bot.spawn_point = Some(position);
+                        bot.vertical_velocity = 0.0;
+
+                        if bot.behaviour == bot_settings::Behaviour::Walk {
+                            request_walk_area(bot);
+                        }
+                    }
+                    Ok(NetworkingMessage::PlayerPositionCorrection(position)) => {
+                        warn!("Bot #{} was corrected to {}", bot.index, position);
+                        bot.position = position;
+                        bot.vertical_velocity = 0.0;
+                    }
+                    // Every bot receives the same report, only keep one copy
+                    Ok(NetworkingMessage::ServerDiagnostics(diagnostics)) if bot.index == 0 => {
+                        stats.server_diagnostics.push(diagnostics);
+                    }
+                    Ok(_) => {}
+                    Err(error) => {
+                        error!(
+                            "Bot #{} could not deserialize message {:?}",
+                            bot.index, error
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn run_behaviours_system(
+    time: Res<Time>,
+    settings: Res<bot_settings::BotSettings>,
+    mut bots: Query<&mut bot_components::Bot>,
+    mut stats: ResMut<bot_resources::BotStats>,
+) {
+    for mut bot in bots.iter_mut() {
+        if !bot.client.is_connected() {
+            continue;
+        }
+
+        if bot.behaviour == bot_settings::Behaviour::Walk {
+            walk(&mut bot, &time);
+        }
+
+        let action_due = bot
+            .last_action
+            .is_none_or(|last_action| last_action.elapsed() >= settings.action_interval);
+        if !action_due {
+            continue;
+        }
+
+        bot.last_action = Some(Instant::now());
+        bot.action_count += 1;
+        stats.actions += 1;
+
+        match bot.behaviour {
+            bot_settings::Behaviour::Chunks => request_chunks(&mut bot),
+            bot_settings::Behaviour::Build => toggle_block(&mut bot),
+            bot_settings::Behaviour::Chat => send_chat_message(&mut bot),
+            bot_settings::Behaviour::Walk | bot_settings::Behaviour::Mixed => {}
+        }
+    }
+}
+
+pub fn send_packets_system(mut bots: Query<&mut bot_components::Bot>) {
+    for mut bot in bots.iter_mut() {
+        let bot = &mut *bot;
+
+        if let Err(error) = bot.transport.send_packets(&mut bot.client) {
+            warn!("Bot #{} could not send packets: {}", bot.index, error);
+        }
+    }
+}
+
+pub fn sample_network_info_system(
+    mut last_sample: Local<Option<Instant>>,
+    bots: Query<&bot_components::Bot>,
+    mut stats: ResMut<bot_resources::BotStats>,
+) {
+    if last_sample.is_some_and(|last_sample| last_sample.elapsed() < SAMPLE_INTERVAL) {
+        return;
+    }
+    *last_sample = Some(Instant::now());
+
+    for bot in bots.iter().filter(|bot| bot.client.is_connected()) {
+        let network_info = bot.client.network_info();
+        stats
+            .bytes_sent_per_second
+            .push(network_info.bytes_sent_per_second);
+        stats
+            .bytes_received_per_second
+            .push(network_info.bytes_received_per_second);
+        stats.round_trip_times.push(network_info.rtt * 1000.0);
+    }
+}
+
+pub fn exit_after_duration_system(
+    time: Res<Time>,
+    settings: Res<bot_settings::BotSettings>,
+    mut exit_events: EventWriter<AppExit>,
+) {
+    if time.elapsed() >= settings.duration {
+        info!("Bot run finished after {:?}", settings.duration);
+        exit_events.send(AppExit::Success);
+    }
+}
+
+pub fn print_summary_on_exit_system(
+    mut exit_events: EventReader<AppExit>,
+    time: Res<Time>,
+    settings: Res<bot_settings::BotSettings>,
+    stats: Res<bot_resources::BotStats>,
+    mut bots: Query<&mut bot_components::Bot>,
+) {
+    if exit_events.read().next().is_none() {
+        return;
+    }
+
+    for mut bot in bots.iter_mut() {
+        let bot = &mut *bot;
+        bot.transport.disconnect();
+    }
+
+    println!("{}", stats.summary(&settings, time.elapsed()));
+}
+
+/// Requests the chunks around the spawn that the walking circle stays within.
+fn request_walk_area(bot: &mut bot_components::Bot) {
+    let chunk = (bot.position / CHUNK_SIZE as f32).floor();
+
+    let mut positions = Vec::new();
+    for x in -1..=1 {
+        for y in -1..=1 {
+            for z in -1..=1 {
+                positions.push(chunk + Vec3::new(x as f32, y as f32, z as f32));
+            }
+        }
+    }
+
+    bot.request_chunks(positions);
+}
+
+/// Walks in a circle on the terrain with gravity, moving through the chunks the same way the
+/// server replays the inputs so the moves pass its validation.
+fn walk(bot: &mut bot_components::Bot, time: &Time) {
+    // Unloaded chunks are not solid, the bot would fall through them
+    if bot.spawn_point.is_none() || !bot.pending_chunks.is_empty() {
+        return;
+    }
+
+    let delta = time.delta_secs();
+    let angle = time.elapsed_secs() * WALK_TURN_RATE + bot.index as f32;
+    let direction = Vec3::new(angle.cos(), 0.0, angle.sin());
+
+    bot.vertical_velocity -= GRAVITY * delta;
+    let displacement = direction * WALK_SPEED * delta + Vec3::Y * bot.vertical_velocity * delta;
+    let sweep = sweep_aabb(&bot.terrain, &Aabb::player(bot.position), displacement);
+
+    let on_ground = sweep.collided.y && bot.vertical_velocity < 0.0;
+    if sweep.collided.y {
+        bot.vertical_velocity = 0.0;
+    }
+    if on_ground && (sweep.collided.x || sweep.collided.z) {
+        bot.vertical_velocity = JUMP_SPEED;
+    }
+
+    bot.movement_sequence += 1;
+    let input = MovementInput {
+        sequence: bot.movement_sequence,
+        displacement,
+    };
+    bot.position += sweep.offset;
+
+    let state = PlayerState {
+        position: bot.position,
+        rotation: Quat::from_rotation_y(-angle),
+    };
+    bot.send(
+        DefaultChannel::ReliableOrdered,
+        &NetworkingMessage::PlayerUpdate { input, state },
+    );
+}
+
+fn request_chunks(bot: &mut bot_components::Bot) {
+    // The server rejects chunks outside its view distance around the bot
+    let chunk = (bot.position / CHUNK_SIZE as f32).floor();
+    let center_x = chunk.x as i32 + rand::random::<i32>().rem_euclid(CHUNK_REQUEST_RANGE * 2)
+        - CHUNK_REQUEST_RANGE;
+    let center_z = chunk.z as i32 + rand::random::<i32>().rem_euclid(CHUNK_REQUEST_RANGE * 2)
+        - CHUNK_REQUEST_RANGE;
+
+    let mut positions = Vec::new();
+    for x in -1..=1 {
+        for y in 0..2 {
+            for z in -1..=1 {
+                positions.push(Vec3::new(
+                    (center_x + x) as f32,
+                    chunk.y + y as f32,
+                    (center_z + z) as f32,
+                ));
+            }
+        }
+    }
+
+    bot.request_chunks(positions);
+}
+
+fn toggle_block(bot: &mut bot_components::Bot) {
+    let position = (bot.position + Vec3::X * 2.0).floor();
+    let block = match bot.block_placed {
+        true => BlockId::Air,
+        false => BlockId::Dirt,
+    };
+    bot.block_placed = !bot.block_placed;
+
+    bot.send(
+        DefaultChannel::ReliableOrdered,
+        &NetworkingMessage::BlockUpdate { position, block },
+    );
+}
+
+fn send_chat_message(bot: &mut bot_components::Bot) {
+    let message = format!("bot {} says hi #{}", bot.index, bot.action_count);
+    bot.send(
+        DefaultChannel::ReliableOrdered,
+        &NetworkingMessage::ChatMessageSend(message),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsmc::server::player::resources::PlayerMovements;
+    use rsmc::server::settings::ServerSettings;
+    use rsmc::server::ServerPlugins;
+
+    /// More moves than the server tolerates violations, so a drifting bot would be kicked.
+    const WALK_UPDATES: u32 = 60;
+    const MAX_STEPS: usize = 2000;
+
+    #[test]
+    fn test_walking_bot_stays_connected() {
+        let world_directory =
+            std::env::temp_dir().join(format!("rsmc-bot-test-{}", rand::random::<u64>()));
+
+        let mut server = App::new();
+        server.add_plugins(MinimalPlugins);
+        server.insert_resource(ServerSettings {
+            world_directory: world_directory.clone(),
+            view_distance: 1,
+            max_mobs: 0,
+            ..ServerSettings::default()
+        });
+        server.insert_resource(MemoryServerTransport::default());
+        server.add_plugins(ServerPlugins);
+        server.finish();
+        server.cleanup();
+
+        let (client_connection, server_connection) = memory_connection_pair();
+        let client_id: ClientId = rand::random();
+        server
+            .world_mut()
+            .resource_mut::<MemoryServerTransport>()
+            .connect(client_id, server_connection);
+
+        let mut bots = App::new();
+        bots.add_plugins(MinimalPlugins);
+        bots.insert_resource(bot_settings::BotSettings::default());
+        bots.insert_resource(bot_resources::BotStats::default());
+        bots.add_systems(
+            Update,
+            (
+                receive_messages_system,
+                run_behaviours_system,
+                send_packets_system,
+            )
+                .chain(),
+        );
+        let bot = bots
+            .world_mut()
+            .spawn(bot_components::Bot::new(
+                0,
+                bot_settings::Behaviour::Walk,
+                bot_components::BotTransport::Memory(MemoryClientTransport::new(client_connection)),
+            ))
+            .id();
+
+        let mut steps = 0;
+        while bots
+            .world()
+            .get::<bot_components::Bot>(bot)
+            .unwrap()
+            .movement_sequence
+            < WALK_UPDATES
+        {
+            assert!(steps < MAX_STEPS, "bot did not walk");
+            steps += 1;
+
+            bots.update();
+            server.update();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        for _ in 0..10 {
+            bots.update();
+            server.update();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        let bot = bots.world().get::<bot_components::Bot>(bot).unwrap();
+        let spawn_point = bot.spawn_point.unwrap();
+        assert!(bot.client.is_connected());
+        assert_eq!(
+            bots.world()
+                .resource::<bot_resources::BotStats>()
+                .disconnects,
+            0
+        );
+        assert!(bot.position.with_y(0.0).distance(spawn_point.with_y(0.0)) > 0.1);
+        assert_eq!(
+            server.world().resource::<PlayerMovements>().players[&client_id].violations,
+            0
+        );
+
+        let _ = std::fs::remove_dir_all(world_directory);
+    }
+}
@@ -0,0 +1,25 @@
+pub mod resources;
+pub mod systems;
+
+use std::path::Path;
+
+use crate::prelude::*;
+
+/// Maps the actions of the player to the inputs configured in the bindings file.
+pub struct ActionsPlugin;
+
+impl Plugin for ActionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(actions_resources::KeyBindings::load(Path::new(
+            actions_resources::KEY_BINDINGS_PATH,
+        )));
+
+        app.add_systems(
+            Update,
+            (
+                actions_systems::apply_movement_bindings_system,
+                actions_systems::save_key_bindings_system,
+            ),
+        );
+    }
+}
@@ -0,0 +1,514 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::prelude::*;
+
+pub const KEY_BINDINGS_PATH: &str = "bindings.toml";
+
+/// Something the player can do, bound to a key or mouse button in `KeyBindings`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveForward,
+    MoveBack,
+    MoveLeft,
+    MoveRight,
+    Jump,
+    Sprint,
+    Crouch,
+    FlyUp,
+    FlyDown,
+    BreakBlock,
+    PlaceBlock,
+    HotbarSlot1,
+    HotbarSlot2,
+    HotbarSlot3,
+    HotbarSlot4,
+    HotbarSlot5,
+    HotbarSlot6,
+    HotbarSlot7,
+    HotbarSlot8,
+    HotbarSlot9,
+    OpenChat,
+    ShowPlayerList,
+    ToggleDebug,
+    ToggleDebugOverlay,
+    Pause,
+}
+
+impl Action {
+    pub const ALL: [Action; 25] = [
+        Action::MoveForward,
+        Action::MoveBack,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Jump,
+        Action::Sprint,
+        Action::Crouch,
+        Action::FlyUp,
+        Action::FlyDown,
+        Action::BreakBlock,
+        Action::PlaceBlock,
+        Action::HotbarSlot1,
+        Action::HotbarSlot2,
+        Action::HotbarSlot3,
+        Action::HotbarSlot4,
+        Action::HotbarSlot5,
+        Action::HotbarSlot6,
+        Action::HotbarSlot7,
+        Action::HotbarSlot8,
+        Action::HotbarSlot9,
+        Action::OpenChat,
+        Action::ShowPlayerList,
+        Action::ToggleDebug,
+        Action::ToggleDebugOverlay,
+        Action::Pause,
+    ];
+
+    pub const HOTBAR: [Action; HOTBAR_SIZE] = [
+        Action::HotbarSlot1,
+        Action::HotbarSlot2,
+        Action::HotbarSlot3,
+        Action::HotbarSlot4,
+        Action::HotbarSlot5,
+        Action::HotbarSlot6,
+        Action::HotbarSlot7,
+        Action::HotbarSlot8,
+        Action::HotbarSlot9,
+    ];
+
+    /// Key of the action in the bindings file.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::MoveForward => "move_forward",
+            Action::MoveBack => "move_back",
+            Action::MoveLeft => "move_left",
+            Action::MoveRight => "move_right",
+            Action::Jump => "jump",
+            Action::Sprint => "sprint",
+            Action::Crouch => "crouch",
+            Action::FlyUp => "fly_up",
+            Action::FlyDown => "fly_down",
+            Action::BreakBlock => "break_block",
+            Action::PlaceBlock => "place_block",
+            Action::HotbarSlot1 => "hotbar_slot_1",
+            Action::HotbarSlot2 => "hotbar_slot_2",
+            Action::HotbarSlot3 => "hotbar_slot_3",
+            Action::HotbarSlot4 => "hotbar_slot_4",
+            Action::HotbarSlot5 => "hotbar_slot_5",
+            Action::HotbarSlot6 => "hotbar_slot_6",
+            Action::HotbarSlot7 => "hotbar_slot_7",
+            Action::HotbarSlot8 => "hotbar_slot_8",
+            Action::HotbarSlot9 => "hotbar_slot_9",
+            Action::OpenChat => "open_chat",
+            Action::ShowPlayerList => "show_player_list",
+            Action::ToggleDebug => "toggle_debug",
+            Action::ToggleDebugOverlay => "toggle_debug_overlay",
+            Action::Pause => "pause",
+        }
+    }
+
+    pub fn label(&self) -> String {
+        if let Some(slot) = Action::HOTBAR.iter().position(|action| action == self) {
+            return format!("Hotbar slot {}", slot + 1);
+        }
+
+        match self {
+            Action::MoveForward => "Move forward",
+            Action::MoveBack => "Move back",
+            Action::MoveLeft => "Move left",
+            Action::MoveRight => "Move right",
+            Action::Jump => "Jump",
+            Action::Sprint => "Sprint",
+            Action::Crouch => "Crouch",
+            Action::FlyUp => "Fly up",
+            Action::FlyDown => "Fly down",
+            Action::BreakBlock => "Break block",
+            Action::PlaceBlock => "Place block",
+            Action::OpenChat => "Open chat",
+            Action::ShowPlayerList => "Show player list",
+            Action::ToggleDebug => "Toggle debug mode",
+            Action::ToggleDebugOverlay => "Toggle debug overlay",
+            Action::Pause => "Pause",
+            _ => unreachable!("hotbar actions are labelled above"),
+        }
+        .into()
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|action| action.name() == name)
+    }
+
+    pub fn default_binding(&self) -> Binding {
+        if let Some(slot) = Action::HOTBAR.iter().position(|action| action == self) {
+            return Binding::Key(DIGIT_KEYS[slot + 1]);
+        }
+
+        match self {
+            Action::MoveForward => Binding::Key(KeyCode::KeyW),
+            Action::MoveBack => Binding::Key(KeyCode::KeyS),
+            Action::MoveLeft => Binding::Key(KeyCode::KeyA),
+            Action::MoveRight => Binding::Key(KeyCode::KeyD),
+            Action::Jump => Binding::Key(KeyCode::Space),
+            Action::Sprint => Binding::Key(KeyCode::ShiftLeft),
+            Action::Crouch => Binding::Key(KeyCode::ControlLeft),
+            Action::FlyUp => Binding::Key(KeyCode::KeyQ),
+            Action::FlyDown => Binding::Key(KeyCode::KeyE),
+            Action::BreakBlock => Binding::Mouse(MouseButton::Left),
+            Action::PlaceBlock => Binding::Mouse(MouseButton::Right),
+            Action::OpenChat => Binding::Key(KeyCode::KeyT),
+            Action::ShowPlayerList => Binding::Key(KeyCode::Backquote),
+            Action::ToggleDebug => Binding::Key(KeyCode::Tab),
+            Action::ToggleDebugOverlay => Binding::Key(KeyCode::F3),
+            Action::Pause => Binding::Key(KeyCode::Escape),
+            _ => unreachable!("hotbar actions are bound above"),
+        }
+    }
+
+    /// Movement is read by the fps controller, which only supports keyboard keys.
+    pub fn requires_key(&self) -> bool {
+        matches!(
+            self,
+            Action::MoveForward
+                | Action::MoveBack
+                | Action::MoveLeft
+                | Action::MoveRight
+                | Action::Jump
+                | Action::Sprint
+                | Action::Crouch
+                | Action::FlyUp
+                | Action::FlyDown
+        )
+    }
+}
+
+const DIGIT_KEYS: [KeyCode; 10] = [
+    KeyCode::Digit0,
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+/// Keys that can be written to and read from the bindings file, named like their `KeyCode`.
+pub const SUPPORTED_KEYS: &[KeyCode] = &[
+    KeyCode::KeyA,
+    KeyCode::KeyB,
+    KeyCode::KeyC,
+    KeyCode::KeyD,
+    KeyCode::KeyE,
+    KeyCode::KeyF,
+    KeyCode::KeyG,
+    KeyCode::KeyH,
+    KeyCode::KeyI,
+    KeyCode::KeyJ,
+    KeyCode::KeyK,
+    KeyCode::KeyL,
+    KeyCode::KeyM,
+    KeyCode::KeyN,
+    KeyCode::KeyO,
+    KeyCode::KeyP,
+    KeyCode::KeyQ,
+    KeyCode::KeyR,
+    KeyCode::KeyS,
+    KeyCode::KeyT,
+    KeyCode::KeyU,
+    KeyCode::KeyV,
+    KeyCode::KeyW,
+    KeyCode::KeyX,
+    KeyCode::KeyY,
+    KeyCode::KeyZ,
+    KeyCode::Digit0,
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+    KeyCode::F1,
+    KeyCode::F2,
+    KeyCode::F3,
+    KeyCode::F4,
+    KeyCode::F5,
+    KeyCode::F6,
+    KeyCode::F7,
+    KeyCode::F8,
+    KeyCode::F9,
+    KeyCode::F10,
+    KeyCode::F11,
+    KeyCode::F12,
+    KeyCode::Escape,
+    KeyCode::Tab,
+    KeyCode::Space,
+    KeyCode::Enter,
+    KeyCode::Backspace,
+    KeyCode::CapsLock,
+    KeyCode::ShiftLeft,
+    KeyCode::ShiftRight,
+    KeyCode::ControlLeft,
+    KeyCode::ControlRight,
+    KeyCode::AltLeft,
+    KeyCode::AltRight,
+    KeyCode::ArrowUp,
+    KeyCode::ArrowDown,
+    KeyCode::ArrowLeft,
+    KeyCode::ArrowRight,
+    KeyCode::Insert,
+    KeyCode::Delete,
+    KeyCode::Home,
+    KeyCode::End,
+    KeyCode::PageUp,
+    KeyCode::PageDown,
+    KeyCode::Backquote,
+    KeyCode::Minus,
+    KeyCode::Equal,
+    KeyCode::BracketLeft,
+    KeyCode::BracketRight,
+    KeyCode::Backslash,
+    KeyCode::Semicolon,
+    KeyCode::Quote,
+    KeyCode::Comma,
+    KeyCode::Period,
+    KeyCode::Slash,
+];
+
+const MOUSE_BUTTONS: [(MouseButton, &str); 5] = [
+    (MouseButton::Left, "MouseLeft"),
+    (MouseButton::Right, "MouseRight"),
+    (MouseButton::Middle, "MouseMiddle"),
+    (MouseButton::Back, "MouseBack"),
+    (MouseButton::Forward, "MouseForward"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+}
+
+impl Binding {
+    /// Whether the binding can be stored in the bindings file.
+    pub fn is_supported(&self) -> bool {
+        match self {
+            Binding::Key(key) => SUPPORTED_KEYS.contains(key),
+            Binding::Mouse(button) => MOUSE_BUTTONS.iter().any(|(known, _)| known == button),
+        }
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Binding::Key(key) => write!(f, "{:?}", key),
+            Binding::Mouse(button) => match MOUSE_BUTTONS.iter().find(|(known, _)| known == button)
+            {
+                Some((_, name)) => write!(f, "{}", name),
+                None => write!(f, "{:?}", button),
+            },
+        }
+    }
+}
+
+impl FromStr for Binding {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+
+        if let Some((button, _)) = MOUSE_BUTTONS.iter().find(|(_, name)| *name == value) {
+            return Ok(Binding::Mouse(*button));
+        }
+
+        SUPPORTED_KEYS
+            .iter()
+            .find(|key| format!("{:?}", key) == value)
+            .map(|key| Binding::Key(*key))
+            .ok_or_else(|| format!("unknown key '{}'", value))
+    }
+}
+
+/// Inputs bound to each action, stored next to the client in `bindings.toml`.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct KeyBindings {
+    bindings: HashMap<Action, Binding>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            bindings: Action::ALL
+                .into_iter()
+                .map(|action| (action, action.default_binding()))
+                .collect(),
+        }
+    }
+}
+
+impl KeyBindings {
+    /// Falls back to the default bindings when the file is missing or invalid.
+    pub fn load(path: &Path) -> Self {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => Self::from_toml(&contents).unwrap_or_else(|error| {
+                warn!("Could not parse {}: {}", path.display(), error);
+                Self::default()
+            }),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(error) => {
+                warn!("Could not read {}: {}", path.display(), error);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(path, self.to_toml())
+    }
+
+    /// Actions missing from the file keep their default binding.
+    pub fn from_toml(contents: &str) -> Result<Self, String> {
+        let entries: BTreeMap<String, String> =
+            toml::from_str(contents).map_err(|error| error.to_string())?;
+        let mut bindings = Self::default();
+
+        for (name, value) in entries {
+            let action =
+                Action::from_name(&name).ok_or_else(|| format!("unknown action '{}'", name))?;
+            let binding = value.parse()?;
+            bindings
+                .set(action, binding)
+                .map_err(|error| format!("{}: {}", name, error))?;
+        }
+
+        Ok(bindings)
+    }
+
+    pub fn to_toml(&self) -> String {
+        let entries: BTreeMap<&str, String> = Action::ALL
+            .into_iter()
+            .map(|action| (action.name(), self.get(action).to_string()))
+            .collect();
+        toml::to_string(&entries).expect("key bindings are serializable")
+    }
+
+    pub fn get(&self, action: Action) -> Binding {
+        self.bindings
+            .get(&action)
+            .copied()
+            .unwrap_or_else(|| action.default_binding())
+    }
+
+    pub fn set(&mut self, action: Action, binding: Binding) -> Result<(), String> {
+        if action.requires_key() && !matches!(binding, Binding::Key(_)) {
+            return Err(format!("{} must be bound to a key", action.label()));
+        }
+        if !binding.is_supported() {
+            return Err(format!("{} cannot be bound", binding));
+        }
+
+        self.bindings.insert(action, binding);
+        Ok(())
+    }
+
+    /// Other actions bound to the same input as the given one.
+    pub fn conflicts(&self, action: Action) -> Vec<Action> {
+        let binding = self.get(action);
+
+        Action::ALL
+            .into_iter()
+            .filter(|other| *other != action && self.get(*other) == binding)
+            .collect()
+    }
+}
+
+/// Reads the state of actions through the current key bindings.
+#[derive(SystemParam)]
+pub struct ActionInput<'w> {
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    mouse: Res<'w, ButtonInput<MouseButton>>,
+    bindings: Res<'w, KeyBindings>,
+}
+
+impl ActionInput<'_> {
+    pub fn pressed(&self, action: Action) -> bool {
+        match self.bindings.get(action) {
+            Binding::Key(key) => self.keys.pressed(key),
+            Binding::Mouse(button) => self.mouse.pressed(button),
+        }
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        match self.bindings.get(action) {
+            Binding::Key(key) => self.keys.just_pressed(key),
+            Binding::Mouse(button) => self.mouse.just_pressed(button),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bindings_round_trip_through_toml() {
+        let mut bindings = KeyBindings::default();
+        bindings
+            .set(Action::OpenChat, Binding::Key(KeyCode::Enter))
+            .unwrap();
+        bindings
+            .set(Action::BreakBlock, Binding::Mouse(MouseButton::Middle))
+            .unwrap();
+
+        assert_eq!(KeyBindings::from_toml(&bindings.to_toml()), Ok(bindings));
+    }
+
+    #[test]
+    fn test_from_toml_keeps_defaults_for_missing_actions() {
+        let bindings = KeyBindings::from_toml("open_chat = \"KeyY\"\n").unwrap();
+
+        assert_eq!(bindings.get(Action::OpenChat), Binding::Key(KeyCode::KeyY));
+        assert_eq!(bindings.get(Action::Jump), Action::Jump.default_binding());
+    }
+
+    #[test]
+    fn test_from_toml_rejects_unknown_entries() {
+        assert!(KeyBindings::from_toml("open_inventory = \"KeyE\"\n").is_err());
+        assert!(KeyBindings::from_toml("open_chat = \"NotAKey\"\n").is_err());
+        assert!(KeyBindings::from_toml("jump = \"MouseLeft\"\n").is_err());
+    }
+
+    #[test]
+    fn test_default_bindings_do_not_conflict() {
+        let bindings = KeyBindings::default();
+
+        for action in Action::ALL {
+            assert!(bindings.conflicts(action).is_empty(), "{:?}", action);
+        }
+    }
+
+    #[test]
+    fn test_conflicts_list_actions_sharing_a_binding() {
+        let mut bindings = KeyBindings::default();
+        bindings
+            .set(Action::OpenChat, Binding::Key(KeyCode::Space))
+            .unwrap();
+
+        assert_eq!(bindings.conflicts(Action::OpenChat), vec![Action::Jump]);
+        assert_eq!(bindings.conflicts(Action::Jump), vec![Action::OpenChat]);
+    }
+
+    #[test]
+    fn test_action_names_are_unique() {
+        for action in Action::ALL {
+            assert_eq!(Action::from_name(action.name()), Some(action));
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use std::path::Path;
+
+use crate::prelude::*;
+
+pub fn save_key_bindings_system(bindings: Res<actions_resources::KeyBindings>) {
+    if !bindings.is_changed() || bindings.is_added() {
+        return;
+    }
+
+    let path = Path::new(actions_resources::KEY_BINDINGS_PATH);
+    if let Err(error) = bindings.save(path) {
+        error!("Could not save {}: {}", path.display(), error);
+    }
+}
+
+/// Hands the movement bindings to the fps controller, which reads the keyboard itself.
+pub fn apply_movement_bindings_system(
+    bindings: Res<actions_resources::KeyBindings>,
+    mut controllers: Query<(&mut FpsController, Ref<player_components::Player>)>,
+) {
+    let key = |action: Action| match bindings.get(action) {
+        actions_resources::Binding::Key(key) => key,
+        actions_resources::Binding::Mouse(_) => unreachable!("movement is bound to keys"),
+    };
+
+    for (mut controller, player) in controllers.iter_mut() {
+        if !bindings.is_changed() && !player.is_added() {
+            continue;
+        }
+
+        controller.key_forward = key(Action::MoveForward);
+        controller.key_back = key(Action::MoveBack);
+        controller.key_left = key(Action::MoveLeft);
+        controller.key_right = key(Action::MoveRight);
+        controller.key_jump = key(Action::Jump);
+        controller.key_sprint = key(Action::Sprint);
+        controller.key_crouch = key(Action::Crouch);
+        controller.key_up = key(Action::FlyUp);
+        controller.key_down = key(Action::FlyDown);
+    }
+}
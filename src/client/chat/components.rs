@@ -8,3 +8,7 @@ pub struct ChatMessageInputElement;
 
 #[derive(Component)]
 pub struct ChatMessageElement;
+
+/// Seconds since startup when the message was received, used to fade it out.
+#[derive(Component)]
+pub struct ChatMessageReceived(pub f32);
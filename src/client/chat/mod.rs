@@ -18,12 +18,15 @@ impl Plugin for ChatPlugin {
                 systems::handle_chat_message_sync_event,
                 systems::add_message_to_chat_container_system,
                 systems::chat_state_transition_system,
+                systems::update_chat_input_text_system,
+                systems::fade_chat_messages_system,
             ),
         );
         app.add_systems(
             Update,
             (
                 systems::process_chat_input_system,
+                systems::scroll_chat_system,
                 systems::send_messages_system,
                 systems::handle_chat_clear_events_system,
             )
@@ -32,9 +35,11 @@ impl Plugin for ChatPlugin {
 
         app.add_systems(OnEnter(GameState::Chatting), systems::focus_chat_system);
         app.add_systems(OnExit(GameState::Chatting), systems::unfocus_chat_system);
+        app.add_systems(OnEnter(GameState::MainMenu), systems::reset_chat_system);
 
         app.insert_resource(resources::ChatHistory::default());
         app.insert_resource(resources::ChatState::default());
+        app.insert_resource(resources::ChatInput::default());
 
         app.add_event::<events::ChatSyncEvent>();
         app.add_event::<events::ChatMessageSendEvent>();
@@ -1,5 +1,8 @@
 use crate::prelude::*;
 
+/// Number of sent messages that can be recalled with the arrow keys.
+pub const MAX_SENT_HISTORY: usize = 50;
+
 #[derive(Resource, Default, Debug)]
 pub struct ChatHistory {
     pub messages: Vec<ChatMessage>,
@@ -9,3 +12,203 @@ pub struct ChatHistory {
 pub struct ChatState {
     pub just_focused: bool,
 }
+
+/// Text being typed into the chat with the caret position and previously sent messages.
+#[derive(Resource, Default, Debug)]
+pub struct ChatInput {
+    characters: Vec<char>,
+    /// Index of the character the caret is in front of.
+    caret: usize,
+    sent: Vec<String>,
+    /// Index into `sent` while recalling, with the unfinished message to return to.
+    recall: Option<(usize, String)>,
+}
+
+impl ChatInput {
+    pub fn text(&self) -> String {
+        self.characters.iter().collect()
+    }
+
+    /// The text with `caret` inserted at the caret position.
+    pub fn text_with_caret(&self, caret: char) -> String {
+        let mut characters = self.characters.clone();
+        characters.insert(self.caret, caret);
+        characters.into_iter().collect()
+    }
+
+    /// Inserts at the caret, dropping characters past `MAX_CHAT_MESSAGE_LENGTH`. Tabs and line
+    /// breaks of pasted text become spaces.
+    pub fn insert(&mut self, input: &str) {
+        let characters = input
+            .chars()
+            .map(|character| match character.is_whitespace() {
+                true => ' ',
+                false => character,
+            })
+            .filter(|character| !character.is_control());
+
+        for character in characters {
+            if self.characters.len() >= MAX_CHAT_MESSAGE_LENGTH {
+                break;
+            }
+            self.characters.insert(self.caret, character);
+            self.caret += 1;
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        if self.caret > 0 {
+            self.caret -= 1;
+            self.characters.remove(self.caret);
+        }
+    }
+
+    pub fn delete(&mut self) {
+        if self.caret < self.characters.len() {
+            self.characters.remove(self.caret);
+        }
+    }
+
+    pub fn move_left(&mut self) {
+        self.caret = self.caret.saturating_sub(1);
+    }
+
+    pub fn move_right(&mut self) {
+        self.caret = (self.caret + 1).min(self.characters.len());
+    }
+
+    pub fn move_home(&mut self) {
+        self.caret = 0;
+    }
+
+    pub fn move_end(&mut self) {
+        self.caret = self.characters.len();
+    }
+
+    /// Replaces the text with the previous sent message.
+    pub fn recall_previous(&mut self) {
+        let index = match &self.recall {
+            Some((index, _)) => index.saturating_sub(1),
+            None if self.sent.is_empty() => return,
+            None => {
+                self.recall = Some((self.sent.len(), self.text()));
+                self.sent.len() - 1
+            }
+        };
+
+        if let Some((recalled, _)) = &mut self.recall {
+            *recalled = index;
+        }
+        let message = self.sent[index].clone();
+        self.set_text(&message);
+    }
+
+    /// Replaces the text with the next sent message, or the unfinished one after the last.
+    pub fn recall_next(&mut self) {
+        let Some((index, draft)) = self.recall.take() else {
+            return;
+        };
+
+        match self.sent.get(index + 1) {
+            Some(message) => {
+                let message = message.clone();
+                self.recall = Some((index + 1, draft));
+                self.set_text(&message);
+            }
+            None => self.set_text(&draft),
+        }
+    }
+
+    /// Clears the input and returns the trimmed text, remembering it for recall.
+    pub fn submit(&mut self) -> Option<String> {
+        let message = self.text().trim().to_string();
+        self.set_text("");
+        self.recall = None;
+
+        if message.is_empty() {
+            return None;
+        }
+
+        if self.sent.last() != Some(&message) {
+            self.sent.push(message.clone());
+        }
+        if self.sent.len() > MAX_SENT_HISTORY {
+            self.sent.remove(0);
+        }
+
+        Some(message)
+    }
+
+    /// Drops the typed text and sent messages.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    fn set_text(&mut self, text: &str) {
+        self.characters = text.chars().collect();
+        self.caret = self.characters.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_caret_editing() {
+        let mut input = ChatInput::default();
+        input.insert("helo");
+        input.move_left();
+        input.insert("l");
+        assert_eq!(input.text_with_caret('|'), "hell|o");
+
+        input.move_home();
+        input.delete();
+        input.move_end();
+        input.backspace();
+        assert_eq!(input.text_with_caret('|'), "ell|");
+
+        input.move_right();
+        input.move_home();
+        input.move_left();
+        assert_eq!(input.text_with_caret('|'), "|ell");
+    }
+
+    #[test]
+    fn test_insert_stops_at_max_length() {
+        let mut input = ChatInput::default();
+        input.insert(&"a".repeat(MAX_CHAT_MESSAGE_LENGTH + 5));
+        assert_eq!(input.text().len(), MAX_CHAT_MESSAGE_LENGTH);
+    }
+
+    #[test]
+    fn test_insert_replaces_line_breaks() {
+        let mut input = ChatInput::default();
+        input.insert("one\ttwo\r\nthree\u{7}");
+        assert_eq!(input.text(), "one two  three");
+    }
+
+    #[test]
+    fn test_recall_sent_messages() {
+        let mut input = ChatInput::default();
+        for message in ["first", "second"] {
+            input.insert(message);
+            assert_eq!(input.submit(), Some(message.to_string()));
+        }
+        assert_eq!(input.submit(), None);
+
+        input.insert("draft");
+        input.recall_previous();
+        assert_eq!(input.text(), "second");
+        input.recall_previous();
+        input.recall_previous();
+        assert_eq!(input.text(), "first");
+
+        input.recall_next();
+        assert_eq!(input.text(), "second");
+        input.recall_next();
+        assert_eq!(input.text(), "draft");
+        input.recall_next();
+        assert_eq!(input.text(), "draft");
+    }
+}
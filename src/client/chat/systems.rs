@@ -4,7 +4,14 @@ use bevy_flair::style::components::{ClassList, NodeStyleSheet};
 use chat_events::ChatMessageSendEvent;
 
 const MESSAGE_PROMPT: &str = "> ";
-const MAX_MESSAGE_LENGTH: usize = 42;
+const CARET: char = '|';
+/// Messages kept in the chat box, older ones are removed.
+const MAX_SCROLLBACK: usize = 100;
+/// How long a message stays fully visible while the chat is not focused.
+const FADE_DELAY: f32 = 10.0;
+const FADE_DURATION: f32 = 1.0;
+const SCROLL_LINE_HEIGHT: f32 = 20.0;
+const SCROLL_PAGE_HEIGHT: f32 = 200.0;
 
 pub fn setup_chat_container(mut commands: Commands, asset_server: Res<AssetServer>) {
     commands
@@ -49,6 +56,7 @@ pub fn send_messages_system(
 }
 
 pub fn chat_state_transition_system(
+    actions: ActionInput,
     keyboard_input: Res<ButtonInput<KeyCode>>,
     current_state: Res<State<GameState>>,
     mut next_state: ResMut<NextState<GameState>>,
@@ -57,84 +65,166 @@ pub fn chat_state_transition_system(
     let current_state_value = current_state.get();
     let mut next_state_value = current_state_value.clone();
 
-    if keyboard_input.just_pressed(KeyCode::KeyT) {
-        info!("Focusing chat via KeyT");
+    if actions.just_pressed(Action::OpenChat) {
+        info!("Focusing chat");
         if *current_state_value == GameState::Playing {
             chat_state.just_focused = true;
             next_state_value = GameState::Chatting;
         }
     }
+    // Escape stays fixed while typing so that any character can be bound to an action
     if keyboard_input.just_pressed(KeyCode::Escape) && *current_state_value == GameState::Chatting {
         info!("Unfocusing chat via Escape");
         next_state_value = GameState::Playing;
     }
 
-    next_state.set(next_state_value);
+    if next_state_value != *current_state_value {
+        next_state.set(next_state_value);
+    }
 }
 
 pub fn process_chat_input_system(
     mut evr_kbd: EventReader<KeyboardInput>,
-    mut chat_input_query: Query<&mut Text, With<chat_components::ChatMessageInputElement>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut chat_input: ResMut<chat_resources::ChatInput>,
     mut send_event_writer: EventWriter<ChatMessageSendEvent>,
     mut chat_state: ResMut<chat_resources::ChatState>,
     mut chat_clear_writer: EventWriter<chat_events::ChatClearEvent>,
 ) {
-    if let Ok(mut text) = chat_input_query.get_single_mut() {
-        let mut chat_input_value = text.0.clone();
-
-        for event in evr_kbd.read() {
-            if event.state != ButtonState::Pressed {
-                continue;
-            }
-
-            if chat_state.just_focused {
-                // Hack to prevent 'T' from being added to the chat input upon focus
-                chat_state.just_focused = false;
-                continue;
-            }
-
-            info!("Chat state: {}", chat_input_value);
+    for event in evr_kbd.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
 
-            let mut message = extract_message(&chat_input_value);
+        if chat_state.just_focused {
+            // Hack to prevent 'T' from being added to the chat input upon focus
+            chat_state.just_focused = false;
+            continue;
+        }
 
-            match &event.logical_key {
-                Key::Enter if !message.trim().is_empty() => {
-                    if message.trim() == "CLEAR" {
-                        chat_clear_writer.send(chat_events::ChatClearEvent);
-                    } else {
-                        send_event_writer.send(ChatMessageSendEvent(message.trim().to_string()));
-                    }
-                    message.clear();
-                }
-                Key::Backspace => {
-                    message.pop();
+        match &event.logical_key {
+            Key::Enter => match chat_input.submit() {
+                Some(message) if message == "CLEAR" => {
+                    chat_clear_writer.send(chat_events::ChatClearEvent);
                 }
-                Key::Space => {
-                    if message.len() < MAX_MESSAGE_LENGTH {
-                        message.push(' ');
-                    }
+                Some(message) => {
+                    send_event_writer.send(ChatMessageSendEvent(message));
                 }
-                Key::Character(input) => {
-                    if message.len() < MAX_MESSAGE_LENGTH && input.chars().all(|c| !c.is_control())
-                    {
-                        message.push_str(input);
-                    }
+                None => {}
+            },
+            Key::Backspace => chat_input.backspace(),
+            Key::Delete => chat_input.delete(),
+            Key::ArrowLeft => chat_input.move_left(),
+            Key::ArrowRight => chat_input.move_right(),
+            Key::Home => chat_input.move_home(),
+            Key::End => chat_input.move_end(),
+            Key::ArrowUp => chat_input.recall_previous(),
+            Key::ArrowDown => chat_input.recall_next(),
+            Key::Space => chat_input.insert(" "),
+            Key::Character(input)
+                if input.eq_ignore_ascii_case("v")
+                    && is_paste_modifier_pressed(&keyboard_input) =>
+            {
+                if let Some(text) = read_clipboard() {
+                    chat_input.insert(&text);
                 }
-                _ => {}
             }
+            Key::Character(input) => chat_input.insert(input),
+            _ => {}
+        }
+    }
+}
 
-            chat_input_value = MESSAGE_PROMPT.to_string() + &message;
+/// Control on most platforms, Command on macOS.
+fn is_paste_modifier_pressed(keyboard_input: &ButtonInput<KeyCode>) -> bool {
+    keyboard_input.any_pressed([
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+        KeyCode::SuperLeft,
+        KeyCode::SuperRight,
+    ])
+}
+
+fn read_clipboard() -> Option<String> {
+    match arboard::Clipboard::new().and_then(|mut clipboard| clipboard.get_text()) {
+        Ok(text) => Some(text),
+        Err(error) => {
+            warn!("Could not read the clipboard: {}", error);
+            None
         }
+    }
+}
+
+pub fn update_chat_input_text_system(
+    chat_input: Res<chat_resources::ChatInput>,
+    current_state: Res<State<GameState>>,
+    mut chat_input_query: Query<&mut Text, With<chat_components::ChatMessageInputElement>>,
+) {
+    if !chat_input.is_changed() && !current_state.is_changed() {
+        return;
+    }
 
-        text.clear();
+    if let Ok(mut text) = chat_input_query.get_single_mut() {
+        let message = match *current_state.get() {
+            GameState::Chatting => chat_input.text_with_caret(CARET),
+            _ => chat_input.text(),
+        };
+
+        text.0 = MESSAGE_PROMPT.to_string() + &message;
+    }
+}
+
+pub fn scroll_chat_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+    mut query: Query<&mut ScrollPosition, With<chat_components::ChatMessageContainer>>,
+) {
+    let mut offset: f32 = mouse_wheel_events
+        .read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => -event.y * SCROLL_LINE_HEIGHT,
+            MouseScrollUnit::Pixel => -event.y,
+        })
+        .sum();
+
+    if keyboard_input.just_pressed(KeyCode::PageUp) {
+        offset -= SCROLL_PAGE_HEIGHT;
+    }
+    if keyboard_input.just_pressed(KeyCode::PageDown) {
+        offset += SCROLL_PAGE_HEIGHT;
+    }
+
+    if offset == 0.0 {
+        return;
+    }
+
+    if let Ok(mut scroll_position) = query.get_single_mut() {
+        scroll_position.offset_y = (scroll_position.offset_y + offset).max(0.0);
+    }
+}
+
+pub fn fade_chat_messages_system(
+    time: Res<Time>,
+    current_state: Res<State<GameState>>,
+    mut query: Query<(&chat_components::ChatMessageReceived, &mut TextColor)>,
+) {
+    let focused = *current_state.get() == GameState::Chatting;
+
+    for (received, mut color) in query.iter_mut() {
+        let alpha = match focused {
+            true => 1.0,
+            false => message_alpha(time.elapsed_secs() - received.0),
+        };
 
-        text.0 += &chat_input_value;
+        if color.0.alpha() != alpha {
+            color.0.set_alpha(alpha);
+        }
     }
 }
 
-fn extract_message(value: &str) -> String {
-    let message = value.trim_start_matches(MESSAGE_PROMPT);
-    message.to_string()
+/// Opacity of a message that was received `age` seconds ago while the chat is not focused.
+fn message_alpha(age: f32) -> f32 {
+    1.0 - ((age - FADE_DELAY) / FADE_DURATION).clamp(0.0, 1.0)
 }
 
 pub fn handle_chat_message_sync_event(
@@ -148,30 +238,75 @@ pub fn handle_chat_message_sync_event(
     }
 }
 
+/// Looks up the names of message senders.
+#[derive(SystemParam)]
+pub struct ChatSenderNames<'w> {
+    player_list: Res<'w, player_list_resources::PlayerList>,
+    remote_player_profiles: Res<'w, remote_player_resources::RemotePlayerProfiles>,
+}
+
+impl ChatSenderNames<'_> {
+    pub fn name(&self, client_id: ClientId) -> String {
+        if client_id == SERVER_MESSAGE_ID {
+            return "SERVER".to_string();
+        }
+
+        match self
+            .player_list
+            .entries
+            .iter()
+            .find(|entry| entry.client_id == client_id)
+        {
+            Some(entry) => entry.name.clone(),
+            None => self.remote_player_profiles.name(client_id),
+        }
+    }
+}
+
 pub fn add_message_to_chat_container_system(
     mut commands: Commands,
+    time: Res<Time>,
+    sender_names: ChatSenderNames,
     mut query: Query<(
         Entity,
         &chat_components::ChatMessageContainer,
         &mut ScrollPosition,
+        Option<&Children>,
     )>,
     mut events: EventReader<chat_events::SingleChatSendEvent>,
 ) {
-    for event in events.read() {
-        if let Ok((entity, _, mut scroll_position)) = query.get_single_mut() {
-            // Offset does not need to be exact, just needs to be large enough to see the new message
-            scroll_position.offset_y += 100.0;
+    let messages: Vec<&ChatMessage> = events.read().map(|event| &event.0).collect();
+    if messages.is_empty() {
+        return;
+    }
 
-            commands.entity(entity).with_children(|parent| {
-                parent.spawn((
-                    Node::default(),
-                    Name::new("chat_entry"),
-                    chat_components::ChatMessageElement,
-                    Text::new(event.0.message.clone()),
-                ));
-            });
+    let Ok((entity, _, mut scroll_position, children)) = query.get_single_mut() else {
+        return;
+    };
+
+    // Offset does not need to be exact, just needs to be large enough to see the new message
+    scroll_position.offset_y += 100.0 * messages.len() as f32;
+
+    let existing = children.map_or(0, |children| children.len());
+    let overflow = (existing + messages.len()).saturating_sub(MAX_SCROLLBACK);
+    if let Some(children) = children {
+        for child in children.iter().take(overflow) {
+            commands.entity(*child).despawn_recursive();
         }
     }
+
+    let skipped = messages.len().saturating_sub(MAX_SCROLLBACK);
+    commands.entity(entity).with_children(|parent| {
+        for message in messages.into_iter().skip(skipped) {
+            parent.spawn((
+                Node::default(),
+                Name::new("chat_entry"),
+                chat_components::ChatMessageElement,
+                chat_components::ChatMessageReceived(time.elapsed_secs()),
+                Text::new(message.format_string(&sender_names.name(message.client_id))),
+            ));
+        }
+    });
 }
 
 pub fn handle_chat_clear_events_system(
@@ -186,9 +321,23 @@ pub fn handle_chat_clear_events_system(
     }
 }
 
+/// Forgets the chat of the server that was left.
+pub fn reset_chat_system(
+    mut commands: Commands,
+    mut chat_history: ResMut<chat_resources::ChatHistory>,
+    mut chat_input: ResMut<chat_resources::ChatInput>,
+    query: Query<Entity, With<chat_components::ChatMessageContainer>>,
+) {
+    chat_history.messages.clear();
+    chat_input.clear();
+    if let Ok(entity) = query.get_single() {
+        commands.entity(entity).despawn_descendants();
+    }
+}
+
 pub fn unfocus_chat_system(
     mut chat_container_query: Query<
-        &mut ClassList,
+        (&mut ClassList, &mut ScrollPosition),
         (
             With<chat_components::ChatMessageContainer>,
             Without<chat_components::ChatMessageInputElement>,
@@ -202,11 +351,14 @@ pub fn unfocus_chat_system(
         ),
     >,
 ) {
-    if let (Ok(mut container_classes), Ok(mut input_classes)) = (
+    if let (Ok((mut container_classes, mut scroll_position)), Ok(mut input_classes)) = (
         chat_container_query.get_single_mut(),
         chat_input_query.get_single_mut(),
     ) {
         info!("Handling unfocus state");
+        // Jump back to the newest messages, the layout clamps the offset
+        scroll_position.offset_y = f32::MAX;
+
         container_classes.remove_class("focused");
         container_classes.add_class("unfocused");
 
@@ -262,19 +414,27 @@ mod tests {
             .collect()
     }
 
-    #[test]
-    fn test_send_message_system() {
+    fn setup_chat_container_app() -> App {
         let mut app = App::new();
 
         app.add_plugins(MinimalPlugins)
             .add_systems(Update, add_message_to_chat_container_system)
-            .insert_resource(Events::<SingleChatSendEvent>::default());
+            .insert_resource(Events::<SingleChatSendEvent>::default())
+            .init_resource::<player_list_resources::PlayerList>()
+            .init_resource::<remote_player_resources::RemotePlayerProfiles>();
 
         app.world_mut().spawn((
             ScrollPosition::default(),
             chat_components::ChatMessageContainer,
         ));
 
+        app
+    }
+
+    #[test]
+    fn test_send_message_system() {
+        let mut app = setup_chat_container_app();
+
         let mut event_writer = app
             .world_mut()
             .get_resource_mut::<Events<SingleChatSendEvent>>()
@@ -295,10 +455,50 @@ mod tests {
 
         let message_count = messages.iter(app.world()).count();
         assert_eq!(message_count, 1);
-        assert_eq!(
-            messages.iter(app.world()).next().unwrap().0 .0,
-            "Hello World"
-        );
+        assert!(messages
+            .iter(app.world())
+            .next()
+            .unwrap()
+            .0
+             .0
+            .ends_with("SERVER: Hello World"));
+    }
+
+    #[test]
+    fn test_scrollback_is_limited() {
+        let mut app = setup_chat_container_app();
+
+        for batch in 0..3 {
+            let mut event_writer = app
+                .world_mut()
+                .get_resource_mut::<Events<SingleChatSendEvent>>()
+                .unwrap();
+
+            for index in 0..MAX_SCROLLBACK / 2 + 1 {
+                event_writer.send(SingleChatSendEvent(ChatMessage {
+                    message: format!("{} {}", batch, index),
+                    client_id: 0,
+                    message_id: index,
+                    timestamp: 0,
+                }));
+            }
+
+            app.update();
+        }
+
+        let messages = get_chat_messages(&mut app);
+        assert_eq!(messages.len(), MAX_SCROLLBACK);
+        assert!(!messages.iter().any(|message| message.ends_with(": 0 0")));
+        let newest = format!(": 2 {}", MAX_SCROLLBACK / 2);
+        assert!(messages.iter().any(|message| message.ends_with(&newest)));
+    }
+
+    #[test]
+    fn test_message_alpha() {
+        assert_eq!(message_alpha(0.0), 1.0);
+        assert_eq!(message_alpha(FADE_DELAY), 1.0);
+        assert_eq!(message_alpha(FADE_DELAY + FADE_DURATION / 2.0), 0.5);
+        assert_eq!(message_alpha(FADE_DELAY + FADE_DURATION * 2.0), 0.0);
     }
 
     #[test]
@@ -1,6 +0,0 @@
-use crate::prelude::*;
-
-#[derive(Component)]
-pub struct BlockCollider {
-    pub relative_position: Vec3,
-}
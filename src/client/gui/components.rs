@@ -1 +1,7 @@
+use crate::prelude::*;
 
+#[derive(Component)]
+pub struct DebugOverlay;
+
+#[derive(Component)]
+pub struct DebugOverlayText;
@@ -11,7 +11,21 @@ pub struct GuiPlugin;
 
 impl Plugin for GuiPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, gui_systems::setup_gui_system);
+        app.add_systems(
+            Startup,
+            (
+                gui_systems::setup_gui_system,
+                gui_systems::setup_debug_overlay_system,
+            ),
+        );
+        app.add_systems(
+            Update,
+            (
+                gui_systems::toggle_debug_overlay_system,
+                gui_systems::update_debug_overlay_system,
+            )
+                .chain(),
+        );
 
         #[cfg(feature = "debug_ui")]
         {
@@ -24,13 +24,14 @@ pub fn setup_gui_system(mut commands: Commands, asset_server: Res<AssetServer>)
 pub fn handle_debug_state_transition_system(
     current_state: Res<State<GameState>>,
     mut next_state: ResMut<NextState<GameState>>,
-    key_input: Res<ButtonInput<KeyCode>>,
+    actions: ActionInput,
 ) {
-    if key_input.just_pressed(KeyCode::Tab) {
+    if actions.just_pressed(Action::ToggleDebug) {
         match *current_state.get() {
             GameState::Playing => next_state.set(GameState::Debugging),
             GameState::Chatting => next_state.set(GameState::Debugging),
             GameState::Debugging => next_state.set(GameState::Playing),
+            GameState::Connecting | GameState::Dead | GameState::MainMenu | GameState::Paused => {}
         }
     }
 }
@@ -41,3 +42,200 @@ pub fn handle_enter_debug_state_system(mut window_query: Query<&mut Window>) {
         window.cursor_options.visible = true;
     }
 }
+
+/// How often the debug overlay text is rebuilt while it is visible.
+const DEBUG_OVERLAY_INTERVAL: Duration = Duration::from_millis(250);
+
+pub fn setup_debug_overlay_system(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn((
+            Node::default(),
+            Name::new("debug_overlay"),
+            NodeStyleSheet::new(asset_server.load("gui.css")),
+            Visibility::Hidden,
+            gui_components::DebugOverlay,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(""),
+                Name::new("debug_overlay_text"),
+                gui_components::DebugOverlayText,
+            ));
+        });
+}
+
+pub fn toggle_debug_overlay_system(
+    actions: ActionInput,
+    mut overlay_query: Query<&mut Visibility, With<gui_components::DebugOverlay>>,
+) {
+    if !actions.just_pressed(Action::ToggleDebugOverlay) {
+        return;
+    }
+
+    for mut visibility in overlay_query.iter_mut() {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        };
+    }
+}
+
+#[derive(SystemParam)]
+pub struct DebugOverlaySources<'w, 's> {
+    chunk_manager: Res<'w, ChunkManager>,
+    mesher_tasks: Res<'w, terrain_resources::MesherTasks>,
+    meshes: Res<'w, Assets<Mesh>>,
+    chunk_meshes: Query<'w, 's, &'static Mesh3d, With<terrain_components::ChunkMesh>>,
+    block_selection: Res<'w, player_resources::BlockSelection>,
+    players: Query<'w, 's, &'static Transform, With<player_components::Player>>,
+    cameras: Query<'w, 's, &'static GlobalTransform, With<player_components::PlayerCamera>>,
+    client: Res<'w, RenetClient>,
+    server_diagnostics: Res<'w, networking_resources::LatestServerDiagnostics>,
+}
+
+pub fn update_debug_overlay_system(
+    sources: DebugOverlaySources,
+    overlay_query: Query<&Visibility, With<gui_components::DebugOverlay>>,
+    mut text_query: Query<&mut Text, With<gui_components::DebugOverlayText>>,
+    mut last_update: Local<Option<Instant>>,
+) {
+    if overlay_query
+        .get_single()
+        .map_or(true, |visibility| *visibility == Visibility::Hidden)
+    {
+        return;
+    }
+
+    if last_update.is_some_and(|last_update| last_update.elapsed() < DEBUG_OVERLAY_INTERVAL) {
+        return;
+    }
+    *last_update = Some(Instant::now());
+
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+
+    let mut lines = Vec::new();
+
+    if let Ok(transform) = sources.players.get_single() {
+        let position = transform.translation;
+        let block = position.floor();
+        let chunk = (position / CHUNK_SIZE as f32).floor();
+
+        lines.push(format!(
+            "XYZ: {:.2} / {:.2} / {:.2}",
+            position.x, position.y, position.z
+        ));
+        lines.push(format!("Block: {} {} {}", block.x, block.y, block.z));
+        lines.push(format!("Chunk: {} {} {}", chunk.x, chunk.y, chunk.z));
+    }
+
+    if let Ok(camera) = sources.cameras.get_single() {
+        let forward = camera.forward();
+        let (yaw, pitch, _) = camera.rotation().to_euler(EulerRot::YXZ);
+
+        lines.push(format!(
+            "Facing: {} ({:.1} / {:.1})",
+            facing_direction(*forward),
+            yaw.to_degrees(),
+            pitch.to_degrees()
+        ));
+    }
+
+    lines.push(match sources.block_selection.position {
+        Some(position) => format!(
+            "Target: {} {} {} {:?}",
+            position.x,
+            position.y,
+            position.z,
+            sources.chunk_manager.get_block(position)
+        ),
+        None => String::from("Target: none"),
+    });
+
+    let vertex_count: usize = sources
+        .chunk_meshes
+        .iter()
+        .filter_map(|mesh| sources.meshes.get(&mesh.0))
+        .map(|mesh| mesh.count_vertices())
+        .sum();
+
+    lines.push(String::new());
+    lines.push(format!(
+        "Chunks: {} loaded, {} meshing",
+        sources.chunk_manager.chunks.len(),
+        sources.mesher_tasks.task_list.len()
+    ));
+    lines.push(format!(
+        "Meshes: {} ({} vertices)",
+        sources.chunk_meshes.iter().count(),
+        vertex_count
+    ));
+
+    lines.push(String::new());
+    match sources.client.is_connected() {
+        true => {
+            let network_info = sources.client.network_info();
+            lines.push(format!(
+                "RTT: {:.0} ms, loss {:.1}%",
+                network_info.rtt * 1000.0,
+                network_info.packet_loss * 100.0
+            ));
+            lines.push(format!(
+                "In: {}, out: {}",
+                format_bandwidth(network_info.bytes_received_per_second),
+                format_bandwidth(network_info.bytes_sent_per_second)
+            ));
+        }
+        false => lines.push(String::from("Not connected")),
+    }
+
+    lines.push(match &sources.server_diagnostics.0 {
+        Some(diagnostics) => format!(
+            "Server tick: {:.2} ms avg, {:.2} ms max, {:.1} TPS, {} clients",
+            diagnostics.average_tick_time,
+            diagnostics.max_tick_time,
+            diagnostics.tick_rate,
+            diagnostics.client_count
+        ),
+        None => String::from("Server tick: unknown"),
+    });
+
+    text.0 = lines.join("\n");
+}
+
+/// Compass direction of the horizontal part of `forward`, with north along -Z.
+fn facing_direction(forward: Vec3) -> &'static str {
+    match forward.x.abs() > forward.z.abs() {
+        true if forward.x > 0.0 => "east",
+        true => "west",
+        false if forward.z > 0.0 => "south",
+        false => "north",
+    }
+}
+
+fn format_bandwidth(bytes_per_second: f64) -> String {
+    match bytes_per_second >= 1024.0 {
+        true => format!("{:.1} KB/s", bytes_per_second / 1024.0),
+        false => format!("{:.0} B/s", bytes_per_second),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_facing_direction() {
+        assert_eq!(facing_direction(Vec3::NEG_Z), "north");
+        assert_eq!(facing_direction(Vec3::Z), "south");
+        assert_eq!(facing_direction(Vec3::X), "east");
+        assert_eq!(facing_direction(Vec3::new(-0.8, -0.5, 0.3)), "west");
+    }
+
+    #[test]
+    fn test_format_bandwidth() {
+        assert_eq!(format_bandwidth(512.0), "512 B/s");
+        assert_eq!(format_bandwidth(2048.0), "2.0 KB/s");
+    }
+}
@@ -0,0 +1,22 @@
+pub mod resources;
+pub mod systems;
+
+use crate::prelude::*;
+
+pub struct ItemsPlugin;
+
+impl Plugin for ItemsPlugin {
+    fn build(&self, app: &mut App) {
+        app.replicate::<DroppedItem>();
+        app.insert_resource(items_resources::ItemDropMeshes::default());
+        app.add_systems(
+            Update,
+            (
+                items_systems::setup_item_drops_system,
+                items_systems::animate_item_drops_system,
+            )
+                .chain()
+                .after(ReplicationSet),
+        );
+    }
+}
@@ -0,0 +1,8 @@
+use crate::prelude::*;
+
+/// Meshes of dropped items, built once per item.
+#[derive(Resource, Default)]
+pub struct ItemDropMeshes {
+    pub meshes: HashMap<ItemId, Handle<Mesh>>,
+    pub tool_material: Option<Handle<StandardMaterial>>,
+}
@@ -0,0 +1,73 @@
+use crate::prelude::*;
+
+const ITEM_DROP_SCALE: f32 = 0.25;
+const ROTATION_SPEED: f32 = 1.5;
+const BOB_HEIGHT: f32 = 0.1;
+const BOB_SPEED: f32 = 2.5;
+/// How quickly drops catch up with the position sent by the server.
+const SMOOTHING: f32 = 12.0;
+
+pub fn setup_item_drops_system(
+    mut commands: Commands,
+    item_drops: Query<(Entity, &DroppedItem, Option<&NetworkPosition>), Added<DroppedItem>>,
+    mut item_drop_meshes: ResMut<items_resources::ItemDropMeshes>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    render_materials: Res<terrain_resources::RenderMaterials>,
+    texture_manager: Res<terrain_util::TextureManager>,
+) {
+    for (entity, dropped_item, position) in item_drops.iter() {
+        let item = dropped_item.item;
+        let mesh = item_drop_meshes
+            .meshes
+            .entry(item)
+            .or_insert_with(|| match item {
+                ItemId::Block(block) => {
+                    let geometry_data = terrain_util::create_cube_geometry_data(
+                        -0.5,
+                        -0.5,
+                        -0.5,
+                        0b111111,
+                        block,
+                        &texture_manager,
+                    );
+                    meshes.add(terrain_util::create_cube_mesh_from_data(geometry_data).unwrap())
+                }
+                ItemId::Tool(_, _) => meshes.add(Cuboid::new(0.25, 1.0, 0.25)),
+            })
+            .clone();
+
+        let material = match item {
+            ItemId::Block(_) => render_materials.chunk_material.clone().unwrap(),
+            ItemId::Tool(_, _) => item_drop_meshes
+                .tool_material
+                .get_or_insert_with(|| materials.add(Color::srgb(0.55, 0.4, 0.25)))
+                .clone(),
+        };
+
+        let translation = position.map(|position| position.0).unwrap_or_default();
+        commands.entity(entity).insert((
+            Mesh3d(mesh),
+            MeshMaterial3d(material),
+            Transform::from_translation(translation).with_scale(Vec3::splat(ITEM_DROP_SCALE)),
+        ));
+    }
+}
+
+pub fn animate_item_drops_system(
+    mut item_drops: Query<(&NetworkId, &NetworkPosition, &mut Transform), With<DroppedItem>>,
+    time: Res<Time>,
+) {
+    let elapsed = time.elapsed_secs();
+    let factor = 1.0 - (-SMOOTHING * time.delta_secs()).exp();
+
+    for (id, position, mut transform) in item_drops.iter_mut() {
+        // The server position is the bottom of the drop, lift the cube so it rests on the ground
+        let phase = elapsed * BOB_SPEED + id.0 as f32;
+        let target =
+            position.0 + Vec3::Y * (ITEM_DROP_SCALE / 2.0 + BOB_HEIGHT * (1.0 + phase.sin()));
+
+        transform.translation = transform.translation.lerp(target, factor);
+        transform.rotation = Quat::from_rotation_y(elapsed * ROTATION_SPEED + id.0 as f32);
+    }
+}
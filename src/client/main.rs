@@ -2,17 +2,24 @@ use crate::prelude::*;
 
 pub mod prelude;
 
+mod actions;
 mod chat;
-mod collider;
 mod gui;
+mod items;
+mod menu;
+mod mobs;
 mod networking;
 mod player;
+mod player_list;
 mod remote_player;
+mod replication;
 mod scene;
+mod settings;
 mod states;
 mod terrain;
 
 use bevy_flair::FlairPlugin;
+use lib::server::settings::{Operator, ServerSettings};
 use scene::setup_scene;
 
 #[cfg(feature = "wireframe")]
@@ -33,10 +40,28 @@ mod wireframe_config {
 }
 
 fn main() {
+    let launch_options =
+        match networking_resources::LaunchOptions::from_args(std::env::args().skip(1)) {
+            Ok(launch_options) => launch_options,
+            Err(error) => {
+                eprintln!("Invalid arguments: {}", error);
+                eprintln!("{}", networking_resources::USAGE);
+                std::process::exit(1);
+            }
+        };
+
+    let client_settings = settings_resources::ClientSettings::load(std::path::Path::new(
+        settings_resources::CLIENT_SETTINGS_PATH,
+    ));
+
     let window_plugin = WindowPlugin {
         primary_window: Some(Window {
-            resolution: WindowResolution::new(1920.0, 1080.0).with_scale_factor_override(2.0),
-            present_mode: bevy::window::PresentMode::Immediate,
+            resolution: WindowResolution::new(
+                client_settings.window_width,
+                client_settings.window_height,
+            )
+            .with_scale_factor_override(client_settings.scale_factor),
+            present_mode: client_settings.present_mode(),
             ..default()
         }),
         ..default()
@@ -47,6 +72,37 @@ fn main() {
         .set(ImagePlugin::default_nearest());
 
     let mut app = App::new();
+    // Launching straight into a world skips the main menu
+    let initial_state = match launch_options.server_address {
+        Some(_) => GameState::Connecting,
+        None if launch_options.singleplayer_world.is_some() => GameState::Connecting,
+        None => GameState::MainMenu,
+    };
+    if let Some(server_address) = launch_options.server_address {
+        app.insert_resource(server_address);
+    }
+    let identity = networking_resources::LocalPlayerIdentity::load(std::path::Path::new(
+        networking_resources::PLAYER_IDENTITY_PATH,
+    ));
+    // The singleplayer host may use operator commands in its own world
+    let operator = Operator {
+        name: launch_options.profile.0.clone().sanitized().name,
+        identity: identity.0,
+    };
+    app.insert_resource(launch_options.profile);
+    app.insert_resource(identity);
+    app.insert_resource(client_settings);
+
+    let integrated_server = launch_options.singleplayer_world.map(|world_directory| {
+        let (server, connection) = lib::server::IntegratedServer::start(ServerSettings {
+            world_directory,
+            operators: vec![operator],
+            ..default()
+        });
+        app.insert_resource(MemoryClientTransport::new(connection));
+        server
+    });
+
     app.add_plugins((
         default_plugins,
         FlairPlugin,
@@ -56,19 +112,33 @@ fn main() {
         EntityCountDiagnosticsPlugin,
         SystemInformationDiagnosticsPlugin,
         PerfUiPlugin,
+    ));
+    app.add_plugins((
         gui::GuiPlugin,
+        actions::ActionsPlugin,
+        settings::SettingsPlugin,
+        menu::MenuPlugin,
         networking::NetworkingPlugin,
+        replication::ReplicationPlugin,
         terrain::TerrainPlugin,
-        collider::ColliderPlugin,
         player::PlayerPlugin,
         remote_player::RemotePlayerPlugin,
+        player_list::PlayerListPlugin,
+        items::ItemsPlugin,
+        mobs::MobsPlugin,
         #[cfg(feature = "chat")]
         chat::ChatPlugin,
     ));
-    app.insert_state(GameState::Playing);
+    app.insert_state(initial_state);
 
     #[cfg(feature = "wireframe")]
     app.insert_resource(wireframe_config::wireframe_config());
 
-    app.add_systems(Startup, setup_scene).run();
+    app.add_systems(Startup, setup_scene);
+    app.run();
+
+    if let Some(server) = integrated_server {
+        info!("Stopping integrated server");
+        server.stop();
+    }
 }
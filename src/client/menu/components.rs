@@ -0,0 +1,92 @@
+use crate::prelude::*;
+
+#[derive(Component)]
+pub struct MainMenu;
+
+/// Parent of the saved server entries, rebuilt when the server list changes.
+#[derive(Component)]
+pub struct ServerListContainer;
+
+/// Connects to the server at this index of the server list.
+#[derive(Component)]
+pub struct JoinServerButton(pub usize);
+
+/// Removes the server at this index from the server list.
+#[derive(Component)]
+pub struct RemoveServerButton(pub usize);
+
+#[derive(Component)]
+pub struct AddressInputText;
+
+#[derive(Component)]
+pub struct ConnectButton;
+
+#[derive(Component)]
+pub struct SaveServerButton;
+
+#[derive(Component)]
+pub struct QuitButton;
+
+#[derive(Component)]
+pub struct ConnectionErrorText;
+
+#[derive(Component)]
+pub struct LoadingScreen;
+
+#[derive(Component)]
+pub struct LoadingStatusText;
+
+#[derive(Component)]
+pub struct LoadingBarFill;
+
+#[derive(Component)]
+pub struct CancelButton;
+
+#[derive(Component)]
+pub struct PauseMenu;
+
+#[derive(Component)]
+pub struct ResumeButton;
+
+/// Returns to the main menu, or quits when playing in singleplayer.
+#[derive(Component)]
+pub struct LeaveButton;
+
+#[derive(Component)]
+pub struct OpenSettingsButton;
+
+#[derive(Component)]
+pub struct SettingsScreen;
+
+#[derive(Component)]
+pub struct SettingValueText(pub settings_resources::Setting);
+
+/// Moves the setting by the given number of steps when pressed.
+#[derive(Component)]
+pub struct AdjustSettingButton {
+    pub setting: settings_resources::Setting,
+    pub steps: i32,
+}
+
+#[derive(Component)]
+pub struct CloseSettingsButton;
+
+#[derive(Component)]
+pub struct OpenKeyBindingsButton;
+
+/// Child of the settings screen listing the binding of every action.
+#[derive(Component)]
+pub struct KeyBindingsScreen;
+
+/// Waits for the next key or mouse button to bind to the action when pressed.
+#[derive(Component)]
+pub struct KeyBindingButton(pub Action);
+
+#[derive(Component)]
+pub struct KeyBindingText(pub Action);
+
+#[derive(Component)]
+pub struct ResetKeyBindingsButton;
+
+#[derive(Component)]
+pub struct CloseKeyBindingsButton;
@@ -0,0 +1,7 @@
+use crate::prelude::*;
+
+/// Request to connect to the server at the typed or saved address.
+#[derive(Event)]
+pub struct ConnectEvent {
+    pub address: String,
+}
@@ -0,0 +1,105 @@
+pub mod components;
+pub mod events;
+pub mod resources;
+pub mod systems;
+
+use std::path::Path;
+
+use crate::prelude::*;
+
+/// Main menu with the saved servers, the loading screen while connecting, the pause menu and
+/// the settings screen opened from both menus.
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(menu_resources::ServerList::load(Path::new(
+            menu_resources::SERVER_LIST_PATH,
+        )));
+        app.insert_resource(menu_resources::DirectConnectInput::default());
+        app.insert_resource(menu_resources::RebindingAction::default());
+        app.add_event::<menu_events::ConnectEvent>();
+
+        app.add_systems(
+            OnEnter(GameState::MainMenu),
+            (
+                menu_systems::setup_main_menu_system,
+                menu_systems::release_cursor_system,
+            ),
+        );
+        app.add_systems(
+            OnExit(GameState::MainMenu),
+            menu_systems::despawn_screen_system::<menu_components::MainMenu>,
+        );
+        app.add_systems(
+            Update,
+            (
+                menu_systems::refresh_server_list_system,
+                menu_systems::handle_server_list_buttons_system,
+                menu_systems::process_address_input_system,
+                menu_systems::update_address_input_system,
+                menu_systems::handle_main_menu_buttons_system,
+                menu_systems::handle_connect_events_system,
+                menu_systems::update_connection_error_system,
+            )
+                .chain()
+                .run_if(in_state(GameState::MainMenu)),
+        );
+
+        app.add_systems(
+            OnEnter(GameState::Connecting),
+            menu_systems::setup_loading_screen_system,
+        );
+        app.add_systems(
+            OnExit(GameState::Connecting),
+            menu_systems::despawn_screen_system::<menu_components::LoadingScreen>,
+        );
+        app.add_systems(
+            Update,
+            (
+                menu_systems::update_loading_screen_system,
+                menu_systems::finish_loading_system,
+                menu_systems::handle_cancel_button_system,
+            )
+                .run_if(in_state(GameState::Connecting)),
+        );
+
+        app.add_systems(Update, menu_systems::pause_state_transition_system);
+        app.add_systems(
+            OnEnter(GameState::Paused),
+            (
+                menu_systems::setup_pause_menu_system,
+                menu_systems::release_cursor_system,
+            ),
+        );
+        app.add_systems(
+            OnExit(GameState::Paused),
+            menu_systems::despawn_screen_system::<menu_components::PauseMenu>,
+        );
+        app.add_systems(
+            Update,
+            menu_systems::handle_pause_menu_buttons_system.run_if(in_state(GameState::Paused)),
+        );
+
+        app.add_systems(
+            Update,
+            (
+                menu_systems::handle_open_settings_button_system,
+                menu_systems::handle_settings_buttons_system,
+                menu_systems::update_setting_values_system,
+                menu_systems::handle_open_key_bindings_button_system,
+                menu_systems::handle_key_binding_buttons_system,
+                menu_systems::capture_key_binding_system,
+                menu_systems::update_key_binding_texts_system,
+            )
+                .chain()
+                .run_if(in_state(GameState::MainMenu).or(in_state(GameState::Paused))),
+        );
+        for state in [GameState::MainMenu, GameState::Paused] {
+            app.add_systems(
+                OnExit(state),
+                menu_systems::despawn_screen_system::<menu_components::SettingsScreen>,
+            );
+        }
+    }
+}
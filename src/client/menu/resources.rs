@@ -0,0 +1,129 @@
+use std::path::Path;
+
+use crate::prelude::*;
+
+pub const SERVER_LIST_PATH: &str = "servers.toml";
+
+/// Longest address that can be typed into the direct connect field.
+pub const MAX_ADDRESS_LENGTH: usize = 64;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ServerEntry {
+    pub name: String,
+    pub address: String,
+}
+
+/// Servers saved in the main menu, stored next to the client.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct ServerList {
+    pub servers: Vec<ServerEntry>,
+}
+
+impl Default for ServerList {
+    fn default() -> Self {
+        Self {
+            servers: vec![ServerEntry {
+                name: "Local server".into(),
+                address: networking_resources::DEFAULT_SERVER_ADDR.into(),
+            }],
+        }
+    }
+}
+
+impl ServerList {
+    /// Falls back to the default list when the file is missing or invalid.
+    pub fn load(path: &Path) -> Self {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => Self::from_toml(&contents).unwrap_or_else(|error| {
+                warn!("Could not parse {}: {}", path.display(), error);
+                Self::default()
+            }),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(error) => {
+                warn!("Could not read {}: {}", path.display(), error);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(path, self.to_toml())
+    }
+
+    pub fn from_toml(contents: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(contents)
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("server list is serializable")
+    }
+
+    /// Saves the address under its own name, returns `false` if it is empty or already saved.
+    pub fn add(&mut self, address: &str) -> bool {
+        let address = address.trim();
+        if address.is_empty() || self.servers.iter().any(|entry| entry.address == address) {
+            return false;
+        }
+
+        self.servers.push(ServerEntry {
+            name: address.into(),
+            address: address.into(),
+        });
+        true
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<ServerEntry> {
+        (index < self.servers.len()).then(|| self.servers.remove(index))
+    }
+}
+
+/// Address typed into the direct connect field.
+#[derive(Resource, Debug, Default)]
+pub struct DirectConnectInput(pub String);
+
+/// Action waiting for the next input in the key binding screen.
+#[derive(Resource, Debug, Default)]
+pub struct RebindingAction(pub Option<Action>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_list_round_trips_through_toml() {
+        let mut list = ServerList::default();
+        list.add("example.com:5000");
+
+        assert_eq!(ServerList::from_toml(&list.to_toml()).unwrap(), list);
+    }
+
+    #[test]
+    fn test_missing_servers_fall_back_to_empty_list() {
+        let list = ServerList::from_toml("").unwrap();
+        assert_eq!(list, ServerList::default());
+
+        let list = ServerList::from_toml("servers = []").unwrap();
+        assert!(list.servers.is_empty());
+    }
+
+    #[test]
+    fn test_add_rejects_duplicate_and_empty_addresses() {
+        let mut list = ServerList { servers: vec![] };
+
+        assert!(list.add(" 10.0.0.1:5000 "));
+        assert!(!list.add("10.0.0.1:5000"));
+        assert!(!list.add("   "));
+        assert_eq!(list.servers.len(), 1);
+        assert_eq!(list.servers[0].name, "10.0.0.1:5000");
+    }
+
+    #[test]
+    fn test_remove_ignores_unknown_index() {
+        let mut list = ServerList::default();
+
+        assert!(list.remove(5).is_none());
+        assert!(list.remove(0).is_some());
+        assert!(list.servers.is_empty());
+    }
+}
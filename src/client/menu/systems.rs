@@ -0,0 +1,749 @@
+use std::path::Path;
+
+use bevy::input::{keyboard::KeyboardInput, ButtonState};
+use bevy_flair::style::components::{ClassList, NodeStyleSheet};
+
+use crate::prelude::*;
+
+const ADDRESS_PLACEHOLDER: &str = "Server address";
+
+fn spawn_button(parent: &mut ChildBuilder, label: &str, marker: impl Bundle) {
+    parent
+        .spawn((Button, Node::default(), Name::new("menu_button"), marker))
+        .with_child((Text::new(label), Name::new("menu_button_text")));
+}
+
+pub fn release_cursor_system(mut window_query: Query<&mut Window>) {
+    if let Ok(mut window) = window_query.get_single_mut() {
+        window.cursor_options.grab_mode = CursorGrabMode::None;
+        window.cursor_options.visible = true;
+    }
+}
+
+pub fn despawn_screen_system<T: Component>(
+    mut commands: Commands,
+    screens: Query<Entity, With<T>>,
+) {
+    for entity in screens.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Leaves the current server, the integrated server of singleplayer cannot be rejoined so the
+/// game quits instead.
+fn leave_session(
+    singleplayer: bool,
+    next_state: &mut NextState<GameState>,
+    exit_events: &mut EventWriter<AppExit>,
+) {
+    if singleplayer {
+        info!("Quitting singleplayer world");
+        exit_events.send(AppExit::Success);
+    } else {
+        next_state.set(GameState::MainMenu);
+    }
+}
+
+pub fn setup_main_menu_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    connection_error: Res<networking_resources::ConnectionError>,
+) {
+    commands
+        .spawn((
+            Node::default(),
+            Name::new("main_menu"),
+            NodeStyleSheet::new(asset_server.load("gui.css")),
+            GlobalZIndex(1),
+            menu_components::MainMenu,
+        ))
+        .with_children(|parent| {
+            parent.spawn((Text::new("RSMC"), Name::new("main_menu_title")));
+            parent.spawn((
+                Text::new(connection_error.0.clone().unwrap_or_default()),
+                Name::new("connection_error"),
+                menu_components::ConnectionErrorText,
+            ));
+            parent.spawn((
+                Node::default(),
+                Name::new("server_list"),
+                menu_components::ServerListContainer,
+            ));
+            parent
+                .spawn((Node::default(), Name::new("direct_connect")))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(ADDRESS_PLACEHOLDER),
+                        Name::new("address_input"),
+                        menu_components::AddressInputText,
+                    ));
+                    spawn_button(parent, "Connect", menu_components::ConnectButton);
+                    spawn_button(parent, "Save", menu_components::SaveServerButton);
+                });
+            spawn_button(parent, "Settings", menu_components::OpenSettingsButton);
+            spawn_button(parent, "Quit", menu_components::QuitButton);
+        });
+}
+
+pub fn refresh_server_list_system(
+    mut commands: Commands,
+    server_list: Res<menu_resources::ServerList>,
+    containers: Query<(Entity, Ref<menu_components::ServerListContainer>)>,
+) {
+    for (entity, container) in containers.iter() {
+        if !server_list.is_changed() && !container.is_added() {
+            continue;
+        }
+
+        commands
+            .entity(entity)
+            .despawn_descendants()
+            .with_children(|parent| {
+                for (index, server) in server_list.servers.iter().enumerate() {
+                    parent
+                        .spawn((Node::default(), Name::new("server_entry")))
+                        .with_children(|parent| {
+                            parent.spawn((
+                                Text::new(format!("{} ({})", server.name, server.address)),
+                                Name::new("server_entry_text"),
+                            ));
+                            spawn_button(parent, "Join", menu_components::JoinServerButton(index));
+                            spawn_button(
+                                parent,
+                                "Remove",
+                                menu_components::RemoveServerButton(index),
+                            );
+                        });
+                }
+            });
+    }
+}
+
+pub fn handle_server_list_buttons_system(
+    mut server_list: ResMut<menu_resources::ServerList>,
+    mut connect_events: EventWriter<menu_events::ConnectEvent>,
+    join_buttons: Query<(&Interaction, &menu_components::JoinServerButton), Changed<Interaction>>,
+    remove_buttons: Query<
+        (&Interaction, &menu_components::RemoveServerButton),
+        Changed<Interaction>,
+    >,
+) {
+    for (interaction, button) in join_buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        if let Some(server) = server_list.servers.get(button.0) {
+            connect_events.send(menu_events::ConnectEvent {
+                address: server.address.clone(),
+            });
+        }
+    }
+
+    for (interaction, button) in remove_buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        if let Some(server) = server_list.remove(button.0) {
+            info!("Removing server {}", server.address);
+            save_server_list(&server_list);
+        }
+    }
+}
+
+fn save_server_list(server_list: &menu_resources::ServerList) {
+    if let Err(error) = server_list.save(Path::new(menu_resources::SERVER_LIST_PATH)) {
+        error!("Could not save server list: {}", error);
+    }
+}
+
+pub fn process_address_input_system(
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut address_input: ResMut<menu_resources::DirectConnectInput>,
+    mut connect_events: EventWriter<menu_events::ConnectEvent>,
+    settings_screens: Query<(), With<menu_components::SettingsScreen>>,
+) {
+    // The settings screen covers the address field
+    if !settings_screens.is_empty() {
+        keyboard_events.clear();
+        return;
+    }
+
+    for event in keyboard_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+
+        match &event.logical_key {
+            Key::Enter if !address_input.0.trim().is_empty() => {
+                connect_events.send(menu_events::ConnectEvent {
+                    address: address_input.0.trim().to_string(),
+                });
+            }
+            Key::Backspace => {
+                address_input.0.pop();
+            }
+            Key::Character(input) => {
+                if address_input.0.len() < menu_resources::MAX_ADDRESS_LENGTH
+                    && input.chars().all(|c| !c.is_control() && !c.is_whitespace())
+                {
+                    address_input.0.push_str(input);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn update_address_input_system(
+    address_input: Res<menu_resources::DirectConnectInput>,
+    mut texts: Query<(&mut Text, Ref<menu_components::AddressInputText>)>,
+) {
+    for (mut text, input) in texts.iter_mut() {
+        if !address_input.is_changed() && !input.is_added() {
+            continue;
+        }
+
+        text.0 = match address_input.0.is_empty() {
+            true => ADDRESS_PLACEHOLDER.to_string(),
+            false => address_input.0.clone(),
+        };
+    }
+}
+
+pub fn handle_main_menu_buttons_system(
+    address_input: Res<menu_resources::DirectConnectInput>,
+    mut server_list: ResMut<menu_resources::ServerList>,
+    mut connect_events: EventWriter<menu_events::ConnectEvent>,
+    mut exit_events: EventWriter<AppExit>,
+    buttons: Query<
+        (
+            &Interaction,
+            Has<menu_components::ConnectButton>,
+            Has<menu_components::SaveServerButton>,
+            Has<menu_components::QuitButton>,
+        ),
+        Changed<Interaction>,
+    >,
+) {
+    for (interaction, connect, save, quit) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        if connect && !address_input.0.trim().is_empty() {
+            connect_events.send(menu_events::ConnectEvent {
+                address: address_input.0.trim().to_string(),
+            });
+        }
+        if save && server_list.add(&address_input.0) {
+            save_server_list(&server_list);
+        }
+        if quit {
+            exit_events.send(AppExit::Success);
+        }
+    }
+}
+
+pub fn handle_connect_events_system(
+    mut connect_events: EventReader<menu_events::ConnectEvent>,
+    mut server_address: ResMut<networking_resources::ServerAddress>,
+    mut connection_error: ResMut<networking_resources::ConnectionError>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    // Only the last request matters if several buttons were pressed at once
+    let Some(event) = connect_events.read().last() else {
+        return;
+    };
+
+    match networking_resources::ServerAddress::resolve(&event.address) {
+        Ok(address) => {
+            *server_address = address;
+            next_state.set(GameState::Connecting);
+        }
+        Err(error) => {
+            warn!("Invalid server address: {}", error);
+            connection_error.0 = Some(format!("Invalid address: {}", error));
+        }
+    }
+}
+
+pub fn update_connection_error_system(
+    connection_error: Res<networking_resources::ConnectionError>,
+    mut texts: Query<&mut Text, With<menu_components::ConnectionErrorText>>,
+) {
+    if !connection_error.is_changed() {
+        return;
+    }
+
+    for mut text in texts.iter_mut() {
+        text.0 = connection_error.0.clone().unwrap_or_default();
+    }
+}
+
+pub fn setup_loading_screen_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    server_address: Res<networking_resources::ServerAddress>,
+    memory_transport: Option<Res<MemoryClientTransport>>,
+) {
+    let title = match memory_transport {
+        Some(_) => "Loading singleplayer world".to_string(),
+        None => format!("Connecting to {}", server_address.0),
+    };
+
+    commands
+        .spawn((
+            Node::default(),
+            Name::new("loading_screen"),
+            NodeStyleSheet::new(asset_server.load("gui.css")),
+            GlobalZIndex(1),
+            menu_components::LoadingScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((Text::new(title), Name::new("loading_title")));
+            parent.spawn((
+                Text::new(""),
+                Name::new("loading_status"),
+                menu_components::LoadingStatusText,
+            ));
+            parent
+                .spawn((Node::default(), Name::new("loading_bar")))
+                .with_child((
+                    Node::default(),
+                    Name::new("loading_bar_fill"),
+                    menu_components::LoadingBarFill,
+                ));
+            spawn_button(parent, "Cancel", menu_components::CancelButton);
+        });
+}
+
+pub fn update_loading_screen_system(
+    client: Res<RenetClient>,
+    spawn_area_loaded: Res<terrain_resources::SpawnAreaLoaded>,
+    chunk_download: Res<terrain_resources::ChunkDownloadProgress>,
+    mut texts: Query<&mut Text, With<menu_components::LoadingStatusText>>,
+    mut bars: Query<&mut Node, With<menu_components::LoadingBarFill>>,
+) {
+    let status = if !client.is_connected() {
+        "Waiting for the server".to_string()
+    } else {
+        let received = chunk_download.received.min(chunk_download.requested);
+        let stage = match spawn_area_loaded.0 {
+            true => "Spawning",
+            false => "Downloading spawn area",
+        };
+        format!(
+            "{} ({}/{} chunks)",
+            stage, received, chunk_download.requested
+        )
+    };
+
+    for mut text in texts.iter_mut() {
+        if text.0 != status {
+            text.0 = status.clone();
+        }
+    }
+    for mut bar in bars.iter_mut() {
+        bar.width = Val::Percent(chunk_download.fraction() * 100.0);
+    }
+}
+
+/// Starts playing once the controller was set up at the spawn point sent by the server.
+pub fn finish_loading_system(
+    player_spawned: Res<player_resources::PlayerSpawned>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if player_spawned.0 {
+        next_state.set(GameState::Playing);
+    }
+}
+
+pub fn handle_cancel_button_system(
+    memory_transport: Option<Res<MemoryClientTransport>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut exit_events: EventWriter<AppExit>,
+    buttons: Query<&Interaction, (Changed<Interaction>, With<menu_components::CancelButton>)>,
+) {
+    for interaction in buttons.iter() {
+        if *interaction == Interaction::Pressed {
+            leave_session(
+                memory_transport.is_some(),
+                &mut next_state,
+                &mut exit_events,
+            );
+        }
+    }
+}
+
+pub fn pause_state_transition_system(
+    actions: ActionInput,
+    current_state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    settings_screens: Query<(), With<menu_components::SettingsScreen>>,
+) {
+    // The settings screens are closed through their buttons
+    if !actions.just_pressed(Action::Pause) || !settings_screens.is_empty() {
+        return;
+    }
+
+    match current_state.get() {
+        GameState::Playing => next_state.set(GameState::Paused),
+        GameState::Paused => next_state.set(GameState::Playing),
+        _ => {}
+    }
+}
+
+pub fn setup_pause_menu_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    memory_transport: Option<Res<MemoryClientTransport>>,
+) {
+    let leave_label = match memory_transport {
+        Some(_) => "Save and quit",
+        None => "Disconnect",
+    };
+
+    commands
+        .spawn((
+            Node::default(),
+            Name::new("pause_menu"),
+            NodeStyleSheet::new(asset_server.load("gui.css")),
+            GlobalZIndex(1),
+            menu_components::PauseMenu,
+        ))
+        .with_children(|parent| {
+            parent.spawn((Text::new("Game paused"), Name::new("pause_title")));
+            spawn_button(parent, "Resume", menu_components::ResumeButton);
+            spawn_button(parent, "Settings", menu_components::OpenSettingsButton);
+            spawn_button(parent, leave_label, menu_components::LeaveButton);
+        });
+}
+
+pub fn handle_pause_menu_buttons_system(
+    memory_transport: Option<Res<MemoryClientTransport>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut exit_events: EventWriter<AppExit>,
+    buttons: Query<
+        (
+            &Interaction,
+            Has<menu_components::ResumeButton>,
+            Has<menu_components::LeaveButton>,
+        ),
+        Changed<Interaction>,
+    >,
+) {
+    for (interaction, resume, leave) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        if resume {
+            next_state.set(GameState::Playing);
+        }
+        if leave {
+            leave_session(
+                memory_transport.is_some(),
+                &mut next_state,
+                &mut exit_events,
+            );
+        }
+    }
+}
+
+pub fn handle_open_settings_button_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    buttons: Query<
+        &Interaction,
+        (
+            Changed<Interaction>,
+            With<menu_components::OpenSettingsButton>,
+        ),
+    >,
+    settings_screens: Query<(), With<menu_components::SettingsScreen>>,
+) {
+    let pressed = buttons
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed);
+    if !pressed || !settings_screens.is_empty() {
+        return;
+    }
+
+    commands
+        .spawn((
+            Node::default(),
+            Name::new("settings_screen"),
+            NodeStyleSheet::new(asset_server.load("gui.css")),
+            GlobalZIndex(2),
+            menu_components::SettingsScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((Text::new("Settings"), Name::new("settings_title")));
+            for setting in settings_resources::Setting::ALL {
+                parent
+                    .spawn((Node::default(), Name::new("setting_row")))
+                    .with_children(|parent| {
+                        parent.spawn((Text::new(setting.label()), Name::new("setting_label")));
+                        if !setting.is_toggle() {
+                            spawn_button(
+                                parent,
+                                "-",
+                                menu_components::AdjustSettingButton { setting, steps: -1 },
+                            );
+                        }
+                        parent.spawn((
+                            Text::new(""),
+                            Name::new("setting_value"),
+                            menu_components::SettingValueText(setting),
+                        ));
+                        spawn_button(
+                            parent,
+                            if setting.is_toggle() { "Toggle" } else { "+" },
+                            menu_components::AdjustSettingButton { setting, steps: 1 },
+                        );
+                    });
+            }
+            spawn_button(parent, "Controls", menu_components::OpenKeyBindingsButton);
+            spawn_button(parent, "Done", menu_components::CloseSettingsButton);
+        });
+}
+
+pub fn handle_settings_buttons_system(
+    mut commands: Commands,
+    mut settings: ResMut<settings_resources::ClientSettings>,
+    adjust_buttons: Query<
+        (&Interaction, &menu_components::AdjustSettingButton),
+        Changed<Interaction>,
+    >,
+    close_buttons: Query<
+        &Interaction,
+        (
+            Changed<Interaction>,
+            With<menu_components::CloseSettingsButton>,
+        ),
+    >,
+    settings_screens: Query<Entity, With<menu_components::SettingsScreen>>,
+) {
+    for (interaction, button) in adjust_buttons.iter() {
+        if *interaction == Interaction::Pressed {
+            button.setting.adjust(&mut settings, button.steps);
+        }
+    }
+
+    if close_buttons
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        for entity in settings_screens.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+pub fn update_setting_values_system(
+    settings: Res<settings_resources::ClientSettings>,
+    mut texts: Query<(&mut Text, Ref<menu_components::SettingValueText>)>,
+) {
+    for (mut text, value) in texts.iter_mut() {
+        if settings.is_changed() || value.is_added() {
+            text.0 = value.0.value(&settings);
+        }
+    }
+}
+
+pub fn handle_open_key_bindings_button_system(
+    mut commands: Commands,
+    buttons: Query<
+        &Interaction,
+        (
+            Changed<Interaction>,
+            With<menu_components::OpenKeyBindingsButton>,
+        ),
+    >,
+    settings_screens: Query<Entity, With<menu_components::SettingsScreen>>,
+    key_bindings_screens: Query<(), With<menu_components::KeyBindingsScreen>>,
+) {
+    let pressed = buttons
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed);
+    let Ok(settings_screen) = settings_screens.get_single() else {
+        return;
+    };
+    if !pressed || !key_bindings_screens.is_empty() {
+        return;
+    }
+
+    commands.entity(settings_screen).with_children(|parent| {
+        parent
+            .spawn((
+                Node::default(),
+                Name::new("key_bindings_screen"),
+                GlobalZIndex(3),
+                menu_components::KeyBindingsScreen,
+            ))
+            .with_children(|parent| {
+                parent.spawn((Text::new("Controls"), Name::new("settings_title")));
+                parent
+                    .spawn((Node::default(), Name::new("key_binding_list")))
+                    .with_children(|parent| {
+                        for action in Action::ALL {
+                            parent
+                                .spawn((Node::default(), Name::new("key_binding_row")))
+                                .with_children(|parent| {
+                                    parent.spawn((
+                                        Text::new(action.label()),
+                                        Name::new("setting_label"),
+                                    ));
+                                    parent
+                                        .spawn((
+                                            Button,
+                                            Node::default(),
+                                            Name::new("menu_button"),
+                                            menu_components::KeyBindingButton(action),
+                                        ))
+                                        .with_child((
+                                            Text::new(""),
+                                            Name::new("key_binding_text"),
+                                            ClassList::new(),
+                                            menu_components::KeyBindingText(action),
+                                        ));
+                                });
+                        }
+                    });
+                parent
+                    .spawn((Node::default(), Name::new("menu_row")))
+                    .with_children(|parent| {
+                        spawn_button(parent, "Reset", menu_components::ResetKeyBindingsButton);
+                        spawn_button(parent, "Done", menu_components::CloseKeyBindingsButton);
+                    });
+            });
+    });
+}
+
+pub fn handle_key_binding_buttons_system(
+    mut commands: Commands,
+    mut bindings: ResMut<actions_resources::KeyBindings>,
+    mut rebinding: ResMut<menu_resources::RebindingAction>,
+    buttons: Query<
+        (
+            &Interaction,
+            Option<&menu_components::KeyBindingButton>,
+            Has<menu_components::ResetKeyBindingsButton>,
+            Has<menu_components::CloseKeyBindingsButton>,
+        ),
+        Changed<Interaction>,
+    >,
+    key_bindings_screens: Query<Entity, With<menu_components::KeyBindingsScreen>>,
+) {
+    for (interaction, binding_button, reset, close) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        if let Some(binding_button) = binding_button {
+            rebinding.0 = Some(binding_button.0);
+        }
+        if reset {
+            *bindings = actions_resources::KeyBindings::default();
+            rebinding.0 = None;
+        }
+        if close {
+            rebinding.0 = None;
+            for entity in key_bindings_screens.iter() {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+    }
+}
+
+/// Binds the first key or mouse button pressed after a binding was selected, Escape cancels.
+pub fn capture_key_binding_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut bindings: ResMut<actions_resources::KeyBindings>,
+    mut rebinding: ResMut<menu_resources::RebindingAction>,
+    key_bindings_screens: Query<(), With<menu_components::KeyBindingsScreen>>,
+) {
+    // The click selecting the binding must not be captured
+    if rebinding.is_changed() {
+        return;
+    }
+    if key_bindings_screens.is_empty() {
+        if rebinding.0.is_some() {
+            rebinding.0 = None;
+        }
+        return;
+    }
+    let Some(action) = rebinding.0 else {
+        return;
+    };
+
+    if keys.just_pressed(KeyCode::Escape) {
+        rebinding.0 = None;
+        return;
+    }
+
+    let binding = keys
+        .get_just_pressed()
+        .map(|key| actions_resources::Binding::Key(*key))
+        .chain(
+            mouse
+                .get_just_pressed()
+                .map(|button| actions_resources::Binding::Mouse(*button)),
+        )
+        .next();
+    let Some(binding) = binding else {
+        return;
+    };
+
+    match bindings.set(action, binding) {
+        Ok(()) => {
+            info!("Bound {} to {}", action.label(), binding);
+            rebinding.0 = None;
+        }
+        Err(error) => warn!("Could not bind {}: {}", action.label(), error),
+    }
+}
+
+pub fn update_key_binding_texts_system(
+    bindings: Res<actions_resources::KeyBindings>,
+    rebinding: Res<menu_resources::RebindingAction>,
+    mut texts: Query<(
+        &mut Text,
+        &mut ClassList,
+        Ref<menu_components::KeyBindingText>,
+    )>,
+) {
+    for (mut text, mut classes, binding_text) in texts.iter_mut() {
+        if !bindings.is_changed() && !rebinding.is_changed() && !binding_text.is_added() {
+            continue;
+        }
+
+        let action = binding_text.0;
+        let conflicts = bindings.conflicts(action);
+        text.0 = match rebinding.0 == Some(action) {
+            true => "Press a key...".to_string(),
+            false if conflicts.is_empty() => bindings.get(action).to_string(),
+            false => format!(
+                "{} (also {})",
+                bindings.get(action),
+                conflicts
+                    .iter()
+                    .map(|other| other.label())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+
+        match conflicts.is_empty() {
+            true => {
+                classes.remove_class("conflict");
+            }
+            false => {
+                classes.add_class("conflict");
+            }
+        }
+    }
+}
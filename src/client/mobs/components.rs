@@ -0,0 +1,15 @@
+use crate::prelude::*;
+
+/// Walk cycle of a mob, applied to its legs.
+#[derive(Component, Debug, Default)]
+pub struct MobAnimation {
+    pub walk_phase: f32,
+    /// Current leg angle in radians.
+    pub swing: f32,
+}
+
+/// Legs on the same diagonal swing together.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct MobLeg {
+    pub forward: bool,
+}
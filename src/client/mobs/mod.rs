@@ -0,0 +1,24 @@
+pub mod components;
+pub mod resources;
+pub mod systems;
+
+use crate::prelude::*;
+
+pub struct MobsPlugin;
+
+impl Plugin for MobsPlugin {
+    fn build(&self, app: &mut App) {
+        app.replicate::<Mob>();
+        app.init_resource::<mobs_resources::MobAssets>();
+        app.add_systems(
+            Update,
+            (
+                mobs_systems::setup_mobs_system,
+                mobs_systems::interpolate_mobs_system,
+                mobs_systems::animate_mob_legs_system,
+            )
+                .chain()
+                .after(ReplicationSet),
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use crate::prelude::*;
+
+/// Body proportions of a mob kind in blocks.
+pub struct MobModel {
+    pub body: Vec3,
+    pub head: f32,
+    pub leg_height: f32,
+    pub color: Color,
+    pub head_color: Color,
+}
+
+impl MobModel {
+    pub fn of(kind: MobKind) -> Self {
+        match kind {
+            MobKind::Pig => Self {
+                body: Vec3::new(0.6, 0.5, 0.9),
+                head: 0.5,
+                leg_height: 0.4,
+                color: Color::srgb(0.93, 0.65, 0.65),
+                head_color: Color::srgb(0.9, 0.6, 0.6),
+            },
+            MobKind::Sheep => Self {
+                body: Vec3::new(0.7, 0.6, 1.0),
+                head: 0.4,
+                leg_height: 0.7,
+                color: Color::srgb(0.92, 0.92, 0.9),
+                head_color: Color::srgb(0.75, 0.65, 0.55),
+            },
+        }
+    }
+}
+
+pub struct MobMeshes {
+    pub body: Handle<Mesh>,
+    pub head: Handle<Mesh>,
+    pub leg: Handle<Mesh>,
+    pub material: Handle<StandardMaterial>,
+    pub head_material: Handle<StandardMaterial>,
+}
+
+#[derive(Resource)]
+pub struct MobAssets {
+    pub kinds: HashMap<MobKind, MobMeshes>,
+}
+
+impl FromWorld for MobAssets {
+    fn from_world(world: &mut World) -> Self {
+        let models: Vec<(MobKind, MobModel)> = MobKind::ALL
+            .iter()
+            .map(|kind| (*kind, MobModel::of(*kind)))
+            .collect();
+
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        let mesh_handles: Vec<_> = models
+            .iter()
+            .map(|(_, model)| {
+                (
+                    meshes.add(Cuboid::from_size(model.body)),
+                    meshes.add(Cuboid::from_length(model.head)),
+                    meshes.add(Cuboid::new(0.2, model.leg_height, 0.2)),
+                )
+            })
+            .collect();
+
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let kinds = models
+            .iter()
+            .zip(mesh_handles)
+            .map(|((kind, model), (body, head, leg))| {
+                (
+                    *kind,
+                    MobMeshes {
+                        body,
+                        head,
+                        leg,
+                        material: materials.add(model.color),
+                        head_material: materials.add(model.head_color),
+                    },
+                )
+            })
+            .collect();
+
+        Self { kinds }
+    }
+}
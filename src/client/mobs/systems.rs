@@ -0,0 +1,124 @@
+use crate::prelude::*;
+
+/// How quickly mobs catch up with the position sent by the server.
+const SMOOTHING: f32 = 12.0;
+const STRIDE_LENGTH: f32 = 1.0;
+const MAX_SWING: f32 = 0.6;
+
+pub fn setup_mobs_system(
+    mut commands: Commands,
+    mobs: Query<
+        (
+            Entity,
+            &Mob,
+            Option<&NetworkPosition>,
+            Option<&NetworkRotation>,
+        ),
+        Added<Mob>,
+    >,
+    mob_assets: Res<mobs_resources::MobAssets>,
+) {
+    for (entity, mob, position, rotation) in mobs.iter() {
+        let model = mobs_resources::MobModel::of(mob.kind);
+        let meshes = &mob_assets.kinds[&mob.kind];
+
+        let translation = position.map(|position| position.0).unwrap_or_default();
+        let rotation = rotation.map(|rotation| rotation.0).unwrap_or_default();
+        let body_y = model.leg_height + model.body.y / 2.0;
+
+        commands
+            .entity(entity)
+            .insert((
+                Transform::from_translation(translation).with_rotation(rotation),
+                mobs_components::MobAnimation::default(),
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    Mesh3d(meshes.body.clone()),
+                    MeshMaterial3d(meshes.material.clone()),
+                    Transform::from_xyz(0.0, body_y, 0.0),
+                ));
+                parent.spawn((
+                    Mesh3d(meshes.head.clone()),
+                    MeshMaterial3d(meshes.head_material.clone()),
+                    Transform::from_xyz(
+                        0.0,
+                        body_y + model.body.y / 2.0,
+                        -(model.body.z + model.head) / 2.0,
+                    ),
+                ));
+
+                let leg_x = model.body.x / 2.0 - 0.1;
+                let leg_z = model.body.z / 2.0 - 0.1;
+                for (x, z, forward) in [
+                    (-leg_x, -leg_z, true),
+                    (leg_x, leg_z, true),
+                    (leg_x, -leg_z, false),
+                    (-leg_x, leg_z, false),
+                ] {
+                    // Legs rotate around the hip, the mesh hangs below it
+                    parent
+                        .spawn((
+                            Transform::from_xyz(x, model.leg_height, z),
+                            Visibility::default(),
+                            mobs_components::MobLeg { forward },
+                        ))
+                        .with_child((
+                            Mesh3d(meshes.leg.clone()),
+                            MeshMaterial3d(meshes.head_material.clone()),
+                            Transform::from_xyz(0.0, -model.leg_height / 2.0, 0.0),
+                        ));
+                }
+            });
+    }
+}
+
+pub fn interpolate_mobs_system(
+    mut mobs: Query<
+        (
+            &NetworkPosition,
+            Option<&NetworkRotation>,
+            &mut Transform,
+            &mut mobs_components::MobAnimation,
+        ),
+        With<Mob>,
+    >,
+    time: Res<Time>,
+) {
+    let delta = time.delta_secs();
+    let factor = 1.0 - (-SMOOTHING * delta).exp();
+
+    for (position, rotation, mut transform, mut animation) in mobs.iter_mut() {
+        let previous = transform.translation;
+        transform.translation = previous.lerp(position.0, factor);
+        if let Some(rotation) = rotation {
+            transform.rotation = transform.rotation.slerp(rotation.0, factor);
+        }
+
+        let moved = (transform.translation - previous).with_y(0.0).length();
+        animation.walk_phase += moved / STRIDE_LENGTH * PI;
+
+        let target_swing = match moved > 1e-3 {
+            true => animation.walk_phase.sin() * MAX_SWING,
+            false => 0.0,
+        };
+        animation.swing += (target_swing - animation.swing) * factor;
+    }
+}
+
+pub fn animate_mob_legs_system(
+    mut legs: Query<(&mobs_components::MobLeg, &Parent, &mut Transform)>,
+    animations: Query<&mobs_components::MobAnimation>,
+) {
+    for (leg, parent, mut transform) in legs.iter_mut() {
+        let Ok(animation) = animations.get(parent.get()) else {
+            continue;
+        };
+
+        let swing = match leg.forward {
+            true => animation.swing,
+            false => -animation.swing,
+        };
+        transform.rotation = Quat::from_rotation_x(swing);
+    }
+}
@@ -1,37 +1,44 @@
+pub mod resources;
 pub mod systems;
 
-use crate::connection_config;
-use bevy_renet::{
-    netcode::{ClientAuthentication, NetcodeClientPlugin, NetcodeClientTransport},
-    RenetClientPlugin,
-};
+use bevy_renet::{netcode::NetcodeClientPlugin, RenetClientPlugin};
 
 use crate::prelude::*;
 
-const SERVER_ADDR: &str = "127.0.0.1:5000";
-
 pub struct NetworkingPlugin;
 impl Plugin for NetworkingPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((RenetClientPlugin, NetcodeClientPlugin));
+        app.add_plugins(RenetClientPlugin);
+        app.add_plugins((NetcodeClientPlugin, MemoryClientTransportPlugin));
 
-        let client = RenetClient::new(connection_config());
-        app.insert_resource(client);
+        app.insert_resource(RenetClient::new(connection_config()));
+        app.insert_resource(networking_resources::LatestServerDiagnostics::default());
+        app.insert_resource(networking_resources::ReceivedPlayerSnapshots::default());
+        app.insert_resource(networking_resources::ConnectionError::default());
+        app.init_resource::<networking_resources::ServerAddress>();
+        app.init_resource::<networking_resources::LocalPlayerProfile>();
+        app.init_resource::<networking_resources::LocalPlayerIdentity>();
 
-        let client_id = rand::random::<u64>();
-        let authentication = ClientAuthentication::Unsecure {
-            server_addr: SERVER_ADDR.parse().unwrap(),
-            client_id,
-            user_data: None,
-            protocol_id: 0,
-        };
-        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
-        let current_time = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap();
-        let transport = NetcodeClientTransport::new(current_time, authentication, socket).unwrap();
-        app.insert_resource(transport);
+        app.add_systems(
+            OnEnter(GameState::Connecting),
+            networking_systems::connect_to_server_system,
+        );
+        app.add_systems(
+            OnEnter(GameState::MainMenu),
+            networking_systems::disconnect_from_server_system,
+        );
 
-        app.add_systems(Update, networking_systems::receive_message_system);
+        app.add_systems(
+            Update,
+            (
+                networking_systems::receive_message_system,
+                networking_systems::send_join_request_system,
+            ),
+        );
+        app.add_systems(
+            Update,
+            networking_systems::handle_connection_loss_system
+                .run_if(not(in_state(GameState::MainMenu))),
+        );
     }
 }
@@ -0,0 +1,159 @@
+use crate::prelude::*;
+
+pub const DEFAULT_SERVER_ADDR: &str = "127.0.0.1:5000";
+pub const DEFAULT_SINGLEPLAYER_WORLD: &str = "saves/singleplayer";
+pub const PLAYER_IDENTITY_PATH: &str = "identity";
+
+pub const USAGE: &str = "Usage: client [--server <ADDR>] [--singleplayer] [--world <DIR>] [--name <NAME>] [--color <RRGGBB>]";
+
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct ServerAddress(pub SocketAddr);
+
+impl Default for ServerAddress {
+    fn default() -> Self {
+        Self(DEFAULT_SERVER_ADDR.parse().unwrap())
+    }
+}
+
+impl ServerAddress {
+    pub fn resolve(value: &str) -> Result<Self, String> {
+        value
+            .to_socket_addrs()
+            .map_err(|error| format!("could not resolve '{}': {}", value, error))?
+            .next()
+            .map(Self)
+            .ok_or_else(|| format!("'{}' did not resolve to an address", value))
+    }
+
+    /// Local address the client socket binds to, matching the address family of the server.
+    pub fn local_bind_address(&self) -> SocketAddr {
+        match self.0 {
+            SocketAddr::V4(_) => SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)),
+            SocketAddr::V6(_) => SocketAddr::from((Ipv6Addr::UNSPECIFIED, 0)),
+        }
+    }
+}
+
+/// Most recent performance report broadcast by the server.
+#[derive(Resource, Debug, Default)]
+pub struct LatestServerDiagnostics(pub Option<ServerDiagnostics>);
+
+/// Number of received snapshots kept as baselines for delta decoding.
+pub const SNAPSHOT_BUFFER_SIZE: usize = 32;
+
+#[derive(Resource, Default)]
+pub struct ReceivedPlayerSnapshots {
+    pub snapshots: VecDeque<(u32, PlayerSnapshotState)>,
+}
+
+impl ReceivedPlayerSnapshots {
+    pub fn latest(&self) -> Option<(u32, &PlayerSnapshotState)> {
+        self.snapshots.back().map(|(tick, state)| (*tick, state))
+    }
+
+    pub fn get(&self, tick: u32) -> Option<&PlayerSnapshotState> {
+        self.snapshots
+            .iter()
+            .find(|(snapshot_tick, _)| *snapshot_tick == tick)
+            .map(|(_, state)| state)
+    }
+
+    pub fn push(&mut self, tick: u32, state: PlayerSnapshotState) {
+        self.snapshots.push_back((tick, state));
+
+        while self.snapshots.len() > SNAPSHOT_BUFFER_SIZE {
+            self.snapshots.pop_front();
+        }
+    }
+}
+
+/// Name and colour sent to the server when joining.
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+pub struct LocalPlayerProfile(pub PlayerProfile);
+
+/// Identity sent to the server when joining, servers key the saved player data by it.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct LocalPlayerIdentity(pub PlayerIdentity);
+
+impl Default for LocalPlayerIdentity {
+    fn default() -> Self {
+        Self(PlayerIdentity::random())
+    }
+}
+
+impl LocalPlayerIdentity {
+    /// Reads the identity stored next to the client, a new one is generated and stored on the
+    /// first launch.
+    pub fn load(path: &std::path::Path) -> Self {
+        let identity = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|hex| PlayerIdentity::from_hex(hex.trim()));
+
+        match identity {
+            Some(identity) => Self(identity),
+            None => {
+                let identity = Self::default();
+                if let Err(error) = std::fs::write(path, identity.0.to_hex()) {
+                    warn!("Could not save {}: {}", path.display(), error);
+                }
+                identity
+            }
+        }
+    }
+}
+
+/// Why the last connection attempt failed or the connection was lost, shown in the main menu.
+#[derive(Resource, Debug, Default)]
+pub struct ConnectionError(pub Option<String>);
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LaunchOptions {
+    /// Server to connect to right away, `None` opens the main menu.
+    pub server_address: Option<ServerAddress>,
+    /// World directory of the integrated server, `None` when joining a remote server.
+    pub singleplayer_world: Option<std::path::PathBuf>,
+    pub profile: LocalPlayerProfile,
+}
+
+impl LaunchOptions {
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(argument) = args.next() {
+            match argument.as_str() {
+                "--singleplayer" => {
+                    options
+                        .singleplayer_world
+                        .get_or_insert_with(|| DEFAULT_SINGLEPLAYER_WORLD.into());
+                }
+                "--world" => {
+                    let value = next_value(&mut args, &argument)?;
+                    options.singleplayer_world = Some(value.into());
+                }
+                "--name" => {
+                    options.profile.0.name = next_value(&mut args, &argument)?;
+                }
+                "--color" => {
+                    let value = next_value(&mut args, &argument)?;
+                    options.profile.0.color = PlayerProfile::parse_color(&value)?;
+                }
+                "--server" | "-s" => {
+                    let value = next_value(&mut args, &argument)?;
+                    options.server_address = Some(ServerAddress::resolve(&value)?);
+                }
+                _ if argument.starts_with('-') => {
+                    return Err(format!("unknown argument '{}'", argument));
+                }
+                _ => options.server_address = Some(ServerAddress::resolve(&argument)?),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+fn next_value<I: Iterator<Item = String>>(args: &mut I, argument: &str) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("missing value for '{}'", argument))
+}
@@ -1,42 +1,106 @@
+use bevy_renet::netcode::{ClientAuthentication, NetcodeClientTransport};
+
 use crate::prelude::*;
 
+/// Writers for messages about the local player.
+#[derive(SystemParam)]
+pub struct LocalPlayerMessageWriters<'w> {
+    correction_events: ResMut<'w, Events<player_events::PlayerCorrectionEvent>>,
+    movement_ack_events: ResMut<'w, Events<player_events::PlayerMovementAckEvent>>,
+    inventory: ResMut<'w, player_resources::PlayerInventory>,
+    health: ResMut<'w, player_resources::PlayerHealth>,
+    spawn_point: ResMut<'w, player_resources::PlayerSpawnPoint>,
+    game_mode: ResMut<'w, player_resources::PlayerGameMode>,
+}
+
+/// Writers for messages about the terrain.
+#[derive(SystemParam)]
+pub struct TerrainMessageWriters<'w> {
+    block_update_events: ResMut<'w, Events<terrain_events::BlockUpdateEvent>>,
+    chunk_manager: ResMut<'w, ChunkManager>,
+    chunk_mesh_events: ResMut<'w, Events<terrain_events::ChunkMeshUpdateEvent>>,
+    world_regenerate_events: ResMut<'w, Events<terrain_events::WorldRegenerateEvent>>,
+    spawn_area_loaded: ResMut<'w, terrain_resources::SpawnAreaLoaded>,
+    chunk_requests: ResMut<'w, terrain_resources::ChunkRequests>,
+    chunk_download: ResMut<'w, terrain_resources::ChunkDownloadProgress>,
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn receive_message_system(
     mut client: ResMut<RenetClient>,
-    mut player_spawn_events: ResMut<Events<remote_player_events::RemotePlayerSpawnedEvent>>,
+    mut remote_player_profiles: ResMut<remote_player_resources::RemotePlayerProfiles>,
     mut player_despawn_events: ResMut<Events<remote_player_events::RemotePlayerDespawnedEvent>>,
     mut player_sync_events: ResMut<Events<remote_player_events::RemotePlayerSyncEvent>>,
-    mut block_update_events: ResMut<Events<terrain_events::BlockUpdateEvent>>,
-    mut chunk_manager: ResMut<ChunkManager>,
-    mut chunk_mesh_events: ResMut<Events<terrain_events::ChunkMeshUpdateEvent>>,
-    mut world_regenerate_events: ResMut<Events<terrain_events::WorldRegenerateEvent>>,
+    mut local_player: LocalPlayerMessageWriters,
+    mut terrain: TerrainMessageWriters,
+    mut replication_events: ResMut<Events<replication_events::ReplicationEvent>>,
     #[cfg(feature = "chat")] mut chat_events: ResMut<Events<chat_events::ChatSyncEvent>>,
     #[cfg(feature = "chat")] mut single_chat_events: ResMut<
         Events<chat_events::SingleChatSendEvent>,
     >,
-    mut spawn_area_loaded: ResMut<terrain_resources::SpawnAreaLoaded>,
+    mut server_diagnostics: ResMut<networking_resources::LatestServerDiagnostics>,
+    mut received_snapshots: ResMut<networking_resources::ReceivedPlayerSnapshots>,
+    mut player_list: ResMut<player_list_resources::PlayerList>,
 ) {
     while let Some(message) = client.receive_message(DefaultChannel::ReliableOrdered) {
         match bincode::deserialize(&message) {
             Ok(message) => match message {
-                NetworkingMessage::PlayerJoin(event) => {
-                    player_spawn_events.send(remote_player_events::RemotePlayerSpawnedEvent {
-                        client_id: event,
-                        position: Vec3::ZERO,
-                    });
+                NetworkingMessage::PlayerJoin(client_id, profile) => {
+                    info!("Player {} joined as {}", client_id, profile.name);
+                    remote_player_profiles.profiles.insert(client_id, profile);
                 }
                 NetworkingMessage::PlayerLeave(event) => {
+                    remote_player_profiles.profiles.remove(&event);
                     player_despawn_events.send(remote_player_events::RemotePlayerDespawnedEvent {
                         client_id: event,
                     });
                 }
                 NetworkingMessage::BlockUpdate { position, block } => {
                     debug!("Client received block update message: {:?}", position);
-                    block_update_events.send(terrain_events::BlockUpdateEvent {
-                        position,
-                        block,
-                        from_network: true,
-                    });
+                    terrain
+                        .block_update_events
+                        .send(terrain_events::BlockUpdateEvent {
+                            position,
+                            block,
+                            from_network: true,
+                        });
+                }
+                NetworkingMessage::InventorySync(inventory) => {
+                    local_player.inventory.0 = inventory;
+                }
+                NetworkingMessage::PlayerSpawn { position, rotation } => {
+                    info!("Spawning at {:?}", position);
+                    local_player.spawn_point.position = Some(position);
+                    local_player.spawn_point.rotation = rotation;
+                    local_player.health.death_cause = None;
+                    local_player
+                        .correction_events
+                        .send(player_events::PlayerCorrectionEvent { position });
+                }
+                NetworkingMessage::HealthSync(health) => {
+                    local_player.health.health = health;
+                }
+                NetworkingMessage::GameModeSync(game_mode) => {
+                    info!("Game mode changed to {}", game_mode);
+                    local_player.game_mode.0 = game_mode;
+                }
+                NetworkingMessage::PlayerList(entries) => {
+                    player_list.entries = entries;
+                }
+                NetworkingMessage::PlayerDied(cause) => {
+                    info!("Player died: {:?}", cause);
+                    local_player.health.death_cause = Some(cause);
+                }
+                NetworkingMessage::PlayerPositionCorrection(position) => {
+                    local_player
+                        .correction_events
+                        .send(player_events::PlayerCorrectionEvent { position });
+                }
+                NetworkingMessage::EntitySpawn(update) => {
+                    replication_events.send(replication_events::ReplicationEvent::Spawned(update));
+                }
+                NetworkingMessage::EntityDespawn(id) => {
+                    replication_events.send(replication_events::ReplicationEvent::Despawned(id));
                 }
                 #[cfg(feature = "chat")]
                 NetworkingMessage::ChatMessageSync(messages) => {
@@ -77,28 +141,33 @@ pub fn receive_message_system(
                             chunk.position
                         );
                         let chunk_position = chunk.position;
-                        chunk_manager.insert_chunk(chunk);
-                        chunk_mesh_events.send(terrain_events::ChunkMeshUpdateEvent {
-                            position: chunk_position,
-                        });
+                        terrain
+                            .chunk_requests
+                            .pending
+                            .remove(&ChunkManager::position_to_key(chunk_position));
+                        terrain.chunk_manager.insert_chunk(chunk);
+                        terrain.chunk_download.received += 1;
+                        terrain
+                            .chunk_mesh_events
+                            .send(terrain_events::ChunkMeshUpdateEvent {
+                                position: chunk_position,
+                            });
 
-                        if chunk_position.eq(&Vec3 {
-                            x: 0.0,
-                            y: 0.0,
-                            z: 0.0,
-                        }) {
+                        let spawn_chunk = local_player
+                            .spawn_point
+                            .position
+                            .map(|position| (position / CHUNK_SIZE as f32).floor());
+                        if spawn_chunk == Some(chunk_position) {
                             info!("Spawn area loaded.");
-                            spawn_area_loaded.0 = true;
+                            terrain.spawn_area_loaded.0 = true;
                         }
                     }
                 }
-                NetworkingMessage::PlayerSync(event) => {
-                    player_sync_events
-                        .send(remote_player_events::RemotePlayerSyncEvent { players: event });
-                }
                 NetworkingMessage::ServerAsksClientNicelyToRerequestChunkBatch() => {
                     info!("Client asked for chunk batch.");
-                    world_regenerate_events.send(terrain_events::WorldRegenerateEvent);
+                    terrain
+                        .world_regenerate_events
+                        .send(terrain_events::WorldRegenerateEvent);
                 }
                 _ => {
                     warn!("Received unknown message type. (ReliableUnordered)");
@@ -106,4 +175,192 @@ pub fn receive_message_system(
             }
         }
     }
+
+    while let Some(message) = client.receive_message(DefaultChannel::Unreliable) {
+        match bincode::deserialize(&message) {
+            Ok(NetworkingMessage::ServerDiagnostics(diagnostics)) => {
+                server_diagnostics.0 = Some(diagnostics);
+            }
+            Ok(NetworkingMessage::PlayerMovementAck { sequence, position }) => {
+                local_player
+                    .movement_ack_events
+                    .send(player_events::PlayerMovementAckEvent { sequence, position });
+            }
+            Ok(NetworkingMessage::EntityUpdates { tick, entities }) => {
+                replication_events
+                    .send(replication_events::ReplicationEvent::Updated { tick, entities });
+            }
+            Ok(NetworkingMessage::PlayerSnapshot(snapshot)) => {
+                let latest = received_snapshots.latest();
+                if latest.is_some_and(|(latest_tick, _)| snapshot.tick <= latest_tick) {
+                    debug!("Dropping outdated snapshot {}", snapshot.tick);
+                    continue;
+                }
+
+                let baseline = snapshot
+                    .baseline_tick
+                    .and_then(|tick| received_snapshots.get(tick));
+                let Some(players) = snapshot.decode(baseline) else {
+                    debug!("Dropping snapshot {} with unknown baseline", snapshot.tick);
+                    continue;
+                };
+
+                // Players that left the view distance
+                if let Some((_, previous_players)) = latest {
+                    for client_id in previous_players.keys() {
+                        if !players.contains_key(client_id) {
+                            player_despawn_events.send(
+                                remote_player_events::RemotePlayerDespawnedEvent {
+                                    client_id: *client_id,
+                                },
+                            );
+                        }
+                    }
+                }
+
+                client.send_message(
+                    DefaultChannel::Unreliable,
+                    bincode::serialize(&NetworkingMessage::PlayerSnapshotAck(snapshot.tick))
+                        .unwrap(),
+                );
+
+                player_sync_events.send(remote_player_events::RemotePlayerSyncEvent {
+                    server_time: snapshot.time as f64 / 1000.0,
+                    players: players
+                        .iter()
+                        .map(|(client_id, state)| (*client_id, PlayerState::from(state)))
+                        .collect(),
+                });
+                received_snapshots.push(snapshot.tick, players);
+            }
+            Ok(_) => {
+                warn!("Received unknown message type. (Unreliable)");
+            }
+            Err(message) => {
+                error!("Could not deserialize message {:?}", message);
+            }
+        }
+    }
+}
+
+pub fn send_join_request_system(
+    mut client: ResMut<RenetClient>,
+    profile: Res<networking_resources::LocalPlayerProfile>,
+    identity: Res<networking_resources::LocalPlayerIdentity>,
+    mut join_request_sent: Local<bool>,
+) {
+    // Every new connection has to join again
+    if !client.is_connected() {
+        *join_request_sent = false;
+        return;
+    }
+
+    if *join_request_sent {
+        return;
+    }
+
+    info!("Joining as {}", profile.0.name);
+    client.send_message(
+        DefaultChannel::ReliableOrdered,
+        bincode::serialize(&NetworkingMessage::JoinGame(profile.0.clone(), identity.0)).unwrap(),
+    );
+    *join_request_sent = true;
+}
+
+/// Starts a new connection, messages sent before it is established are queued by the client.
+pub fn connect_to_server_system(
+    mut commands: Commands,
+    mut client: ResMut<RenetClient>,
+    server_address: Res<networking_resources::ServerAddress>,
+    memory_transport: Option<Res<MemoryClientTransport>>,
+    mut connection_error: ResMut<networking_resources::ConnectionError>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    *client = RenetClient::new(connection_config());
+    connection_error.0 = None;
+
+    if memory_transport.is_some() {
+        info!("Connecting to integrated server");
+        return;
+    }
+
+    info!("Connecting to {}", server_address.0);
+    match create_netcode_transport(&server_address) {
+        Ok(transport) => commands.insert_resource(transport),
+        Err(error) => {
+            error!("Could not connect to {}: {}", server_address.0, error);
+            connection_error.0 = Some(format!("Could not connect: {}", error));
+            next_state.set(GameState::MainMenu);
+        }
+    }
+}
+
+fn create_netcode_transport(
+    server_address: &networking_resources::ServerAddress,
+) -> Result<NetcodeClientTransport, String> {
+    let authentication = ClientAuthentication::Unsecure {
+        server_addr: server_address.0,
+        client_id: rand::random::<u64>(),
+        user_data: None,
+        protocol_id: 0,
+    };
+    let socket =
+        UdpSocket::bind(server_address.local_bind_address()).map_err(|error| error.to_string())?;
+    let current_time = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap();
+
+    NetcodeClientTransport::new(current_time, authentication, socket)
+        .map_err(|error| error.to_string())
+}
+
+pub fn disconnect_from_server_system(
+    mut commands: Commands,
+    mut client: ResMut<RenetClient>,
+    netcode_transport: Option<ResMut<NetcodeClientTransport>>,
+    memory_transport: Option<ResMut<MemoryClientTransport>>,
+    mut server_diagnostics: ResMut<networking_resources::LatestServerDiagnostics>,
+    mut received_snapshots: ResMut<networking_resources::ReceivedPlayerSnapshots>,
+) {
+    server_diagnostics.0 = None;
+    received_snapshots.snapshots.clear();
+
+    if let Some(mut transport) = netcode_transport {
+        info!("Disconnecting from server");
+        transport.disconnect();
+        commands.remove_resource::<NetcodeClientTransport>();
+    }
+
+    // The integrated server cannot be rejoined once its connection is closed
+    if let Some(mut transport) = memory_transport {
+        info!("Disconnecting from integrated server");
+        transport.disconnect();
+        commands.remove_resource::<MemoryClientTransport>();
+    }
+
+    client.disconnect();
+}
+
+/// Returns to the main menu with the reason when the server closes or refuses the connection.
+pub fn handle_connection_loss_system(
+    client: Res<RenetClient>,
+    netcode_transport: Option<Res<NetcodeClientTransport>>,
+    mut connection_error: ResMut<networking_resources::ConnectionError>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !client.is_disconnected() {
+        return;
+    }
+
+    let reason = match netcode_transport.and_then(|transport| transport.disconnect_reason()) {
+        Some(reason) => format!("{:?}", reason),
+        None => client
+            .disconnect_reason()
+            .map(|reason| format!("{:?}", reason))
+            .unwrap_or_else(|| "unknown reason".to_string()),
+    };
+
+    warn!("Lost connection to server: {}", reason);
+    connection_error.0 = Some(format!("Disconnected: {}", reason));
+    next_state.set(GameState::MainMenu);
 }
@@ -9,5 +9,25 @@ pub struct Player;
 #[derive(Component)]
 pub struct PlayerCamera;
 
+/// Crack texture shown on the highlighted block while it is being mined.
 #[derive(Component)]
-pub struct Raycastable;
+pub struct CrackOverlay;
+
+#[derive(Component)]
+pub struct HotbarSlot(pub usize);
+
+/// Hidden while the game mode does not take damage.
+#[derive(Component)]
+pub struct HealthBar;
+
+#[derive(Component)]
+pub struct HealthBarFill;
+
+#[derive(Component)]
+pub struct HealthText;
+
+#[derive(Component)]
+pub struct DeathScreen;
+
+#[derive(Component)]
+pub struct RespawnButton;
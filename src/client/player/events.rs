@@ -1,4 +1,14 @@
 use crate::prelude::*;
 
+/// The server rejected a movement and moved the player back to `position`.
 #[derive(Event)]
-pub struct PlayerColliderUpdateEvent;
+pub struct PlayerCorrectionEvent {
+    pub position: Vec3,
+}
+
+/// The server applied the movement input with `sequence`, ending up at `position`.
+#[derive(Event)]
+pub struct PlayerMovementAckEvent {
+    pub sequence: u32,
+    pub position: Vec3,
+}
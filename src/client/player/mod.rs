@@ -13,40 +13,61 @@ impl Plugin for PlayerPlugin {
         info!("Building PlayerPlugin");
         app.add_plugins(FpsControllerPlugin);
         app.add_plugins(RapierPhysicsPlugin::<NoUserData>::default());
-        #[cfg(feature = "physics_debug")]
-        app.add_plugins(RapierDebugRenderPlugin::default());
-        app.add_event::<player_events::PlayerColliderUpdateEvent>();
+        // Toggled at runtime through the client settings
+        app.add_plugins(RapierDebugRenderPlugin {
+            enabled: false,
+            ..default()
+        });
+        app.add_event::<player_events::PlayerCorrectionEvent>();
+        app.add_event::<player_events::PlayerMovementAckEvent>();
         app.insert_resource(player_resources::BlockSelection::new());
         app.insert_resource(player_resources::PlayerSpawned(false));
-        app.insert_resource(player_resources::LastPlayerPosition::new());
+        app.insert_resource(player_resources::PredictedMovement::default());
+        app.insert_resource(player_resources::PlayerInventory::default());
+        app.insert_resource(player_resources::MiningProgress::default());
+        app.insert_resource(player_resources::PlayerHealth::default());
+        app.insert_resource(player_resources::PlayerSpawnPoint::default());
+        app.insert_resource(player_resources::PlayerGameMode::default());
+        app.insert_resource(player_resources::PlayerMoveMode::default());
         app.add_systems(
             Startup,
             (
-                player_systems::setup_highlight_cube_system,
+                (
+                    player_systems::setup_highlight_cube_system,
+                    player_systems::setup_crack_overlay_system,
+                )
+                    .chain(),
                 player_systems::setup_player_camera,
+                player_systems::setup_hotbar_system,
+                player_systems::setup_health_bar_system,
             ),
         );
         app.add_systems(
             Update,
             (player_systems::setup_controller_on_area_ready_system,)
                 .run_if(terrain_resources::SpawnAreaLoaded::is_loaded)
+                .run_if(player_resources::PlayerSpawnPoint::is_known)
                 .run_if(player_resources::PlayerSpawned::is_not_spawned),
         );
         app.add_systems(
             Update,
             (
-                player_systems::handle_controller_movement_system,
-                player_systems::handle_player_collider_events_system,
+                player_systems::handle_player_correction_events_system,
+                player_systems::reconcile_player_movement_system,
             )
+                .chain()
+                .before(player_systems::broadcast_player_attributes_system)
                 .run_if(player_resources::PlayerSpawned::is_spawned),
         );
         app.add_systems(
             Update,
             (
                 player_systems::manage_cursor_system,
+                player_systems::toggle_flight_system,
                 player_systems::handle_mouse_events_system,
-                player_systems::handle_keyboard_events_system,
                 player_systems::raycast_system,
+                player_systems::handle_mining_system.after(player_systems::raycast_system),
+                player_systems::handle_hotbar_selection_system,
                 player_systems::handle_block_update_events,
                 player_systems::broadcast_player_attributes_system,
             )
@@ -54,6 +75,32 @@ impl Plugin for PlayerPlugin {
                 .run_if(in_state(GameState::Playing)),
         );
 
+        app.add_systems(
+            Update,
+            (
+                player_systems::update_crack_overlay_system,
+                player_systems::update_hotbar_system,
+                player_systems::update_health_bar_system,
+                player_systems::handle_player_death_system,
+                player_systems::apply_game_mode_system,
+                player_systems::apply_move_mode_system
+                    .after(player_systems::apply_game_mode_system),
+            ),
+        );
+
+        app.add_systems(
+            OnEnter(GameState::Dead),
+            player_systems::setup_death_screen_system,
+        );
+        app.add_systems(
+            OnExit(GameState::Dead),
+            player_systems::despawn_death_screen_system,
+        );
+        app.add_systems(
+            Update,
+            player_systems::handle_respawn_button_system.run_if(in_state(GameState::Dead)),
+        );
+
         app.add_systems(
             OnEnter(GameState::Playing),
             (
@@ -66,5 +113,10 @@ impl Plugin for PlayerPlugin {
             OnExit(GameState::Playing),
             player_systems::deactivate_fps_controller_system,
         );
+
+        app.add_systems(
+            OnEnter(GameState::MainMenu),
+            player_systems::reset_player_system,
+        );
     }
 }
@@ -34,17 +34,255 @@ impl BlockSelection {
     }
 }
 
+/// Position sent by the server for the player to spawn at.
+#[derive(Resource, Default)]
+pub struct PlayerSpawnPoint {
+    pub position: Option<Vec3>,
+    /// Camera rotation a returning player left with.
+    pub rotation: Option<Quat>,
+}
+
+impl PlayerSpawnPoint {
+    pub fn is_known(resource: Res<PlayerSpawnPoint>) -> bool {
+        resource.position.is_some()
+    }
+}
+
+/// Health the server synced to this client.
 #[derive(Resource)]
-pub struct LastPlayerPosition(pub Vec3);
+pub struct PlayerHealth {
+    pub health: u32,
+    /// Cause of death while the player is dead.
+    pub death_cause: Option<DamageCause>,
+}
 
-impl Default for LastPlayerPosition {
+impl Default for PlayerHealth {
     fn default() -> Self {
-        Self::new()
+        Self {
+            health: MAX_HEALTH,
+            death_cause: None,
+        }
     }
 }
 
-impl LastPlayerPosition {
-    pub fn new() -> Self {
-        Self(Vec3::ZERO)
+/// Game mode the server synced to this client.
+#[derive(Resource, Default)]
+pub struct PlayerGameMode(pub GameMode);
+
+/// How the local player moves, the controller itself only knows walking and noclip.
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlayerMoveMode {
+    #[default]
+    Walking,
+    /// Creative flight, free of gravity but stopped by blocks through the collider and the swept
+    /// movement prediction like walking.
+    Flying,
+    /// Spectators pass through blocks.
+    Noclip,
+}
+
+impl PlayerMoveMode {
+    pub fn for_game_mode(game_mode: GameMode) -> Self {
+        match game_mode {
+            GameMode::Spectator => PlayerMoveMode::Noclip,
+            GameMode::Survival | GameMode::Creative => PlayerMoveMode::Walking,
+        }
+    }
+
+    /// Controller mode moving the player, its noclip mode only skips gravity while the
+    /// collider is enabled.
+    pub fn controller_mode(&self) -> MoveMode {
+        match self {
+            PlayerMoveMode::Walking => MoveMode::Ground,
+            PlayerMoveMode::Flying | PlayerMoveMode::Noclip => MoveMode::Noclip,
+        }
+    }
+
+    pub fn collides(&self) -> bool {
+        *self != PlayerMoveMode::Noclip
+    }
+}
+
+/// Copy of the inventory the server synced to this client.
+#[derive(Resource, Default)]
+pub struct PlayerInventory(pub Inventory);
+
+#[derive(Resource, Default)]
+pub struct MiningProgress {
+    pub target: Option<Vec3>,
+    /// Share of the mining duration that has passed, the block breaks at `1.0`.
+    pub progress: f32,
+}
+
+#[derive(Resource)]
+pub struct CrackMaterials {
+    pub stages: Vec<Handle<StandardMaterial>>,
+}
+
+/// Inputs kept for replay, enough for more than a second of unacknowledged movement.
+const MAX_PREDICTION_HISTORY: usize = 128;
+
+/// Distance between the predicted and acknowledged position that is tolerated without replaying.
+const RECONCILE_THRESHOLD: f32 = 0.01;
+
+pub struct PredictedInput {
+    pub input: MovementInput,
+    pub position: Vec3,
+}
+
+/// Movement inputs sent to the server together with the positions they were predicted to
+/// reach, replayed on top of the acknowledged position when the server disagrees.
+#[derive(Resource, Default)]
+pub struct PredictedMovement {
+    pub sequence: u32,
+    /// Position after the last predicted input, `None` until the first input was sent.
+    pub position: Option<Vec3>,
+    pub history: VecDeque<PredictedInput>,
+}
+
+impl PredictedMovement {
+    /// Turns the controller movement since the last input into a new input and predicts its result.
+    pub fn predict(
+        &mut self,
+        chunk_manager: &ChunkManager,
+        position: Vec3,
+        game_mode: GameMode,
+    ) -> (MovementInput, Vec3) {
+        let previous = self.position.unwrap_or(position);
+
+        self.sequence += 1;
+        let input = MovementInput {
+            sequence: self.sequence,
+            displacement: position - previous,
+        };
+        let predicted = step_movement(chunk_manager, previous, &input, game_mode);
+
+        self.position = Some(predicted);
+        self.history.push_back(PredictedInput {
+            input,
+            position: predicted,
+        });
+        if self.history.len() > MAX_PREDICTION_HISTORY {
+            self.history.pop_front();
+        }
+
+        (input, predicted)
+    }
+
+    /// Drops acknowledged inputs, returns the new position if the remaining ones were replayed.
+    pub fn reconcile(
+        &mut self,
+        chunk_manager: &ChunkManager,
+        sequence: u32,
+        position: Vec3,
+        game_mode: GameMode,
+    ) -> Option<Vec3> {
+        let index = self
+            .history
+            .iter()
+            .position(|predicted| predicted.input.sequence == sequence)?;
+        let predicted = self.history[index].position;
+        self.history.drain(..=index);
+
+        if predicted.distance(position) <= RECONCILE_THRESHOLD {
+            return None;
+        }
+
+        let mut replayed = position;
+        for predicted in self.history.iter_mut() {
+            replayed = step_movement(chunk_manager, replayed, &predicted.input, game_mode);
+            predicted.position = replayed;
+        }
+
+        self.position = Some(replayed);
+        Some(replayed)
+    }
+
+    /// Forgets all pending inputs after the server moved the player.
+    pub fn reset(&mut self, position: Vec3) {
+        self.history.clear();
+        self.position = Some(position);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flying_players_collide_with_blocks() {
+        assert!(PlayerMoveMode::Flying.collides());
+        assert!(matches!(
+            PlayerMoveMode::Flying.controller_mode(),
+            MoveMode::Noclip
+        ));
+        assert!(!PlayerMoveMode::for_game_mode(GameMode::Spectator).collides());
+
+        let mut chunk_manager = ChunkManager::new();
+        chunk_manager.insert_chunk(Chunk::new(Vec3::ZERO));
+        chunk_manager.update_block(Vec3::new(3.0, 1.0, 0.0), BlockId::Stone);
+
+        // Flying into a wall without gravity stops in front of it at the same height
+        let mut movement = PredictedMovement::default();
+        movement.predict(&chunk_manager, Vec3::new(0.5, 1.0, 0.5), GameMode::Creative);
+        let (_, position) =
+            movement.predict(&chunk_manager, Vec3::new(4.5, 1.0, 0.5), GameMode::Creative);
+
+        assert!(position.x < 3.0);
+        assert_eq!(position.y, 1.0);
+    }
+
+    #[test]
+    fn test_matching_acknowledgement_keeps_prediction() {
+        let chunk_manager = ChunkManager::new();
+        let mut movement = PredictedMovement::default();
+
+        movement.predict(&chunk_manager, Vec3::ZERO, GameMode::Survival);
+        let (input, position) = movement.predict(&chunk_manager, Vec3::X, GameMode::Survival);
+        movement.predict(&chunk_manager, Vec3::X * 2.0, GameMode::Survival);
+
+        assert_eq!(
+            movement.reconcile(&chunk_manager, input.sequence, position, GameMode::Survival),
+            None
+        );
+        assert_eq!(movement.history.len(), 1);
+        assert_eq!(movement.position, Some(Vec3::X * 2.0));
+    }
+
+    #[test]
+    fn test_mismatching_acknowledgement_replays_inputs() {
+        let chunk_manager = ChunkManager::new();
+        let mut movement = PredictedMovement::default();
+
+        movement.predict(&chunk_manager, Vec3::ZERO, GameMode::Survival);
+        let (input, _) = movement.predict(&chunk_manager, Vec3::X, GameMode::Survival);
+        movement.predict(&chunk_manager, Vec3::X * 2.0, GameMode::Survival);
+        movement.predict(&chunk_manager, Vec3::X * 3.0, GameMode::Survival);
+
+        let corrected = movement.reconcile(
+            &chunk_manager,
+            input.sequence,
+            Vec3::ZERO,
+            GameMode::Survival,
+        );
+
+        assert_eq!(corrected, Some(Vec3::X * 2.0));
+        assert_eq!(movement.position, corrected);
+        assert_eq!(movement.history.len(), 2);
+    }
+
+    #[test]
+    fn test_unknown_acknowledgement_is_ignored() {
+        let chunk_manager = ChunkManager::new();
+        let mut movement = PredictedMovement::default();
+
+        movement.predict(&chunk_manager, Vec3::ZERO, GameMode::Survival);
+        movement.reset(Vec3::Y);
+
+        assert_eq!(
+            movement.reconcile(&chunk_manager, 1, Vec3::ZERO, GameMode::Survival),
+            None
+        );
+        assert_eq!(movement.position, Some(Vec3::Y));
     }
 }
@@ -1,21 +1,25 @@
 use crate::prelude::*;
 
 #[cfg(feature = "skip_terrain")]
-const SPAWN_POINT: Vec3 = Vec3::new(0.0, 1.0, 0.0);
+const SPAWN_POINT: Option<Vec3> = Some(Vec3::new(0.0, 1.0, 0.0));
 
+/// Spawn points overriding the one sent by the server.
 #[cfg(all(not(feature = "skip_terrain"), not(feature = "lock_player")))]
-const SPAWN_POINT: Vec3 = Vec3::new(0.0, 64.0, 0.0);
+const SPAWN_POINT: Option<Vec3> = None;
 
 #[cfg(all(not(feature = "skip_terrain"), feature = "lock_player"))]
-const SPAWN_POINT: Vec3 = Vec3::new(128.0, 96.0, -128.0);
+const SPAWN_POINT: Option<Vec3> = Some(Vec3::new(128.0, 96.0, -128.0));
 
-pub fn setup_player_camera(mut commands: Commands) {
+pub fn setup_player_camera(
+    mut commands: Commands,
+    settings: Res<settings_resources::ClientSettings>,
+) {
     commands.spawn((
         Name::new("Player cam?"),
         Camera3d::default(),
         #[cfg(not(feature = "ortho_camera"))]
         Projection::Perspective(PerspectiveProjection {
-            fov: TAU / 5.0,
+            fov: settings.fov_radians(),
             ..default()
         }),
         #[cfg(feature = "ortho_camera")]
@@ -38,8 +42,19 @@ pub fn setup_controller_on_area_ready_system(
     mut commands: Commands,
     mut player_spawned: ResMut<player_resources::PlayerSpawned>,
     mut render_player: Query<&mut RenderPlayer>,
+    spawn_point: Res<player_resources::PlayerSpawnPoint>,
 ) {
     info!("Setting up controller");
+    let position = SPAWN_POINT.or(spawn_point.position).unwrap_or_default();
+    // Returning players keep looking where they did when they left
+    #[cfg(not(feature = "lock_player"))]
+    let (yaw, pitch) = match spawn_point.rotation {
+        Some(rotation) => {
+            let (yaw, pitch, _) = rotation.to_euler(EulerRot::YXZ);
+            (yaw, pitch)
+        }
+        None => (TAU * 5.0 / 12.0, -TAU / 20.0),
+    };
 
     let logical_entity = commands
         .spawn((
@@ -63,12 +78,12 @@ pub fn setup_controller_on_area_ready_system(
             AdditionalMassProperties::Mass(1.0),
             GravityScale(0.0),
             Ccd { enabled: true }, // Prevent clipping when going fast
-            Transform::from_translation(SPAWN_POINT),
+            Transform::from_translation(position),
             LogicalPlayer,
             #[cfg(not(feature = "lock_player"))]
             FpsControllerInput {
-                pitch: -TAU / 20.0,
-                yaw: TAU * 5.0 / 12.0,
+                pitch,
+                yaw,
                 ..default()
             },
             #[cfg(feature = "lock_player")]
@@ -96,22 +111,94 @@ pub fn setup_controller_on_area_ready_system(
     player_spawned.0 = true;
 }
 
-pub fn handle_controller_movement_system(
-    query: Query<(Entity, &FpsControllerInput, &Transform)>,
-    mut last_position: ResMut<player_resources::LastPlayerPosition>,
-    mut collider_events: EventWriter<collider_events::ColliderUpdateEvent>,
+/// Removes the player of the server that was left, a new one is set up after the next spawn.
+#[allow(clippy::too_many_arguments)]
+pub fn reset_player_system(
+    mut commands: Commands,
+    mut player_spawned: ResMut<player_resources::PlayerSpawned>,
+    mut predicted_movement: ResMut<player_resources::PredictedMovement>,
+    mut inventory: ResMut<player_resources::PlayerInventory>,
+    mut health: ResMut<player_resources::PlayerHealth>,
+    mut spawn_point: ResMut<player_resources::PlayerSpawnPoint>,
+    mut game_mode: ResMut<player_resources::PlayerGameMode>,
+    mut mining_progress: ResMut<player_resources::MiningProgress>,
+    players: Query<Entity, With<player_components::Player>>,
+) {
+    player_spawned.0 = false;
+    *predicted_movement = player_resources::PredictedMovement::default();
+    *inventory = player_resources::PlayerInventory::default();
+    *health = player_resources::PlayerHealth::default();
+    *spawn_point = player_resources::PlayerSpawnPoint::default();
+    *game_mode = player_resources::PlayerGameMode::default();
+    *mining_progress = player_resources::MiningProgress::default();
+
+    for entity in players.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Longest time between two jump presses that toggles flight in creative mode.
+const DOUBLE_JUMP_WINDOW: Duration = Duration::from_millis(300);
+
+/// Lets spectators fly through blocks, creative players start walking and toggle flight themselves.
+pub fn apply_game_mode_system(
+    game_mode: Res<player_resources::PlayerGameMode>,
+    mut move_mode: ResMut<player_resources::PlayerMoveMode>,
+) {
+    if game_mode.is_changed() {
+        *move_mode = player_resources::PlayerMoveMode::for_game_mode(game_mode.0);
+    }
+}
+
+pub fn apply_move_mode_system(
+    mut commands: Commands,
+    move_mode: Res<player_resources::PlayerMoveMode>,
+    mut query: Query<(Entity, &mut FpsController, Ref<player_components::Player>)>,
 ) {
-    for (_entity, _input, transform) in &mut query.iter() {
-        let controller_position = transform.translation;
-        if last_position.0.floor() != controller_position.floor() {
-            collider_events.send(collider_events::ColliderUpdateEvent {
-                grid_center_position: controller_position.floor().into(),
-            });
+    for (entity, mut controller, player) in query.iter_mut() {
+        if !move_mode.is_changed() && !player.is_added() {
+            continue;
         }
-        last_position.0 = controller_position.floor();
+
+        match move_mode.collides() {
+            true => commands.entity(entity).remove::<ColliderDisabled>(),
+            false => commands.entity(entity).insert(ColliderDisabled),
+        };
+        controller.move_mode = move_mode.controller_mode();
     }
 }
 
+pub fn toggle_flight_system(
+    actions: ActionInput,
+    time: Res<Time>,
+    game_mode: Res<player_resources::PlayerGameMode>,
+    mut move_mode: ResMut<player_resources::PlayerMoveMode>,
+    mut last_jump: Local<Option<Duration>>,
+) {
+    if game_mode.0 != GameMode::Creative || !actions.just_pressed(Action::Jump) {
+        return;
+    }
+
+    let now = time.elapsed();
+    let double_jump = last_jump.is_some_and(|last_jump| now - last_jump <= DOUBLE_JUMP_WINDOW);
+    if !double_jump {
+        *last_jump = Some(now);
+        return;
+    }
+
+    *last_jump = None;
+    *move_mode = match *move_mode {
+        player_resources::PlayerMoveMode::Flying => {
+            info!("Stopped flying");
+            player_resources::PlayerMoveMode::Walking
+        }
+        _ => {
+            info!("Started flying");
+            player_resources::PlayerMoveMode::Flying
+        }
+    };
+}
+
 pub fn activate_fps_controller_system(mut controller_query: Query<&mut FpsController>) {
     for mut controller in &mut controller_query.iter_mut() {
         controller.enable_input = true;
@@ -0,0 +1,136 @@
+use bevy_flair::style::components::NodeStyleSheet;
+
+use crate::prelude::*;
+
+pub fn setup_health_bar_system(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn((
+            Node::default(),
+            Name::new("health_wrapper"),
+            NodeStyleSheet::new(asset_server.load("gui.css")),
+            player_components::HealthBar,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((Node::default(), Name::new("health_bar")))
+                .with_child((
+                    Node::default(),
+                    Name::new("health_bar_fill"),
+                    player_components::HealthBarFill,
+                ));
+            parent.spawn((
+                Text::new(""),
+                Name::new("health_text"),
+                player_components::HealthText,
+            ));
+        });
+}
+
+pub fn update_health_bar_system(
+    health: Res<player_resources::PlayerHealth>,
+    game_mode: Res<player_resources::PlayerGameMode>,
+    mut bar_query: Query<&mut Visibility, With<player_components::HealthBar>>,
+    mut fill_query: Query<&mut Node, With<player_components::HealthBarFill>>,
+    mut text_query: Query<&mut Text, With<player_components::HealthText>>,
+) {
+    if !health.is_changed() && !game_mode.is_changed() {
+        return;
+    }
+
+    for mut visibility in bar_query.iter_mut() {
+        *visibility = match game_mode.0.takes_damage() {
+            true => Visibility::Inherited,
+            false => Visibility::Hidden,
+        };
+    }
+
+    for mut node in fill_query.iter_mut() {
+        node.width = Val::Percent(health.health as f32 / MAX_HEALTH as f32 * 100.0);
+    }
+    for mut text in text_query.iter_mut() {
+        text.0 = format!("{} / {}", health.health, MAX_HEALTH);
+    }
+}
+
+/// Switches to the death screen when the server reports a death and back after respawning.
+pub fn handle_player_death_system(
+    health: Res<player_resources::PlayerHealth>,
+    current_state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !health.is_changed() {
+        return;
+    }
+
+    match (health.death_cause, current_state.get()) {
+        (Some(_), GameState::Dead) | (None, GameState::Playing) => {}
+        (Some(_), _) => next_state.set(GameState::Dead),
+        (None, GameState::Dead) => next_state.set(GameState::Playing),
+        (None, _) => {}
+    }
+}
+
+pub fn setup_death_screen_system(
+    mut commands: Commands,
+    mut window_query: Query<&mut Window>,
+    mut controller_query: Query<&mut FpsController>,
+    health: Res<player_resources::PlayerHealth>,
+    asset_server: Res<AssetServer>,
+) {
+    if let Ok(mut window) = window_query.get_single_mut() {
+        window.cursor_options.grab_mode = CursorGrabMode::None;
+        window.cursor_options.visible = true;
+    }
+    for mut controller in &mut controller_query {
+        controller.enable_input = false;
+    }
+
+    let cause = health
+        .death_cause
+        .map(|cause| cause.death_message())
+        .unwrap_or_default();
+
+    commands
+        .spawn((
+            Node::default(),
+            Name::new("death_screen"),
+            NodeStyleSheet::new(asset_server.load("gui.css")),
+            player_components::DeathScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((Text::new("You died!"), Name::new("death_title")));
+            parent.spawn((Text::new(cause), Name::new("death_cause")));
+            parent
+                .spawn((
+                    Button,
+                    Node::default(),
+                    Name::new("respawn_button"),
+                    player_components::RespawnButton,
+                ))
+                .with_child((Text::new("Respawn"), Name::new("respawn_button_text")));
+        });
+}
+
+pub fn despawn_death_screen_system(
+    mut commands: Commands,
+    death_screens: Query<Entity, With<player_components::DeathScreen>>,
+) {
+    for entity in death_screens.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn handle_respawn_button_system(
+    mut client: ResMut<RenetClient>,
+    buttons: Query<&Interaction, (Changed<Interaction>, With<player_components::RespawnButton>)>,
+) {
+    for interaction in buttons.iter() {
+        if *interaction == Interaction::Pressed {
+            info!("Requesting respawn");
+            client.send_message(
+                DefaultChannel::ReliableOrdered,
+                bincode::serialize(&NetworkingMessage::RespawnRequest).unwrap(),
+            );
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use bevy_flair::style::components::{ClassList, NodeStyleSheet};
+
+use crate::prelude::*;
+
+pub fn setup_hotbar_system(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn((
+            Node::default(),
+            Name::new("hotbar_wrapper"),
+            NodeStyleSheet::new(asset_server.load("gui.css")),
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((Node::default(), Name::new("hotbar")))
+                .with_children(|parent| {
+                    for slot in 0..HOTBAR_SIZE {
+                        parent.spawn((
+                            Node::default(),
+                            Name::new("hotbar_slot"),
+                            ClassList::new(),
+                            Text::new(""),
+                            player_components::HotbarSlot(slot),
+                        ));
+                    }
+                });
+        });
+}
+
+pub fn handle_hotbar_selection_system(
+    mut client: ResMut<RenetClient>,
+    actions: ActionInput,
+    mut inventory: ResMut<player_resources::PlayerInventory>,
+) {
+    for (slot, action) in Action::HOTBAR.into_iter().enumerate() {
+        if actions.just_pressed(action) && inventory.0.selected != slot {
+            inventory.0.select(slot);
+            client.send_message(
+                DefaultChannel::ReliableOrdered,
+                bincode::serialize(&NetworkingMessage::SelectHotbarSlot(slot)).unwrap(),
+            );
+        }
+    }
+}
+
+pub fn update_hotbar_system(
+    inventory: Res<player_resources::PlayerInventory>,
+    mut slot_query: Query<(&player_components::HotbarSlot, &mut Text, &mut ClassList)>,
+) {
+    if !inventory.is_changed() {
+        return;
+    }
+
+    for (slot, mut text, mut classes) in slot_query.iter_mut() {
+        text.0 = match inventory.0.slots.get(slot.0).copied().flatten() {
+            Some(stack) if stack.count > 1 => format!("{} {}", stack.item.name(), stack.count),
+            Some(stack) => stack.item.name(),
+            None => String::new(),
+        };
+
+        match slot.0 == inventory.0.selected {
+            true => {
+                classes.remove_class("unselected");
+                classes.add_class("selected");
+            }
+            false => {
+                classes.remove_class("selected");
+                classes.add_class("unselected");
+            }
+        }
+    }
+}
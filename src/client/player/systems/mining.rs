@@ -0,0 +1,202 @@
+use bevy::image::ImageSampler;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+use crate::prelude::*;
+
+const CRACK_STAGES: usize = 10;
+const CRACK_TEXTURE_SIZE: usize = 16;
+const CRACK_BRANCHES: usize = 7;
+const CRACK_BRANCH_LENGTH: usize = 10;
+
+#[allow(clippy::too_many_arguments)]
+pub fn handle_mining_system(
+    mut client: ResMut<RenetClient>,
+    actions: ActionInput,
+    time: Res<Time>,
+    chunk_manager: Res<ChunkManager>,
+    block_selection: Res<player_resources::BlockSelection>,
+    inventory: Res<player_resources::PlayerInventory>,
+    game_mode: Res<player_resources::PlayerGameMode>,
+    mut mining: ResMut<player_resources::MiningProgress>,
+    mut block_update_events: EventWriter<terrain_events::BlockUpdateEvent>,
+) {
+    let target = block_selection
+        .position
+        .filter(|_| actions.pressed(Action::BreakBlock) && game_mode.0.can_interact());
+
+    if target != mining.target {
+        mining.target = target;
+        mining.progress = 0.0;
+        client.send_message(
+            DefaultChannel::ReliableOrdered,
+            bincode::serialize(&NetworkingMessage::MiningTarget(target)).unwrap(),
+        );
+    }
+
+    let Some(position) = target else {
+        return;
+    };
+
+    let block = chunk_manager.get_block(position).unwrap_or(BlockId::Air);
+    let Some(duration) = mining_duration(block, inventory.0.selected_item()) else {
+        return;
+    };
+
+    mining.progress += match duration.is_zero() || game_mode.0.breaks_instantly() {
+        true => 1.0,
+        false => time.delta_secs() / duration.as_secs_f32(),
+    };
+
+    if mining.progress >= 1.0 {
+        block_update_events.send(terrain_events::BlockUpdateEvent {
+            position,
+            block: BlockId::Air,
+            from_network: false,
+        });
+
+        // The server clears its mining target when it accepts the break
+        mining.target = None;
+        mining.progress = 0.0;
+    }
+}
+
+pub fn setup_crack_overlay_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+    highlight_query: Query<Entity, With<player_components::HighlightCube>>,
+) {
+    let stages: Vec<Handle<StandardMaterial>> = (1..=CRACK_STAGES)
+        .map(|stage| {
+            let texture = images.add(create_crack_image(stage as f32 / CRACK_STAGES as f32));
+
+            materials.add(StandardMaterial {
+                base_color_texture: Some(texture),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..default()
+            })
+        })
+        .collect();
+
+    let overlay = commands
+        .spawn((
+            Mesh3d(meshes.add(Cuboid::new(1.02, 1.02, 1.02))),
+            MeshMaterial3d(stages[0].clone()),
+            Transform::default(),
+            Visibility::Hidden,
+            player_components::CrackOverlay,
+        ))
+        .id();
+
+    if let Ok(highlight_cube) = highlight_query.get_single() {
+        commands.entity(highlight_cube).add_child(overlay);
+    }
+
+    commands.insert_resource(player_resources::CrackMaterials { stages });
+}
+
+pub fn update_crack_overlay_system(
+    mining: Res<player_resources::MiningProgress>,
+    crack_materials: Res<player_resources::CrackMaterials>,
+    mut overlay_query: Query<
+        (&mut MeshMaterial3d<StandardMaterial>, &mut Visibility),
+        With<player_components::CrackOverlay>,
+    >,
+) {
+    if !mining.is_changed() {
+        return;
+    }
+
+    for (mut material, mut visibility) in overlay_query.iter_mut() {
+        if mining.target.is_none() || mining.progress <= 0.0 {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+
+        let stage = ((mining.progress * CRACK_STAGES as f32) as usize).min(CRACK_STAGES - 1);
+        material.0 = crack_materials.stages[stage].clone();
+        *visibility = Visibility::Inherited;
+    }
+}
+
+/// Crack texture where `progress` of the crack branches, grown from the center, are visible.
+fn create_crack_image(progress: f32) -> Image {
+    let mut data = vec![0u8; CRACK_TEXTURE_SIZE * CRACK_TEXTURE_SIZE * 4];
+
+    for (index, order) in crack_order().into_iter().enumerate() {
+        if order <= progress {
+            data[index * 4..index * 4 + 4].copy_from_slice(&[20, 20, 20, 220]);
+        }
+    }
+
+    let mut image = Image::new(
+        Extent3d {
+            width: CRACK_TEXTURE_SIZE as u32,
+            height: CRACK_TEXTURE_SIZE as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    image.sampler = ImageSampler::nearest();
+    image
+}
+
+/// Progress at which each pixel cracks, computed from deterministic random walks so every
+/// stage extends the previous one.
+fn crack_order() -> Vec<f32> {
+    const DIRECTIONS: [(i32, i32); 8] = [
+        (1, 0),
+        (1, 1),
+        (0, 1),
+        (-1, 1),
+        (-1, 0),
+        (-1, -1),
+        (0, -1),
+        (1, -1),
+    ];
+
+    let size = CRACK_TEXTURE_SIZE as i32;
+    let mut order = vec![f32::INFINITY; CRACK_TEXTURE_SIZE * CRACK_TEXTURE_SIZE];
+    let mut seed: u32 = 0x2545_f491;
+
+    for branch in 0..CRACK_BRANCHES {
+        let (mut x, mut y) = (size / 2, size / 2);
+        let (direction_x, direction_y) = DIRECTIONS[branch * 3 % DIRECTIONS.len()];
+
+        for step in 0..CRACK_BRANCH_LENGTH {
+            let index = (x + y * size) as usize;
+            let pixel_order = (step + 1) as f32 / CRACK_BRANCH_LENGTH as f32;
+            order[index] = order[index].min(pixel_order);
+
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+
+            let jitter = (seed % 3) as i32 - 1;
+            x = (x + direction_x + jitter * direction_y).clamp(0, size - 1);
+            y = (y + direction_y + jitter * direction_x).clamp(0, size - 1);
+        }
+    }
+
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crack_stages_grow() {
+        let order = crack_order();
+        let cracked = |progress: f32| order.iter().filter(|order| **order <= progress).count();
+
+        assert_eq!(cracked(0.0), 0);
+        assert!(cracked(0.5) > 0);
+        assert!(cracked(1.0) > cracked(0.5));
+    }
+}
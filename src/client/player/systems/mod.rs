@@ -1,12 +1,16 @@
 pub mod controller;
-pub mod keyboard;
+pub mod health;
+pub mod hotbar;
+pub mod mining;
 pub mod mouse;
 pub mod network;
 pub mod selection;
 pub mod terrain;
 
 pub use controller::*;
-pub use keyboard::*;
+pub use health::*;
+pub use hotbar::*;
+pub use mining::*;
 pub use mouse::*;
 pub use network::*;
 pub use selection::*;
@@ -2,23 +2,80 @@ use crate::prelude::*;
 
 pub fn broadcast_player_attributes_system(
     mut client: ResMut<RenetClient>,
-    query: Query<(&player_components::Player, &Transform)>,
-    camera_query: Query<(&Camera3d, &player_components::PlayerCamera, &Transform)>,
+    mut prediction: ResMut<player_resources::PredictedMovement>,
+    chunk_manager: Res<ChunkManager>,
+    game_mode: Res<player_resources::PlayerGameMode>,
+    mut query: Query<&mut Transform, With<player_components::Player>>,
+    camera_query: Query<
+        &Transform,
+        (
+            With<player_components::PlayerCamera>,
+            Without<player_components::Player>,
+        ),
+    >,
 ) {
-    if query.is_empty() {
+    let Ok(mut transform) = query.get_single_mut() else {
         return;
-    }
+    };
+    let camera_transform = camera_query.single();
 
-    let (_, transform) = query.single();
-    let (_, _, camera_transform) = camera_query.single();
+    let (input, position) = prediction.predict(&chunk_manager, transform.translation, game_mode.0);
+    if transform.translation != position {
+        transform.translation = position;
+    }
 
-    let player_state = PlayerState {
-        position: transform.translation,
+    let state = PlayerState {
+        position,
         rotation: camera_transform.rotation,
     };
 
     client.send_message(
-        DefaultChannel::ReliableUnordered,
-        bincode::serialize(&NetworkingMessage::PlayerUpdate(player_state)).unwrap(),
+        DefaultChannel::ReliableOrdered,
+        bincode::serialize(&NetworkingMessage::PlayerUpdate { input, state }).unwrap(),
     );
 }
+
+pub fn handle_player_correction_events_system(
+    mut correction_events: EventReader<player_events::PlayerCorrectionEvent>,
+    mut prediction: ResMut<player_resources::PredictedMovement>,
+    mut query: Query<(&mut Transform, &mut Velocity), With<player_components::Player>>,
+) {
+    let Some(correction) = correction_events.read().last() else {
+        return;
+    };
+
+    prediction.reset(correction.position);
+
+    for (mut transform, mut velocity) in query.iter_mut() {
+        warn!(
+            "Server corrected player position to {:?}",
+            correction.position
+        );
+        transform.translation = correction.position;
+        *velocity = Velocity::zero();
+    }
+}
+
+pub fn reconcile_player_movement_system(
+    mut ack_events: EventReader<player_events::PlayerMovementAckEvent>,
+    mut prediction: ResMut<player_resources::PredictedMovement>,
+    chunk_manager: Res<ChunkManager>,
+    game_mode: Res<player_resources::PlayerGameMode>,
+    mut query: Query<&mut Transform, With<player_components::Player>>,
+) {
+    for ack in ack_events.read() {
+        let Some(position) =
+            prediction.reconcile(&chunk_manager, ack.sequence, ack.position, game_mode.0)
+        else {
+            continue;
+        };
+
+        debug!(
+            "Replayed unacknowledged inputs, moved player to {:?}",
+            position
+        );
+        for mut transform in query.iter_mut() {
+            transform.translation = position;
+        }
+    }
+}
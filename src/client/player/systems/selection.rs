@@ -1,6 +1,5 @@
 use crate::prelude::*;
 
-const RAY_DIST: Vec3 = Vec3::new(0.0, 0.0, -20.0);
 const HIGHLIGHT_CUBE_ORIGIN: Vec3 = Vec3::new(0.0, 2.0, 0.0);
 
 pub fn setup_highlight_cube_system(
@@ -19,56 +18,40 @@ pub fn setup_highlight_cube_system(
         .insert(player_components::HighlightCube);
 }
 
-#[allow(clippy::type_complexity)]
 pub fn raycast_system(
-    mut raycast: Raycast,
+    chunk_manager: Res<ChunkManager>,
     #[cfg(feature = "raycast_debug")] mut gizmos: Gizmos,
     raycast_origin: Query<&Transform, With<player_components::PlayerCamera>>,
     mut selection_query: Query<
-        (&mut Transform, &player_components::HighlightCube),
+        &mut Transform,
         (
+            With<player_components::HighlightCube>,
             Without<player_components::PlayerCamera>,
-            Without<player_components::Raycastable>,
         ),
     >,
-    raycastable_query: Query<&Transform, With<player_components::Raycastable>>,
     mut block_selection: ResMut<player_resources::BlockSelection>,
 ) {
     let camera_transform = raycast_origin.single();
-    let filter = |entity| raycastable_query.get(entity).is_ok();
+    let origin = camera_transform.translation;
+    let direction = camera_transform.forward();
 
-    let pos = camera_transform.translation;
-    let dir = camera_transform.rotation.mul_vec3(Vec3::Z).normalize();
-    let dir = dir * RAY_DIST.z;
-
-    let ray = Ray3d::new(pos, Dir3::new(dir).expect("Ray can be cast"));
-    let settings = RaycastSettings {
-        filter: &filter,
-        ..default()
-    };
+    let hit = raycast_blocks(&chunk_manager, origin, *direction, MAX_REACH);
 
     #[cfg(feature = "raycast_debug")]
-    let intersections = raycast.debug_cast_ray(ray, &settings, &mut gizmos);
-
-    #[cfg(not(feature = "raycast_debug"))]
-    let intersections = raycast.cast_ray(ray, &settings);
-
-    let (mut highlight_transform, _) = selection_query.single_mut();
-    let hover_position = intersections
-        .first()
-        .map(|(_, intersection)| (intersection.position() - intersection.normal() * 0.5).floor());
-
-    block_selection.position = hover_position;
-    block_selection.normal = intersections
-        .first()
-        .map(|(_, intersection)| intersection.normal());
-
-    if hover_position.is_none() {
-        highlight_transform.translation = HIGHLIGHT_CUBE_ORIGIN;
-        return;
-    }
-
-    highlight_transform.translation = hover_position.unwrap() + 0.5;
+    gizmos.line(
+        origin,
+        origin + direction * hit.map_or(MAX_REACH, |hit| hit.distance),
+        Color::srgb(1.0, 0.0, 0.0),
+    );
+
+    block_selection.position = hit.map(|hit| hit.position);
+    block_selection.normal = hit.map(|hit| hit.normal);
+
+    let mut highlight_transform = selection_query.single_mut();
+    highlight_transform.translation = match hit {
+        Some(hit) => hit.position + 0.5,
+        None => HIGHLIGHT_CUBE_ORIGIN,
+    };
 }
 
 #[cfg(test)]
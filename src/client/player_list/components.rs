@@ -0,0 +1,10 @@
+use crate::prelude::*;
+
+#[derive(Component)]
+pub struct PlayerListPanel;
+
+#[derive(Component)]
+pub struct PlayerListTitle;
+
+#[derive(Component)]
+pub struct PlayerListRows;
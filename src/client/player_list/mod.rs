@@ -0,0 +1,26 @@
+pub mod components;
+pub mod resources;
+pub mod systems;
+
+use crate::prelude::*;
+
+/// Shows the players the server lists while the player list key is held.
+pub struct PlayerListPlugin;
+
+impl Plugin for PlayerListPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(player_list_resources::PlayerList::default());
+        app.add_systems(Startup, player_list_systems::setup_player_list_system);
+        app.add_systems(
+            Update,
+            (
+                player_list_systems::toggle_player_list_system,
+                player_list_systems::update_player_list_system,
+            ),
+        );
+        app.add_systems(
+            OnEnter(GameState::MainMenu),
+            player_list_systems::reset_player_list_system,
+        );
+    }
+}
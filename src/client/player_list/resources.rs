@@ -0,0 +1,10 @@
+use crate::prelude::*;
+
+/// Pings above this many milliseconds are highlighted in the player list.
+pub const HIGH_PING: u32 = 250;
+
+/// Connected players as last sent by the server.
+#[derive(Resource, Default)]
+pub struct PlayerList {
+    pub entries: Vec<PlayerListEntry>,
+}
@@ -0,0 +1,105 @@
+use bevy_flair::style::components::{ClassList, NodeStyleSheet};
+
+use crate::prelude::*;
+
+pub fn setup_player_list_system(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn((
+            Node::default(),
+            Name::new("player_list_wrapper"),
+            NodeStyleSheet::new(asset_server.load("gui.css")),
+            Visibility::Hidden,
+            GlobalZIndex(1),
+            player_list_components::PlayerListPanel,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((Node::default(), Name::new("player_list")))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new("Players"),
+                        Name::new("player_list_title"),
+                        player_list_components::PlayerListTitle,
+                    ));
+                    parent.spawn((
+                        Node::default(),
+                        Name::new("player_list_rows"),
+                        player_list_components::PlayerListRows,
+                    ));
+                });
+        });
+}
+
+pub fn toggle_player_list_system(
+    actions: ActionInput,
+    current_state: Res<State<GameState>>,
+    mut panel_query: Query<&mut Visibility, With<player_list_components::PlayerListPanel>>,
+) {
+    let shown = actions.pressed(Action::ShowPlayerList)
+        && matches!(
+            current_state.get(),
+            GameState::Playing | GameState::Debugging | GameState::Dead
+        );
+
+    for mut visibility in panel_query.iter_mut() {
+        let target = match shown {
+            true => Visibility::Inherited,
+            false => Visibility::Hidden,
+        };
+
+        if *visibility != target {
+            *visibility = target;
+        }
+    }
+}
+
+pub fn update_player_list_system(
+    mut commands: Commands,
+    player_list: Res<player_list_resources::PlayerList>,
+    mut title_query: Query<&mut Text, With<player_list_components::PlayerListTitle>>,
+    rows_query: Query<Entity, With<player_list_components::PlayerListRows>>,
+) {
+    if !player_list.is_changed() {
+        return;
+    }
+
+    for mut title in title_query.iter_mut() {
+        title.0 = format!("Players ({})", player_list.entries.len());
+    }
+
+    for rows in rows_query.iter() {
+        commands
+            .entity(rows)
+            .despawn_descendants()
+            .with_children(|parent| {
+                for entry in player_list.entries.iter() {
+                    let mut ping_classes = ClassList::new();
+                    if entry.ping > player_list_resources::HIGH_PING {
+                        ping_classes.add_class("high_ping");
+                    }
+
+                    parent
+                        .spawn((Node::default(), Name::new("player_list_row")))
+                        .with_children(|parent| {
+                            parent.spawn((
+                                Text::new(entry.name.clone()),
+                                Name::new("player_list_name"),
+                            ));
+                            parent.spawn((
+                                Text::new(entry.game_mode.name()),
+                                Name::new("player_list_game_mode"),
+                            ));
+                            parent.spawn((
+                                Text::new(format!("{} ms", entry.ping)),
+                                Name::new("player_list_ping"),
+                                ping_classes,
+                            ));
+                        });
+                }
+            });
+    }
+}
+
+pub fn reset_player_list_system(mut player_list: ResMut<player_list_resources::PlayerList>) {
+    player_list.entries.clear();
+}
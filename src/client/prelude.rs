@@ -1,5 +1,5 @@
 // std crates
-pub use std::collections::HashMap;
+pub use std::collections::{HashMap, VecDeque};
 pub use std::f32::consts::*;
 pub use std::{net::*, time::*};
 
@@ -9,7 +9,7 @@ pub use bevy::diagnostic::*;
 pub use bevy::ecs::{event::*, query::*, system::*};
 pub use bevy::gizmos::gizmos::*;
 pub use bevy::input::{keyboard::*, mouse::*, ButtonInput};
-pub use bevy::math::{primitives::Cuboid, EulerRot, Quat, Ray3d, Vec3};
+pub use bevy::math::{primitives::Cuboid, EulerRot, Quat, Vec3};
 pub use bevy::pbr::*;
 pub use bevy::prelude::*;
 pub use bevy::render::{camera::*, mesh::Mesh};
@@ -20,8 +20,6 @@ pub use bevy_fps_controller::controller::FpsController;
 pub use bevy_fps_controller::controller::FpsControllerPlugin;
 pub use bevy_fps_controller::controller::*;
 
-pub use bevy_mod_raycast::immediate::*;
-
 pub use bevy_rapier3d::geometry::Collider;
 pub use bevy_rapier3d::{dynamics::*, geometry::*};
 pub use bevy_rapier3d::{plugin::*, render::RapierDebugRenderPlugin};
@@ -48,10 +46,20 @@ pub use crate::states::GameState;
 pub use lib::*;
 pub use rsmc as lib;
 
-pub use crate::collider::components as collider_components;
-pub use crate::collider::events as collider_events;
-pub use crate::collider::systems as collider_systems;
+pub use crate::actions::resources as actions_resources;
+pub use crate::actions::resources::{Action, ActionInput};
+pub use crate::actions::systems as actions_systems;
+
+pub use crate::mobs::components as mobs_components;
+pub use crate::mobs::resources as mobs_resources;
+pub use crate::mobs::systems as mobs_systems;
 
+pub use crate::menu::components as menu_components;
+pub use crate::menu::events as menu_events;
+pub use crate::menu::resources as menu_resources;
+pub use crate::menu::systems as menu_systems;
+
+pub use crate::networking::resources as networking_resources;
 pub use crate::networking::systems as networking_systems;
 pub use crate::networking::NetworkingPlugin;
 
@@ -60,16 +68,32 @@ pub use crate::player::events as player_events;
 pub use crate::player::resources as player_resources;
 pub use crate::player::systems as player_systems;
 
+pub use crate::player_list::components as player_list_components;
+pub use crate::player_list::resources as player_list_resources;
+pub use crate::player_list::systems as player_list_systems;
+
 pub use crate::remote_player::components as remote_player_components;
 pub use crate::remote_player::events as remote_player_events;
+pub use crate::remote_player::resources as remote_player_resources;
 pub use crate::remote_player::systems as remote_player_systems;
 
+pub use crate::settings::resources as settings_resources;
+pub use crate::settings::systems as settings_systems;
+
 pub use crate::terrain::components as terrain_components;
 pub use crate::terrain::events as terrain_events;
 pub use crate::terrain::resources as terrain_resources;
 pub use crate::terrain::systems as terrain_systems;
 pub use crate::terrain::util as terrain_util;
 
+pub use crate::items::resources as items_resources;
+pub use crate::items::systems as items_systems;
+
+pub use crate::replication::events as replication_events;
+pub use crate::replication::resources as replication_resources;
+pub use crate::replication::systems as replication_systems;
+pub use crate::replication::{AppReplicationExt, ReplicationSet};
+
 pub use crate::gui::components as gui_components;
 pub use crate::gui::events as gui_events;
 pub use crate::gui::systems as gui_systems;
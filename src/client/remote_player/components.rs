@@ -1,9 +1,176 @@
 use crate::prelude::*;
 
+/// Number of received states kept per remote player.
+const MAX_BUFFERED_STATES: usize = 32;
+
 #[derive(Component)]
 pub struct RemotePlayer {
     pub client_id: ClientId,
 }
 
-#[derive(Default, Reflect, GizmoConfigGroup)]
-pub struct RemotePlayerGizmos;
+/// Head pitch and walk cycle of the avatar, applied to its body parts.
+#[derive(Component, Debug, Default)]
+pub struct RemotePlayerPose {
+    pub pitch: f32,
+    pub walk_phase: f32,
+    /// Current limb angle in radians.
+    pub swing: f32,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemotePlayerPart {
+    Head,
+    LeftArm,
+    RightArm,
+    LeftLeg,
+    RightLeg,
+}
+
+/// Material coloured with the player profile colour.
+#[derive(Component)]
+pub struct RemotePlayerMaterial(pub Handle<StandardMaterial>);
+
+/// UI node following the remote player on screen.
+#[derive(Component)]
+pub struct RemotePlayerNameplate {
+    pub player: Entity,
+}
+
+#[derive(Component)]
+pub struct RemotePlayerDebugOverlay;
+
+#[derive(Component)]
+pub struct RemotePlayerDebugText;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimedPlayerState {
+    /// Server time in seconds.
+    pub time: f64,
+    pub position: Vec3,
+    pub rotation: Quat,
+}
+
+/// States received for a remote player, sampled at a delayed render time.
+#[derive(Component, Debug, Default)]
+pub struct RemotePlayerStateBuffer {
+    pub states: VecDeque<TimedPlayerState>,
+    /// Velocity at the last sampled time.
+    pub velocity: Vec3,
+    pub extrapolating: bool,
+    received_velocity: Vec3,
+}
+
+impl RemotePlayerStateBuffer {
+    pub fn push(&mut self, state: TimedPlayerState) {
+        if let Some(newest) = self.states.back() {
+            if state.time <= newest.time {
+                return;
+            }
+
+            self.received_velocity =
+                (state.position - newest.position) / (state.time - newest.time) as f32;
+        }
+
+        self.states.push_back(state);
+        while self.states.len() > MAX_BUFFERED_STATES {
+            self.states.pop_front();
+        }
+    }
+
+    pub fn newest_time(&self) -> Option<f64> {
+        self.states.back().map(|state| state.time)
+    }
+
+    /// Interpolates between the states around `render_time`, or continues the last velocity for
+    /// at most `max_extrapolation` seconds when there is no newer state yet.
+    pub fn sample(&mut self, render_time: f64, max_extrapolation: f64) -> Option<(Vec3, Quat)> {
+        // Keep one state at or before the render time as start of the interpolation
+        while self.states.len() >= 2 && self.states[1].time <= render_time {
+            self.states.pop_front();
+        }
+
+        let from = *self.states.front()?;
+
+        if render_time <= from.time {
+            self.velocity = Vec3::ZERO;
+            self.extrapolating = false;
+            return Some((from.position, from.rotation));
+        }
+
+        if let Some(to) = self.states.get(1) {
+            let factor = ((render_time - from.time) / (to.time - from.time)) as f32;
+
+            self.velocity = (to.position - from.position) / (to.time - from.time) as f32;
+            self.extrapolating = false;
+            return Some((
+                from.position.lerp(to.position, factor),
+                from.rotation.slerp(to.rotation, factor),
+            ));
+        }
+
+        let extrapolation = (render_time - from.time).min(max_extrapolation) as f32;
+        let moving = render_time - from.time < max_extrapolation;
+
+        self.velocity = match moving {
+            true => self.received_velocity,
+            false => Vec3::ZERO,
+        };
+        self.extrapolating = true;
+        Some((
+            from.position + self.received_velocity * extrapolation,
+            from.rotation,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(time: f64, x: f32) -> TimedPlayerState {
+        TimedPlayerState {
+            time,
+            position: Vec3::new(x, 0.0, 0.0),
+            rotation: Quat::IDENTITY,
+        }
+    }
+
+    #[test]
+    fn test_sample_interpolates_between_states() {
+        let mut buffer = RemotePlayerStateBuffer::default();
+        buffer.push(state(1.0, 0.0));
+        buffer.push(state(1.1, 1.0));
+        buffer.push(state(1.2, 3.0));
+
+        let (position, _) = buffer.sample(1.15, 0.25).unwrap();
+
+        assert!((position.x - 2.0).abs() < 1e-4);
+        assert!(!buffer.extrapolating);
+        assert_eq!(buffer.states.len(), 2);
+    }
+
+    #[test]
+    fn test_sample_extrapolates_up_to_limit() {
+        let mut buffer = RemotePlayerStateBuffer::default();
+        buffer.push(state(1.0, 0.0));
+        buffer.push(state(1.1, 1.0));
+
+        let (position, _) = buffer.sample(1.2, 0.25).unwrap();
+        assert!((position.x - 2.0).abs() < 1e-4);
+        assert!(buffer.extrapolating);
+
+        let (position, _) = buffer.sample(5.0, 0.25).unwrap();
+        assert!((position.x - 3.5).abs() < 1e-4);
+        assert_eq!(buffer.velocity, Vec3::ZERO);
+    }
+
+    #[test]
+    fn test_push_ignores_outdated_states() {
+        let mut buffer = RemotePlayerStateBuffer::default();
+        buffer.push(state(2.0, 0.0));
+        buffer.push(state(1.0, 5.0));
+
+        assert_eq!(buffer.states.len(), 1);
+        assert_eq!(buffer.newest_time(), Some(2.0));
+    }
+}
@@ -13,5 +13,7 @@ pub struct RemotePlayerDespawnedEvent {
 
 #[derive(Event)]
 pub struct RemotePlayerSyncEvent {
+    /// Server time of the states in seconds.
+    pub server_time: f64,
     pub players: HashMap<ClientId, PlayerState>,
 }
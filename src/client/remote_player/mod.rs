@@ -1,5 +1,6 @@
 pub mod components;
 pub mod events;
+pub mod resources;
 pub mod systems;
 
 use crate::prelude::*;
@@ -9,17 +10,45 @@ pub struct RemotePlayerPlugin;
 impl Plugin for RemotePlayerPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<events::RemotePlayerSpawnedEvent>();
-        app.init_gizmo_group::<remote_player_components::RemotePlayerGizmos>();
         app.add_event::<events::RemotePlayerDespawnedEvent>();
         app.add_event::<events::RemotePlayerSyncEvent>();
+        app.insert_resource(remote_player_resources::RemotePlayerSettings::default());
+        app.insert_resource(remote_player_resources::ServerClock::default());
+        app.insert_resource(remote_player_resources::RemotePlayerProfiles::default());
+        app.init_resource::<remote_player_resources::AvatarAssets>();
         app.add_systems(
             Update,
             (
                 remote_player_systems::spawn_remote_player_system,
-                remote_player_systems::update_remote_player_system,
+                (
+                    remote_player_systems::update_remote_player_system,
+                    remote_player_systems::interpolate_remote_players_system,
+                    remote_player_systems::animate_remote_players_system,
+                )
+                    .chain(),
                 remote_player_systems::despawn_remote_player_system,
-                remote_player_systems::draw_gizmos,
+                remote_player_systems::update_remote_player_profile_system,
+                remote_player_systems::update_nameplate_position_system,
             ),
         );
+
+        app.add_systems(
+            OnEnter(GameState::MainMenu),
+            remote_player_systems::reset_remote_players_system,
+        );
+
+        app.add_systems(
+            OnEnter(GameState::Debugging),
+            remote_player_systems::spawn_debug_overlay_system,
+        );
+        app.add_systems(
+            OnExit(GameState::Debugging),
+            remote_player_systems::despawn_debug_overlay_system,
+        );
+        app.add_systems(
+            Update,
+            remote_player_systems::update_debug_overlay_system
+                .run_if(in_state(GameState::Debugging)),
+        );
     }
 }
@@ -0,0 +1,117 @@
+use crate::prelude::*;
+
+#[derive(Resource, Debug, Clone)]
+pub struct RemotePlayerSettings {
+    /// How far behind the newest server state remote players are rendered.
+    pub interpolation_delay: Duration,
+    /// How long the last known velocity is continued when states arrive late.
+    pub max_extrapolation: Duration,
+}
+
+impl Default for RemotePlayerSettings {
+    fn default() -> Self {
+        Self {
+            interpolation_delay: Duration::from_millis(100),
+            max_extrapolation: Duration::from_millis(250),
+        }
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct RemotePlayerProfiles {
+    pub profiles: HashMap<ClientId, PlayerProfile>,
+}
+
+impl RemotePlayerProfiles {
+    pub fn name(&self, client_id: ClientId) -> String {
+        match self.profiles.get(&client_id) {
+            Some(profile) => profile.name.clone(),
+            None => client_id.to_string(),
+        }
+    }
+
+    pub fn color(&self, client_id: ClientId) -> Color {
+        let [red, green, blue] = self
+            .profiles
+            .get(&client_id)
+            .map(|profile| profile.color)
+            .unwrap_or(PlayerProfile::default().color);
+
+        Color::srgb_u8(red, green, blue)
+    }
+}
+
+/// Meshes and materials shared by all avatars.
+#[derive(Resource)]
+pub struct AvatarAssets {
+    pub head: Handle<Mesh>,
+    pub torso: Handle<Mesh>,
+    pub limb: Handle<Mesh>,
+    pub skin: Handle<StandardMaterial>,
+    pub legs: Handle<StandardMaterial>,
+}
+
+impl FromWorld for AvatarAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        let head = meshes.add(Cuboid::new(0.5, 0.5, 0.5));
+        let torso = meshes.add(Cuboid::new(0.5, 0.75, 0.25));
+        let limb = meshes.add(Cuboid::new(0.25, 0.75, 0.25));
+
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let skin = materials.add(Color::srgb(0.8, 0.7, 0.6));
+        let legs = materials.add(Color::srgb(0.2, 0.2, 0.35));
+
+        Self {
+            head,
+            torso,
+            limb,
+            skin,
+            legs,
+        }
+    }
+}
+
+/// Estimated offset between the server clock and the local clock.
+#[derive(Resource, Debug, Default)]
+pub struct ServerClock {
+    pub offset: Option<f64>,
+}
+
+impl ServerClock {
+    /// Rate at which the offset follows samples that are lower than the estimate.
+    const DRIFT_CORRECTION: f64 = 0.01;
+
+    /// Samples arriving with less network delay give a larger offset, so the estimate
+    /// jumps up immediately and only slowly drifts down to account for clock drift.
+    pub fn observe(&mut self, server_time: f64, local_time: f64) {
+        let sample = server_time - local_time;
+
+        self.offset = Some(match self.offset {
+            Some(offset) if sample < offset => offset + (sample - offset) * Self::DRIFT_CORRECTION,
+            _ => sample,
+        });
+    }
+
+    pub fn server_time(&self, local_time: f64) -> Option<f64> {
+        self.offset.map(|offset| local_time + offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_clock_follows_least_delayed_sample() {
+        let mut clock = ServerClock::default();
+        assert_eq!(clock.server_time(1.0), None);
+
+        clock.observe(10.0, 1.0);
+        clock.observe(10.5, 1.6);
+        assert!((clock.server_time(2.0).unwrap() - 10.999).abs() < 1e-9);
+
+        clock.observe(12.0, 2.5);
+        assert_eq!(clock.server_time(3.0), Some(12.5));
+    }
+}
@@ -1,24 +1,116 @@
+use bevy_flair::style::components::NodeStyleSheet;
+
 use crate::prelude::*;
 
+/// Height of the shoulders and hips above the feet, limbs rotate around these points.
+const SHOULDER_HEIGHT: f32 = 1.5;
+const HIP_HEIGHT: f32 = 0.75;
+const LIMB_LENGTH: f32 = 0.75;
+
+const NAMEPLATE_OFFSET: Vec3 = Vec3::new(0.0, 2.3, 0.0);
+const NAMEPLATE_MAX_DISTANCE: f32 = 64.0;
+
+/// Horizontal speed at which limbs swing the furthest.
+const WALK_SPEED: f32 = 4.0;
+const STRIDE_LENGTH: f32 = 1.5;
+const MAX_SWING: f32 = 0.8;
+
 pub fn spawn_remote_player_system(
     mut commands: Commands,
     mut spawn_events: EventReader<remote_player_events::RemotePlayerSpawnedEvent>,
-    mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    avatar_assets: Res<remote_player_resources::AvatarAssets>,
+    profiles: Res<remote_player_resources::RemotePlayerProfiles>,
+    asset_server: Res<AssetServer>,
 ) {
     for event in spawn_events.read() {
         let client_id = event.client_id;
+        let body_material = materials.add(profiles.color(client_id));
 
-        let material = materials.add(StandardMaterial {
-            base_color: Color::srgb(0.8, 0.7, 0.6),
-            ..default()
-        });
+        let player = commands
+            .spawn((
+                Transform::from_translation(event.position),
+                Visibility::default(),
+                Name::new(format!("remote_player_{}", client_id)),
+                remote_player_components::RemotePlayer { client_id },
+                remote_player_components::RemotePlayerStateBuffer::default(),
+                remote_player_components::RemotePlayerPose::default(),
+                remote_player_components::RemotePlayerMaterial(body_material.clone()),
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    Mesh3d(avatar_assets.torso.clone()),
+                    MeshMaterial3d(body_material.clone()),
+                    Transform::from_xyz(0.0, HIP_HEIGHT + LIMB_LENGTH / 2.0, 0.0),
+                ));
 
-        commands.spawn((
-            bevy::prelude::Mesh3d(meshes.add(Cuboid::new(0.5, 0.5, 0.5))),
-            MeshMaterial3d(material),
-            remote_player_components::RemotePlayer { client_id },
-        ));
+                parent
+                    .spawn((
+                        Transform::from_xyz(0.0, SHOULDER_HEIGHT, 0.0),
+                        Visibility::default(),
+                        remote_player_components::RemotePlayerPart::Head,
+                    ))
+                    .with_child((
+                        Mesh3d(avatar_assets.head.clone()),
+                        MeshMaterial3d(avatar_assets.skin.clone()),
+                        Transform::from_xyz(0.0, 0.25, 0.0),
+                    ));
+
+                let limbs = [
+                    (
+                        remote_player_components::RemotePlayerPart::LeftArm,
+                        -0.375,
+                        SHOULDER_HEIGHT,
+                        body_material.clone(),
+                    ),
+                    (
+                        remote_player_components::RemotePlayerPart::RightArm,
+                        0.375,
+                        SHOULDER_HEIGHT,
+                        body_material.clone(),
+                    ),
+                    (
+                        remote_player_components::RemotePlayerPart::LeftLeg,
+                        -0.125,
+                        HIP_HEIGHT,
+                        avatar_assets.legs.clone(),
+                    ),
+                    (
+                        remote_player_components::RemotePlayerPart::RightLeg,
+                        0.125,
+                        HIP_HEIGHT,
+                        avatar_assets.legs.clone(),
+                    ),
+                ];
+
+                for (part, x, height, material) in limbs {
+                    parent
+                        .spawn((
+                            Transform::from_xyz(x, height, 0.0),
+                            Visibility::default(),
+                            part,
+                        ))
+                        .with_child((
+                            Mesh3d(avatar_assets.limb.clone()),
+                            MeshMaterial3d(material),
+                            Transform::from_xyz(0.0, -LIMB_LENGTH / 2.0, 0.0),
+                        ));
+                }
+            })
+            .id();
+
+        commands
+            .spawn((
+                Node::default(),
+                Name::new("nameplate"),
+                NodeStyleSheet::new(asset_server.load("gui.css")),
+                Visibility::Hidden,
+                remote_player_components::RemotePlayerNameplate { player },
+            ))
+            .with_child((
+                Text::new(profiles.name(client_id)),
+                Name::new("nameplate_text"),
+            ));
     }
 }
 
@@ -26,53 +118,286 @@ pub fn despawn_remote_player_system(
     mut commands: Commands,
     mut despawn_events: EventReader<remote_player_events::RemotePlayerDespawnedEvent>,
     query: Query<(Entity, &remote_player_components::RemotePlayer)>,
+    nameplates: Query<(Entity, &remote_player_components::RemotePlayerNameplate)>,
 ) {
     for event in despawn_events.read() {
         for (entity, remote_player) in query.iter() {
-            if remote_player.client_id == event.client_id {
-                commands.entity(entity).despawn();
+            if remote_player.client_id != event.client_id {
+                continue;
+            }
+
+            commands.entity(entity).despawn_recursive();
+            for (nameplate_entity, nameplate) in nameplates.iter() {
+                if nameplate.player == entity {
+                    commands.entity(nameplate_entity).despawn_recursive();
+                }
             }
         }
     }
 }
 
+/// Removes the players of the server that was left.
+pub fn reset_remote_players_system(
+    mut commands: Commands,
+    mut profiles: ResMut<remote_player_resources::RemotePlayerProfiles>,
+    mut server_clock: ResMut<remote_player_resources::ServerClock>,
+    players: Query<Entity, With<remote_player_components::RemotePlayer>>,
+    nameplates: Query<Entity, With<remote_player_components::RemotePlayerNameplate>>,
+) {
+    profiles.profiles.clear();
+    *server_clock = remote_player_resources::ServerClock::default();
+
+    for entity in players.iter().chain(nameplates.iter()) {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
 pub fn update_remote_player_system(
+    time: Res<Time>,
+    mut server_clock: ResMut<remote_player_resources::ServerClock>,
     mut sync_events: EventReader<remote_player_events::RemotePlayerSyncEvent>,
     mut spawn_events: EventWriter<remote_player_events::RemotePlayerSpawnedEvent>,
-    mut query: Query<(&remote_player_components::RemotePlayer, &mut Transform)>,
+    mut query: Query<(
+        &remote_player_components::RemotePlayer,
+        &mut remote_player_components::RemotePlayerStateBuffer,
+    )>,
 ) {
-    let latest_event = sync_events.read().last();
+    let mut spawned_players = Vec::new();
+
+    for event in sync_events.read() {
+        server_clock.observe(event.server_time, time.elapsed_secs_f64());
 
-    if let Some(event) = latest_event {
         for (client_id, player_state) in event.players.iter() {
-            let mut player_exists = false;
-            for (remote_player, mut transform) in query.iter_mut() {
-                if remote_player.client_id == *client_id {
-                    player_exists = true;
-                    transform.translation = player_state.position + Vec3::new(0.0, 1.55, 0.0);
-                    transform.rotation = player_state.rotation;
+            let state = remote_player_components::TimedPlayerState {
+                time: event.server_time,
+                position: player_state.position,
+                rotation: player_state.rotation,
+            };
+
+            let remote_player = query
+                .iter_mut()
+                .find(|(remote_player, _)| remote_player.client_id == *client_id);
+
+            match remote_player {
+                Some((_, mut buffer)) => buffer.push(state),
+                None if !spawned_players.contains(client_id) => {
+                    spawned_players.push(*client_id);
+                    spawn_events.send(remote_player_events::RemotePlayerSpawnedEvent {
+                        client_id: *client_id,
+                        position: player_state.position,
+                    });
                 }
+                None => {}
             }
+        }
+    }
+}
+
+pub fn interpolate_remote_players_system(
+    time: Res<Time>,
+    server_clock: Res<remote_player_resources::ServerClock>,
+    settings: Res<remote_player_resources::RemotePlayerSettings>,
+    mut query: Query<
+        (
+            &mut remote_player_components::RemotePlayerStateBuffer,
+            &mut remote_player_components::RemotePlayerPose,
+            &mut Transform,
+        ),
+        With<remote_player_components::RemotePlayer>,
+    >,
+) {
+    let Some(server_time) = server_clock.server_time(time.elapsed_secs_f64()) else {
+        return;
+    };
+    let render_time = server_time - settings.interpolation_delay.as_secs_f64();
+    let max_extrapolation = settings.max_extrapolation.as_secs_f64();
 
-            if !player_exists {
-                spawn_events.send(remote_player_events::RemotePlayerSpawnedEvent {
-                    client_id: *client_id,
-                    position: player_state.position,
-                });
+    for (mut buffer, mut pose, mut transform) in query.iter_mut() {
+        if let Some((position, rotation)) = buffer.sample(render_time, max_extrapolation) {
+            let (yaw, pitch, _) = rotation.to_euler(EulerRot::YXZ);
+
+            transform.translation = position;
+            transform.rotation = Quat::from_rotation_y(yaw);
+            pose.pitch = pitch;
+        }
+    }
+}
+
+pub fn animate_remote_players_system(
+    time: Res<Time>,
+    mut players: Query<(
+        &remote_player_components::RemotePlayerStateBuffer,
+        &mut remote_player_components::RemotePlayerPose,
+    )>,
+    mut parts: Query<(
+        &Parent,
+        &remote_player_components::RemotePlayerPart,
+        &mut Transform,
+    )>,
+) {
+    for (buffer, mut pose) in players.iter_mut() {
+        let speed = buffer.velocity.xz().length();
+
+        pose.walk_phase = (pose.walk_phase + speed / STRIDE_LENGTH * PI * time.delta_secs()) % TAU;
+        pose.swing = pose.walk_phase.sin() * (speed / WALK_SPEED).min(1.0) * MAX_SWING;
+    }
+
+    for (parent, part, mut transform) in parts.iter_mut() {
+        let Ok((_, pose)) = players.get(parent.get()) else {
+            continue;
+        };
+
+        transform.rotation = match part {
+            remote_player_components::RemotePlayerPart::Head => Quat::from_rotation_x(pose.pitch),
+            remote_player_components::RemotePlayerPart::LeftArm
+            | remote_player_components::RemotePlayerPart::RightLeg => {
+                Quat::from_rotation_x(-pose.swing)
+            }
+            remote_player_components::RemotePlayerPart::RightArm
+            | remote_player_components::RemotePlayerPart::LeftLeg => {
+                Quat::from_rotation_x(pose.swing)
             }
+        };
+    }
+}
+
+pub fn update_nameplate_position_system(
+    camera_query: Query<(&Camera, &GlobalTransform), With<player_components::PlayerCamera>>,
+    players: Query<&GlobalTransform, With<remote_player_components::RemotePlayer>>,
+    mut nameplates: Query<(
+        &remote_player_components::RemotePlayerNameplate,
+        &ComputedNode,
+        &mut Node,
+        &mut Visibility,
+    )>,
+) {
+    let Ok((camera, camera_transform)) = camera_query.get_single() else {
+        return;
+    };
+
+    for (nameplate, computed_node, mut node, mut visibility) in nameplates.iter_mut() {
+        let Ok(player_transform) = players.get(nameplate.player) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+
+        let position = player_transform.translation() + NAMEPLATE_OFFSET;
+        let in_range = camera_transform.translation().distance(position) <= NAMEPLATE_MAX_DISTANCE;
+
+        match camera.world_to_viewport(camera_transform, position) {
+            Ok(viewport_position) if in_range => {
+                let size = computed_node.size() * computed_node.inverse_scale_factor();
+                node.left = Val::Px(viewport_position.x - size.x / 2.0);
+                node.top = Val::Px(viewport_position.y - size.y);
+                *visibility = Visibility::Inherited;
+            }
+            _ => *visibility = Visibility::Hidden,
         }
     }
 }
 
-pub fn draw_gizmos(
-    mut player_gizmos: Gizmos<remote_player_components::RemotePlayerGizmos>,
-    query: Query<(&remote_player_components::RemotePlayer, &Transform)>,
+pub fn update_remote_player_profile_system(
+    profiles: Res<remote_player_resources::RemotePlayerProfiles>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    players: Query<(
+        Entity,
+        &remote_player_components::RemotePlayer,
+        &remote_player_components::RemotePlayerMaterial,
+    )>,
+    nameplates: Query<(&remote_player_components::RemotePlayerNameplate, &Children)>,
+    mut texts: Query<&mut Text>,
 ) {
-    for (_, transform) in query.iter() {
-        player_gizmos.ray(
-            transform.translation,
-            transform.rotation * Vec3::new(0.0, 0.0, -1.0),
-            Color::srgb(0.8, 0.7, 0.6),
-        );
+    if !profiles.is_changed() {
+        return;
     }
+
+    for (entity, remote_player, material) in players.iter() {
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.base_color = profiles.color(remote_player.client_id);
+        }
+
+        for (nameplate, children) in nameplates.iter() {
+            if nameplate.player != entity {
+                continue;
+            }
+
+            for child in children.iter() {
+                if let Ok(mut text) = texts.get_mut(*child) {
+                    text.0 = profiles.name(remote_player.client_id);
+                }
+            }
+        }
+    }
+}
+
+pub fn spawn_debug_overlay_system(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn((
+            Node::default(),
+            Name::new("remote_player_debug_wrapper"),
+            NodeStyleSheet::new(asset_server.load("gui.css")),
+            remote_player_components::RemotePlayerDebugOverlay,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(""),
+                Name::new("remote_player_debug"),
+                remote_player_components::RemotePlayerDebugText,
+            ));
+        });
+}
+
+pub fn despawn_debug_overlay_system(
+    mut commands: Commands,
+    query: Query<Entity, With<remote_player_components::RemotePlayerDebugOverlay>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn update_debug_overlay_system(
+    time: Res<Time>,
+    server_clock: Res<remote_player_resources::ServerClock>,
+    settings: Res<remote_player_resources::RemotePlayerSettings>,
+    players: Query<(
+        &remote_player_components::RemotePlayer,
+        &remote_player_components::RemotePlayerStateBuffer,
+    )>,
+    mut text_query: Query<&mut Text, With<remote_player_components::RemotePlayerDebugText>>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+
+    let render_time = server_clock
+        .server_time(time.elapsed_secs_f64())
+        .map(|server_time| server_time - settings.interpolation_delay.as_secs_f64());
+
+    let mut lines = vec![format!(
+        "Remote players, delay {} ms",
+        settings.interpolation_delay.as_millis()
+    )];
+
+    for (remote_player, buffer) in players.iter() {
+        // Positive when newer states are buffered, negative when the player is extrapolated
+        let buffered_ms = match (buffer.newest_time(), render_time) {
+            (Some(newest_time), Some(render_time)) => (newest_time - render_time) * 1000.0,
+            _ => 0.0,
+        };
+
+        lines.push(format!(
+            "{}: {} states, {:+.0} ms{}",
+            remote_player.client_id,
+            buffer.states.len(),
+            buffered_ms,
+            if buffer.extrapolating {
+                " (extrapolating)"
+            } else {
+                ""
+            }
+        ));
+    }
+
+    text.0 = lines.join("\n");
 }
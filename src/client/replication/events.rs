@@ -0,0 +1,12 @@
+use crate::prelude::*;
+
+/// Replication messages in the order they were received.
+#[derive(Event)]
+pub enum ReplicationEvent {
+    Spawned(EntityUpdate),
+    Updated {
+        tick: u64,
+        entities: Vec<EntityUpdate>,
+    },
+    Despawned(NetworkId),
+}
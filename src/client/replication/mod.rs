@@ -0,0 +1,59 @@
+pub mod events;
+pub mod resources;
+pub mod systems;
+
+use crate::prelude::*;
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ReplicationSet;
+
+/// Mirrors the entities replicated by the server, see `AppReplicationExt`.
+pub struct ReplicationPlugin;
+
+impl Plugin for ReplicationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<events::ReplicationEvent>();
+        app.insert_resource(replication_resources::ReplicatedEntities::default());
+        app.insert_resource(replication_resources::ReceivedComponents::default());
+        app.configure_sets(
+            Update,
+            ReplicationSet.after(networking_systems::receive_message_system),
+        );
+        app.add_systems(
+            Update,
+            replication_systems::handle_replication_events_system.in_set(ReplicationSet),
+        );
+        app.add_systems(
+            Update,
+            replication_systems::discard_unknown_components_system
+                .in_set(ReplicationSet)
+                .after(replication_systems::handle_replication_events_system),
+        );
+        app.add_systems(
+            OnEnter(GameState::MainMenu),
+            replication_systems::reset_replicated_entities_system,
+        );
+        app.replicate::<NetworkPosition>();
+        app.replicate::<NetworkRotation>();
+    }
+}
+
+pub trait AppReplicationExt {
+    /// Inserts the component sent by the server into the replicated entities.
+    fn replicate<C: ReplicatedComponent>(&mut self) -> &mut Self;
+}
+
+impl AppReplicationExt for App {
+    fn replicate<C: ReplicatedComponent>(&mut self) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_insert_with(ReplicationRegistry::default)
+            .register::<C>();
+        self.add_systems(
+            Update,
+            replication_systems::apply_components_system::<C>
+                .in_set(ReplicationSet)
+                .after(replication_systems::handle_replication_events_system)
+                .before(replication_systems::discard_unknown_components_system),
+        )
+    }
+}
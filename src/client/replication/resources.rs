@@ -0,0 +1,14 @@
+use crate::prelude::*;
+
+#[derive(Resource, Default)]
+pub struct ReplicatedEntities {
+    pub entities: HashMap<NetworkId, Entity>,
+    /// Tick of the newest applied update, older updates arrived out of order.
+    pub last_tick: u64,
+}
+
+/// Components waiting to be inserted by the system of their kind.
+#[derive(Resource, Default)]
+pub struct ReceivedComponents {
+    pub components: Vec<(Entity, ComponentData)>,
+}
@@ -0,0 +1,108 @@
+use crate::prelude::*;
+
+pub fn handle_replication_events_system(
+    mut commands: Commands,
+    mut replication_events: EventReader<replication_events::ReplicationEvent>,
+    mut replicated_entities: ResMut<replication_resources::ReplicatedEntities>,
+    mut received_components: ResMut<replication_resources::ReceivedComponents>,
+) {
+    for event in replication_events.read() {
+        match event {
+            replication_events::ReplicationEvent::Spawned(update) => {
+                // Joining clients may receive entities they already know about
+                let entity = *replicated_entities
+                    .entities
+                    .entry(update.id)
+                    .or_insert_with(|| {
+                        commands
+                            .spawn((
+                                update.id,
+                                Transform::default(),
+                                Visibility::default(),
+                                Name::new(format!("replicated_{}", update.id.0)),
+                            ))
+                            .id()
+                    });
+
+                received_components.components.extend(
+                    update
+                        .components
+                        .iter()
+                        .map(|component| (entity, component.clone())),
+                );
+            }
+            replication_events::ReplicationEvent::Updated { tick, entities } => {
+                if *tick <= replicated_entities.last_tick {
+                    continue;
+                }
+                replicated_entities.last_tick = *tick;
+
+                for update in entities {
+                    // Updates can overtake the reliable spawn message
+                    let Some(entity) = replicated_entities.entities.get(&update.id) else {
+                        continue;
+                    };
+
+                    received_components.components.extend(
+                        update
+                            .components
+                            .iter()
+                            .map(|component| (*entity, component.clone())),
+                    );
+                }
+            }
+            replication_events::ReplicationEvent::Despawned(id) => {
+                if let Some(entity) = replicated_entities.entities.remove(id) {
+                    received_components
+                        .components
+                        .retain(|(target, _)| *target != entity);
+                    commands.entity(entity).despawn_recursive();
+                }
+            }
+        }
+    }
+}
+
+/// Removes the entities replicated by the server that was left.
+pub fn reset_replicated_entities_system(
+    mut commands: Commands,
+    mut replicated_entities: ResMut<replication_resources::ReplicatedEntities>,
+    mut received_components: ResMut<replication_resources::ReceivedComponents>,
+) {
+    for (_, entity) in replicated_entities.entities.drain() {
+        commands.entity(entity).despawn_recursive();
+    }
+    replicated_entities.last_tick = 0;
+    received_components.components.clear();
+}
+
+pub fn apply_components_system<C: ReplicatedComponent>(
+    mut commands: Commands,
+    mut received_components: ResMut<replication_resources::ReceivedComponents>,
+) {
+    received_components
+        .components
+        .retain(|(entity, component)| match component.kind == C::KIND {
+            true => {
+                match component.decode::<C>() {
+                    Some(component) => {
+                        commands.entity(*entity).try_insert(component);
+                    }
+                    None => warn!("Could not decode replicated component {}", C::KIND),
+                }
+                false
+            }
+            false => true,
+        });
+}
+
+pub fn discard_unknown_components_system(
+    mut received_components: ResMut<replication_resources::ReceivedComponents>,
+    registry: Res<ReplicationRegistry>,
+) {
+    for (_, component) in received_components.components.drain(..) {
+        if !registry.is_registered(component.kind) {
+            warn!("Received unregistered component kind {}", component.kind);
+        }
+    }
+}
@@ -0,0 +1,33 @@
+pub mod resources;
+pub mod systems;
+
+use std::path::Path;
+
+use crate::prelude::*;
+
+/// Applies the client settings whenever they change and saves them to the config file.
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        if !app
+            .world()
+            .contains_resource::<settings_resources::ClientSettings>()
+        {
+            app.insert_resource(settings_resources::ClientSettings::load(Path::new(
+                settings_resources::CLIENT_SETTINGS_PATH,
+            )));
+        }
+
+        app.add_systems(
+            Update,
+            (
+                settings_systems::apply_window_settings_system,
+                settings_systems::apply_fov_system,
+                settings_systems::apply_mouse_sensitivity_system,
+                settings_systems::apply_debug_settings_system,
+                settings_systems::save_settings_system,
+            ),
+        );
+    }
+}
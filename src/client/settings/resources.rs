@@ -0,0 +1,278 @@
+use std::path::Path;
+
+use bevy::window::PresentMode;
+
+use crate::prelude::*;
+
+pub const CLIENT_SETTINGS_PATH: &str = "client.toml";
+
+/// Servers reject chunks outside their view distance, which defaults to 8 chunks.
+const RENDER_DISTANCE_RANGE: (u32, u32) = (2, 16);
+const FOV_RANGE: (f32, f32) = (30.0, 110.0);
+const MOUSE_SENSITIVITY_RANGE: (f32, f32) = (0.1, 5.0);
+const GUI_SCALE_RANGE: (f32, f32) = (0.5, 2.0);
+
+/// Mouse sensitivity of the controller at a sensitivity setting of `1.0`.
+pub const BASE_MOUSE_SENSITIVITY: f32 = 0.001;
+
+/// Preferences of the local player, edited in the settings screen and stored next to the client.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct ClientSettings {
+    /// Horizontal chunk radius requested around the spawn, half of it is used vertically.
+    pub render_distance: u32,
+    /// Vertical field of view in degrees.
+    pub fov: f32,
+    pub mouse_sensitivity: f32,
+    pub vsync: bool,
+    /// Scale of the user interface on top of the window scale factor.
+    pub gui_scale: f32,
+    pub window_width: f32,
+    pub window_height: f32,
+    pub scale_factor: f32,
+    pub show_fps: bool,
+    pub show_colliders: bool,
+}
+
+impl Default for ClientSettings {
+    fn default() -> Self {
+        Self {
+            render_distance: 8,
+            fov: 72.0,
+            mouse_sensitivity: 1.0,
+            vsync: false,
+            gui_scale: 1.0,
+            window_width: 1920.0,
+            window_height: 1080.0,
+            scale_factor: 2.0,
+            show_fps: true,
+            show_colliders: cfg!(feature = "physics_debug"),
+        }
+    }
+}
+
+impl ClientSettings {
+    /// Falls back to the defaults when the file is missing or invalid.
+    pub fn load(path: &Path) -> Self {
+        let settings = match std::fs::read_to_string(path) {
+            Ok(contents) => Self::from_toml(&contents).unwrap_or_else(|error| {
+                warn!("Could not parse {}: {}", path.display(), error);
+                Self::default()
+            }),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(error) => {
+                warn!("Could not read {}: {}", path.display(), error);
+                Self::default()
+            }
+        };
+
+        settings.clamped()
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(path, self.to_toml())
+    }
+
+    pub fn from_toml(contents: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(contents)
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("client settings are serializable")
+    }
+
+    /// Moves values edited by hand back into the ranges offered by the settings screen.
+    pub fn clamped(mut self) -> Self {
+        self.render_distance = self
+            .render_distance
+            .clamp(RENDER_DISTANCE_RANGE.0, RENDER_DISTANCE_RANGE.1);
+        self.fov = self.fov.clamp(FOV_RANGE.0, FOV_RANGE.1);
+        self.mouse_sensitivity = self
+            .mouse_sensitivity
+            .clamp(MOUSE_SENSITIVITY_RANGE.0, MOUSE_SENSITIVITY_RANGE.1);
+        self.gui_scale = self.gui_scale.clamp(GUI_SCALE_RANGE.0, GUI_SCALE_RANGE.1);
+        self.scale_factor = self.scale_factor.max(0.5);
+        self
+    }
+
+    /// Chunk radius in each direction, as expected by `ChunkManager::instantiate_chunks`.
+    pub fn render_distance_chunks(&self) -> Vec3 {
+        let horizontal = self.render_distance as f32;
+        let vertical = (self.render_distance / 2).max(1) as f32;
+        Vec3::new(horizontal, vertical, horizontal)
+    }
+
+    pub fn fov_radians(&self) -> f32 {
+        self.fov.to_radians()
+    }
+
+    pub fn controller_sensitivity(&self) -> f32 {
+        BASE_MOUSE_SENSITIVITY * self.mouse_sensitivity
+    }
+
+    pub fn present_mode(&self) -> PresentMode {
+        match self.vsync {
+            true => PresentMode::AutoVsync,
+            false => PresentMode::Immediate,
+        }
+    }
+}
+
+/// Entries of the settings screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Setting {
+    RenderDistance,
+    Fov,
+    MouseSensitivity,
+    Vsync,
+    GuiScale,
+    ShowFps,
+    ShowColliders,
+}
+
+impl Setting {
+    pub const ALL: [Setting; 7] = [
+        Setting::RenderDistance,
+        Setting::Fov,
+        Setting::MouseSensitivity,
+        Setting::Vsync,
+        Setting::GuiScale,
+        Setting::ShowFps,
+        Setting::ShowColliders,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Setting::RenderDistance => "Render distance",
+            Setting::Fov => "Field of view",
+            Setting::MouseSensitivity => "Mouse sensitivity",
+            Setting::Vsync => "VSync",
+            Setting::GuiScale => "GUI scale",
+            Setting::ShowFps => "Show FPS",
+            Setting::ShowColliders => "Show colliders",
+        }
+    }
+
+    pub fn is_toggle(&self) -> bool {
+        matches!(
+            self,
+            Setting::Vsync | Setting::ShowFps | Setting::ShowColliders
+        )
+    }
+
+    pub fn value(&self, settings: &ClientSettings) -> String {
+        match self {
+            Setting::RenderDistance => format!("{} chunks", settings.render_distance),
+            Setting::Fov => format!("{:.0}", settings.fov),
+            Setting::MouseSensitivity => format!("{:.1}", settings.mouse_sensitivity),
+            Setting::Vsync => on_off(settings.vsync),
+            Setting::GuiScale => format!("{:.2}", settings.gui_scale),
+            Setting::ShowFps => on_off(settings.show_fps),
+            Setting::ShowColliders => on_off(settings.show_colliders),
+        }
+    }
+
+    /// Moves the value by the given number of steps, toggles flip regardless of the direction.
+    pub fn adjust(&self, settings: &mut ClientSettings, steps: i32) {
+        match self {
+            Setting::RenderDistance => {
+                settings.render_distance = settings
+                    .render_distance
+                    .saturating_add_signed(steps)
+                    .clamp(RENDER_DISTANCE_RANGE.0, RENDER_DISTANCE_RANGE.1);
+            }
+            Setting::Fov => {
+                settings.fov = (settings.fov + 5.0 * steps as f32).clamp(FOV_RANGE.0, FOV_RANGE.1);
+            }
+            Setting::MouseSensitivity => {
+                // Rounded so repeated steps do not accumulate float errors
+                let value =
+                    ((settings.mouse_sensitivity + 0.1 * steps as f32) * 10.0).round() / 10.0;
+                settings.mouse_sensitivity =
+                    value.clamp(MOUSE_SENSITIVITY_RANGE.0, MOUSE_SENSITIVITY_RANGE.1);
+            }
+            Setting::Vsync => settings.vsync = !settings.vsync,
+            Setting::GuiScale => {
+                settings.gui_scale = (settings.gui_scale + 0.25 * steps as f32)
+                    .clamp(GUI_SCALE_RANGE.0, GUI_SCALE_RANGE.1);
+            }
+            Setting::ShowFps => settings.show_fps = !settings.show_fps,
+            Setting::ShowColliders => settings.show_colliders = !settings.show_colliders,
+        }
+    }
+}
+
+fn on_off(value: bool) -> String {
+    match value {
+        true => "On".into(),
+        false => "Off".into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_toml_keeps_defaults_for_missing_fields() {
+        let settings = ClientSettings::from_toml("fov = 90.0\n").unwrap();
+
+        assert_eq!(settings.fov, 90.0);
+        assert_eq!(
+            settings.render_distance,
+            ClientSettings::default().render_distance
+        );
+    }
+
+    #[test]
+    fn test_settings_round_trip_through_toml() {
+        let settings = ClientSettings {
+            vsync: true,
+            gui_scale: 1.5,
+            ..default()
+        };
+
+        assert_eq!(
+            ClientSettings::from_toml(&settings.to_toml()).unwrap(),
+            settings
+        );
+    }
+
+    #[test]
+    fn test_clamped_limits_hand_edited_values() {
+        let settings = ClientSettings {
+            render_distance: 100,
+            fov: 5.0,
+            ..default()
+        }
+        .clamped();
+
+        assert_eq!(settings.render_distance, RENDER_DISTANCE_RANGE.1);
+        assert_eq!(settings.fov, FOV_RANGE.0);
+    }
+
+    #[test]
+    fn test_default_render_distance_matches_previous_radius() {
+        assert_eq!(
+            ClientSettings::default().render_distance_chunks(),
+            Vec3::new(8.0, 4.0, 8.0)
+        );
+    }
+
+    #[test]
+    fn test_adjust_steps_and_toggles() {
+        let mut settings = ClientSettings::default();
+
+        Setting::RenderDistance.adjust(&mut settings, -10);
+        assert_eq!(settings.render_distance, RENDER_DISTANCE_RANGE.0);
+
+        for _ in 0..3 {
+            Setting::MouseSensitivity.adjust(&mut settings, 1);
+        }
+        assert_eq!(settings.mouse_sensitivity, 1.3);
+
+        Setting::Vsync.adjust(&mut settings, -1);
+        assert!(settings.vsync);
+        assert_eq!(Setting::Vsync.value(&settings), "On");
+    }
+}
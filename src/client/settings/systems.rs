@@ -0,0 +1,77 @@
+use std::path::Path;
+
+use bevy_rapier3d::render::DebugRenderContext;
+
+use crate::prelude::*;
+
+pub fn save_settings_system(settings: Res<settings_resources::ClientSettings>) {
+    if !settings.is_changed() || settings.is_added() {
+        return;
+    }
+
+    let path = Path::new(settings_resources::CLIENT_SETTINGS_PATH);
+    if let Err(error) = settings.save(path) {
+        error!("Could not save {}: {}", path.display(), error);
+    }
+}
+
+pub fn apply_window_settings_system(
+    settings: Res<settings_resources::ClientSettings>,
+    mut ui_scale: ResMut<UiScale>,
+    mut window_query: Query<&mut Window>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    ui_scale.0 = settings.gui_scale;
+    if let Ok(mut window) = window_query.get_single_mut() {
+        window.present_mode = settings.present_mode();
+    }
+}
+
+pub fn apply_fov_system(
+    settings: Res<settings_resources::ClientSettings>,
+    mut projections: Query<&mut Projection, With<player_components::PlayerCamera>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    for mut projection in projections.iter_mut() {
+        if let Projection::Perspective(perspective) = projection.as_mut() {
+            perspective.fov = settings.fov_radians();
+        }
+    }
+}
+
+pub fn apply_mouse_sensitivity_system(
+    settings: Res<settings_resources::ClientSettings>,
+    mut controllers: Query<(&mut FpsController, Ref<player_components::Player>)>,
+) {
+    for (mut controller, player) in controllers.iter_mut() {
+        if settings.is_changed() || player.is_added() {
+            controller.sensitivity = settings.controller_sensitivity();
+        }
+    }
+}
+
+pub fn apply_debug_settings_system(
+    mut commands: Commands,
+    settings: Res<settings_resources::ClientSettings>,
+    mut debug_render_context: ResMut<DebugRenderContext>,
+    perf_uis: Query<(Entity, Ref<PerfUiRoot>)>,
+) {
+    for (entity, perf_ui) in perf_uis.iter() {
+        if settings.is_changed() || perf_ui.is_added() {
+            commands.entity(entity).insert(match settings.show_fps {
+                true => Visibility::Inherited,
+                false => Visibility::Hidden,
+            });
+        }
+    }
+
+    if settings.is_changed() {
+        debug_render_context.enabled = settings.show_colliders;
+    }
+}
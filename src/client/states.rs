@@ -3,6 +3,11 @@ use bevy::prelude::States;
 #[derive(States, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum GameState {
     Chatting,
+    /// Waiting for the server to accept the connection and send the spawn area.
+    Connecting,
+    Dead,
     Debugging,
+    MainMenu,
+    Paused,
     Playing,
 }
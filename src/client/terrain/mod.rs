@@ -15,6 +15,8 @@ impl Plugin for TerrainPlugin {
         app.insert_resource(util::TextureManager::new());
         app.insert_resource(resources::RenderMaterials::new());
         app.insert_resource(resources::MesherTasks::default());
+        app.insert_resource(resources::ChunkRequests::default());
+        app.insert_resource(resources::ChunkDownloadProgress::default());
         app.add_event::<terrain_events::BlockUpdateEvent>();
         app.add_event::<terrain_events::ChunkMeshUpdateEvent>();
         app.add_event::<terrain_events::WorldRegenerateEvent>();
@@ -27,8 +29,10 @@ impl Plugin for TerrainPlugin {
         #[cfg(not(feature = "skip_terrain"))]
         {
             app.insert_resource(terrain_resources::SpawnAreaLoaded(false));
-            app.add_systems(Startup, terrain_systems::prepare_spawn_area_system);
-            app.add_systems(Startup, terrain_systems::generate_world_system);
+            app.add_systems(
+                OnEnter(GameState::MainMenu),
+                terrain_systems::reset_terrain_system,
+            );
             app.add_systems(
                 Update,
                 terrain_systems::handle_chunk_mesh_update_events_system,
@@ -38,6 +42,7 @@ impl Plugin for TerrainPlugin {
                 terrain_systems::handle_terrain_regeneration_events_system,
             );
             app.add_systems(Update, terrain_systems::handle_chunk_tasks_system);
+            app.add_systems(Update, terrain_systems::request_chunks_around_player_system);
         }
     }
 }
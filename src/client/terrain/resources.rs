@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use bevy::tasks::Task;
 
 use crate::prelude::*;
@@ -11,6 +13,33 @@ impl SpawnAreaLoaded {
     }
 }
 
+/// Where chunks were last requested around and which of them have not arrived yet.
+#[derive(Resource, Debug, Default)]
+pub struct ChunkRequests {
+    /// Chunk the player was in, `None` until the spawn point is known.
+    pub center: Option<Vec3>,
+    pub render_distance: u32,
+    pub pending: HashSet<[i32; 3]>,
+}
+
+/// Chunks requested from the server since connecting and how many of them arrived.
+#[derive(Resource, Debug, Default)]
+pub struct ChunkDownloadProgress {
+    pub requested: usize,
+    pub received: usize,
+}
+
+impl ChunkDownloadProgress {
+    /// Share of the requested chunks that arrived, between `0.0` and `1.0`.
+    pub fn fraction(&self) -> f32 {
+        if self.requested == 0 {
+            return 0.0;
+        }
+
+        (self.received as f32 / self.requested as f32).min(1.0)
+    }
+}
+
 #[derive(Clone, PartialEq)]
 pub enum MeshType {
     Solid,
@@ -20,6 +49,7 @@ pub enum MeshType {
 pub struct ChunkMeshes {
     pub cube_mesh: Option<Mesh>,
     pub cross_mesh: Option<Mesh>,
+    pub collider: Option<Collider>,
 }
 
 pub struct MeshTask(pub Task<ChunkMeshes>);
@@ -53,3 +83,22 @@ impl RenderMaterials {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_download_fraction() {
+        let mut progress = ChunkDownloadProgress::default();
+        assert_eq!(progress.fraction(), 0.0);
+
+        progress.requested = 4;
+        progress.received = 1;
+        assert_eq!(progress.fraction(), 0.25);
+
+        // Chunks requested again after a world regeneration are not counted
+        progress.received = 5;
+        assert_eq!(progress.fraction(), 1.0);
+    }
+}
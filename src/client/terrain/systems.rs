@@ -30,37 +30,81 @@ pub fn generate_simple_ground_system(
     commands.spawn((
         Mesh3d(meshes.add(mesh)),
         MeshMaterial3d(materials.add(Color::srgba(1.0, 0.0, 1.0, 1.0))),
+        Collider::cuboid(32.0, 0.5, 32.0),
         Name::new("Simple Ground Plane"),
     ));
 }
 
-pub fn prepare_spawn_area_system(mut client: ResMut<RenetClient>) {
-    info!("Sending chunk requests for spawn area");
-
-    let chunks = ChunkManager::instantiate_chunks(Vec3::ZERO, Vec3::ONE);
-
-    let positions: Vec<Vec3> = chunks.into_iter().map(|chunk| chunk.position).collect();
-    let message = bincode::serialize(&NetworkingMessage::ChunkBatchRequest(positions));
-    info!("requesting world");
-    client.send_message(DefaultChannel::ReliableUnordered, message.unwrap());
-}
-
-pub fn generate_world_system(
+/// Requests the chunks around the player, starting at the spawn point sent by the server and
+/// again whenever the player enters another chunk or the render distance changes.
+pub fn request_chunks_around_player_system(
     mut client: ResMut<RenetClient>,
-    mut chunk_manager: ResMut<ChunkManager>,
+    chunk_manager: Res<ChunkManager>,
+    mut chunk_requests: ResMut<terrain_resources::ChunkRequests>,
+    mut chunk_download: ResMut<terrain_resources::ChunkDownloadProgress>,
+    spawn_point: Res<player_resources::PlayerSpawnPoint>,
+    settings: Res<settings_resources::ClientSettings>,
+    player_query: Query<&Transform, With<player_components::Player>>,
 ) {
-    let render_distance = Vec3::new(8.0, 4.0, 8.0);
+    if !client.is_connected() {
+        return;
+    }
 
-    info!("Sending chunk requests for chunks");
+    let position = match player_query.get_single() {
+        Ok(transform) => transform.translation,
+        Err(_) => match spawn_point.position {
+            Some(position) => position,
+            None => return,
+        },
+    };
+    let center = (position / CHUNK_SIZE as f32).floor();
 
-    let chunks = chunk_manager.instantiate_new_chunks(Vec3::new(0.0, 0.0, 0.0), render_distance);
+    if chunk_requests.center == Some(center)
+        && chunk_requests.render_distance == settings.render_distance
+    {
+        return;
+    }
 
-    let positions: Vec<Vec3> = chunks.into_iter().map(|chunk| chunk.position).collect();
+    debug!(
+        "Requesting chunks around {} with render distance {}",
+        center, settings.render_distance
+    );
+    chunk_requests.center = Some(center);
+    chunk_requests.render_distance = settings.render_distance;
+    chunk_download.requested += request_new_chunks(
+        &mut client,
+        &chunk_manager,
+        &mut chunk_requests,
+        center,
+        settings.render_distance_chunks(),
+    );
+}
 
-    let batched_positions = positions.chunks(16);
-    assert!(batched_positions.len() > 0, "Batched positions is empty");
+/// Sends batched requests for the chunks in range that are neither loaded nor already
+/// requested, nearest first, returns their count.
+fn request_new_chunks(
+    client: &mut RenetClient,
+    chunk_manager: &ChunkManager,
+    chunk_requests: &mut terrain_resources::ChunkRequests,
+    center: Vec3,
+    render_distance: Vec3,
+) -> usize {
+    let mut positions: Vec<Vec3> = ChunkManager::instantiate_chunks(center, render_distance)
+        .into_iter()
+        .map(|chunk| chunk.position)
+        .filter(|position| chunk_manager.get_chunk(*position).is_none())
+        .filter(|position| {
+            chunk_requests
+                .pending
+                .insert(ChunkManager::position_to_key(*position))
+        })
+        .collect();
+    positions.sort_by(|a, b| {
+        a.distance_squared(center)
+            .total_cmp(&b.distance_squared(center))
+    });
 
-    batched_positions.enumerate().for_each(|(index, batch)| {
+    positions.chunks(16).enumerate().for_each(|(index, batch)| {
         let request_positions = batch.to_vec();
         info!(
             "Sending chunk batch request for {:?}",
@@ -70,6 +114,8 @@ pub fn generate_world_system(
         info!("requesting chunks #{}", index);
         client.send_message(DefaultChannel::ReliableUnordered, message.unwrap());
     });
+
+    positions.len()
 }
 
 pub fn handle_chunk_mesh_update_events_system(
@@ -106,6 +152,7 @@ fn create_mesh_task(chunk: &Chunk, texture_manager: &terrain_util::TextureManage
         ChunkMeshes {
             cube_mesh: terrain_util::create_cube_mesh_for_chunk(&chunk, &texture_manager),
             cross_mesh: terrain_util::create_cross_mesh_for_chunk(&chunk, &texture_manager),
+            collider: terrain_util::create_collider_for_chunk(&chunk),
         }
     }))
 }
@@ -142,14 +189,30 @@ pub fn handle_chunk_tasks_system(
             }
 
             if mesh_option.cube_mesh.is_some() {
-                commands
-                    .spawn(create_chunk_bundle(
-                        meshes.add(mesh_option.cube_mesh.unwrap()),
-                        chunk_position,
-                        MeshType::Solid,
-                        materials.chunk_material.clone().unwrap(),
-                    ))
-                    .insert(player_components::Raycastable);
+                let mut solid_chunk = commands.spawn(create_chunk_bundle(
+                    meshes.add(mesh_option.cube_mesh.unwrap()),
+                    chunk_position,
+                    MeshType::Solid,
+                    materials.chunk_material.clone().unwrap(),
+                ));
+
+                if let Some(collider) = mesh_option.collider {
+                    solid_chunk.insert(collider);
+                }
+            } else if let Some(collider) = mesh_option.collider {
+                // Chunks without visible faces still block movement
+                commands.spawn((
+                    collider,
+                    Transform::from_translation(chunk_position * CHUNK_SIZE as f32),
+                    terrain_components::ChunkMesh {
+                        key: [
+                            chunk_position.x as i32,
+                            chunk_position.y as i32,
+                            chunk_position.z as i32,
+                        ],
+                        mesh_type: MeshType::Solid,
+                    },
+                ));
             }
 
             for (old_chunk, old_mesh) in mesh_query.iter_mut() {
@@ -232,6 +295,27 @@ fn create_chunk_material(_texture_handle: Handle<Image>) -> StandardMaterial {
     }
 }
 
+/// Forgets the terrain of the server that was left.
+pub fn reset_terrain_system(
+    mut commands: Commands,
+    mut chunk_manager: ResMut<ChunkManager>,
+    mut tasks: ResMut<MesherTasks>,
+    mut spawn_area_loaded: ResMut<terrain_resources::SpawnAreaLoaded>,
+    mut chunk_requests: ResMut<terrain_resources::ChunkRequests>,
+    mut chunk_download: ResMut<terrain_resources::ChunkDownloadProgress>,
+    chunk_meshes: Query<Entity, With<ChunkMesh>>,
+) {
+    *chunk_manager = ChunkManager::new();
+    tasks.task_list.clear();
+    spawn_area_loaded.0 = false;
+    *chunk_requests = terrain_resources::ChunkRequests::default();
+    *chunk_download = terrain_resources::ChunkDownloadProgress::default();
+
+    for entity in chunk_meshes.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
 fn obtain_texture_handle(asset_server: &Res<AssetServer>) -> Handle<Image> {
     asset_server.load("textures/texture_atlas.png")
 }
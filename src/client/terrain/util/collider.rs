@@ -0,0 +1,136 @@
+use crate::prelude::*;
+
+/// Solid blocks of the chunk merged into as few boxes as possible, in block coordinates
+/// relative to the chunk origin.
+pub fn create_collider_boxes_for_chunk(chunk: &Chunk) -> Vec<Aabb> {
+    let mut merged = vec![false; CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE];
+    let mut boxes = vec![];
+
+    for y in 0..CHUNK_SIZE {
+        for z in 0..CHUNK_SIZE {
+            for x in 0..CHUNK_SIZE {
+                if !is_unmerged_solid(chunk, &merged, x, y, z) {
+                    continue;
+                }
+
+                let mut width = 1;
+                while x + width < CHUNK_SIZE && is_unmerged_solid(chunk, &merged, x + width, y, z) {
+                    width += 1;
+                }
+
+                let mut depth = 1;
+                while z + depth < CHUNK_SIZE
+                    && (x..x + width).all(|x| is_unmerged_solid(chunk, &merged, x, y, z + depth))
+                {
+                    depth += 1;
+                }
+
+                let mut height = 1;
+                while y + height < CHUNK_SIZE
+                    && (z..z + depth).all(|z| {
+                        (x..x + width).all(|x| is_unmerged_solid(chunk, &merged, x, y + height, z))
+                    })
+                {
+                    height += 1;
+                }
+
+                for merged_y in y..y + height {
+                    for merged_z in z..z + depth {
+                        for merged_x in x..x + width {
+                            merged[merged_index(merged_x, merged_y, merged_z)] = true;
+                        }
+                    }
+                }
+
+                let min = Vec3::new(x as f32, y as f32, z as f32);
+                boxes.push(Aabb::new(
+                    min,
+                    min + Vec3::new(width as f32, height as f32, depth as f32),
+                ));
+            }
+        }
+    }
+
+    boxes
+}
+
+/// Compound collider of all solid blocks in the chunk, built next to the chunk meshes.
+pub fn create_collider_for_chunk(chunk: &Chunk) -> Option<Collider> {
+    let boxes = create_collider_boxes_for_chunk(chunk);
+    if boxes.is_empty() {
+        return None;
+    }
+
+    let shapes = boxes
+        .into_iter()
+        .map(|aabb| {
+            let half_size = (aabb.max - aabb.min) / 2.0;
+            (
+                aabb.min + half_size,
+                Quat::IDENTITY,
+                Collider::cuboid(half_size.x, half_size.y, half_size.z),
+            )
+        })
+        .collect();
+
+    Some(Collider::compound(shapes))
+}
+
+fn merged_index(x: usize, y: usize, z: usize) -> usize {
+    x + CHUNK_SIZE * (y + CHUNK_SIZE * z)
+}
+
+fn is_unmerged_solid(chunk: &Chunk, merged: &[bool], x: usize, y: usize, z: usize) -> bool {
+    chunk.get(x, y, z).is_solid() && !merged[merged_index(x, y, z)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_chunk_has_no_collider() {
+        let chunk = Chunk::new(Vec3::ZERO);
+
+        assert!(create_collider_boxes_for_chunk(&chunk).is_empty());
+        assert!(create_collider_for_chunk(&chunk).is_none());
+    }
+
+    #[test]
+    fn test_solid_layer_is_merged_into_one_box() {
+        let mut chunk = Chunk::new(Vec3::ZERO);
+        for x in 0..CHUNK_SIZE {
+            for z in 0..CHUNK_SIZE {
+                chunk.set(x, 3, z, BlockId::Stone);
+                chunk.set(x, 4, z, BlockId::Dirt);
+                chunk.set(x, 5, z, BlockId::Tallgrass);
+            }
+        }
+
+        let boxes = create_collider_boxes_for_chunk(&chunk);
+
+        assert_eq!(
+            boxes,
+            vec![Aabb::new(
+                Vec3::new(0.0, 3.0, 0.0),
+                Vec3::new(CHUNK_SIZE as f32, 5.0, CHUNK_SIZE as f32)
+            )]
+        );
+    }
+
+    #[test]
+    fn test_separate_blocks_get_separate_boxes() {
+        let mut chunk = Chunk::new(Vec3::ZERO);
+        chunk.set(1, 1, 1, BlockId::Stone);
+        chunk.set(3, 1, 1, BlockId::Stone);
+        chunk.set(1, 2, 1, BlockId::Stone);
+
+        let boxes = create_collider_boxes_for_chunk(&chunk);
+
+        assert_eq!(boxes.len(), 2);
+        assert!(boxes.contains(&Aabb::new(
+            Vec3::new(1.0, 1.0, 1.0),
+            Vec3::new(2.0, 3.0, 2.0)
+        )));
+    }
+}
@@ -1,9 +1,11 @@
 pub mod blocks;
+pub mod collider;
 pub mod cross_mesher;
 pub mod cube_mesher;
 pub mod mesher;
 
 pub use blocks::*;
+pub use collider::*;
 pub use cross_mesher::*;
 pub use cube_mesher::*;
 pub use mesher::*;
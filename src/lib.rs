@@ -1,2 +1,3 @@
+pub mod server;
 pub mod shared;
 pub use shared::*;
@@ -0,0 +1,178 @@
+use std::fmt;
+use std::net::IpAddr;
+
+use crate::server::prelude::*;
+
+const GAME_MODE_USAGE: &str = "/gamemode <survival|creative|spectator> [player]";
+const MUTE_USAGE: &str = "/mute <player>";
+const UNMUTE_USAGE: &str = "/unmute <player>";
+const KICK_USAGE: &str = "/kick <player>";
+const BAN_USAGE: &str = "/ban <player>";
+const UNBAN_USAGE: &str = "/unban <player>";
+const BAN_ADDRESS_USAGE: &str = "/banip <address|player>";
+const UNBAN_ADDRESS_USAGE: &str = "/unbanip <address>";
+
+/// A chat message starting with `/`, run by the server instead of being broadcast.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChatCommand {
+    /// Switches the game mode of the sender, or of the named player.
+    GameMode {
+        game_mode: GameMode,
+        player: Option<String>,
+    },
+    /// Stops the named player's chat messages from being broadcast.
+    Mute(String),
+    Unmute(String),
+    /// Disconnects the named player.
+    Kick(String),
+    /// Disconnects the named player and refuses them when joining again.
+    Ban(String),
+    Unban(String),
+    /// Refuses clients connecting from an address, or from the address of the named player.
+    BanAddress(BanTarget),
+    UnbanAddress(IpAddr),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BanTarget {
+    Address(IpAddr),
+    Player(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandError {
+    Unknown(String),
+    Usage(&'static str),
+    InvalidArgument(String),
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::Unknown(name) => write!(f, "Unknown command '/{}'", name),
+            CommandError::Usage(usage) => write!(f, "Usage: {}", usage),
+            CommandError::InvalidArgument(reason) => write!(f, "Invalid argument: {}", reason),
+        }
+    }
+}
+
+impl ChatCommand {
+    /// Parses a command without its leading `/`.
+    pub fn parse(command: &str) -> Result<Self, CommandError> {
+        let mut arguments = command.split_whitespace();
+        let name = arguments.next().unwrap_or_default().to_lowercase();
+
+        match name.as_str() {
+            "gamemode" | "gm" => {
+                let game_mode = arguments
+                    .next()
+                    .ok_or(CommandError::Usage(GAME_MODE_USAGE))?
+                    .parse()
+                    .map_err(CommandError::InvalidArgument)?;
+                // Player names may contain spaces
+                let player = arguments.collect::<Vec<_>>().join(" ");
+
+                Ok(ChatCommand::GameMode {
+                    game_mode,
+                    player: (!player.is_empty()).then_some(player),
+                })
+            }
+            "mute" => player_argument(arguments, MUTE_USAGE).map(ChatCommand::Mute),
+            "unmute" => player_argument(arguments, UNMUTE_USAGE).map(ChatCommand::Unmute),
+            "kick" => player_argument(arguments, KICK_USAGE).map(ChatCommand::Kick),
+            "ban" => player_argument(arguments, BAN_USAGE).map(ChatCommand::Ban),
+            "unban" => player_argument(arguments, UNBAN_USAGE).map(ChatCommand::Unban),
+            "banip" => {
+                let target = player_argument(arguments, BAN_ADDRESS_USAGE)?;
+                Ok(ChatCommand::BanAddress(match target.parse() {
+                    Ok(address) => BanTarget::Address(address),
+                    Err(_) => BanTarget::Player(target),
+                }))
+            }
+            "unbanip" => player_argument(arguments, UNBAN_ADDRESS_USAGE)?
+                .parse()
+                .map(ChatCommand::UnbanAddress)
+                .map_err(|error| CommandError::InvalidArgument(error.to_string())),
+            _ => Err(CommandError::Unknown(name)),
+        }
+    }
+}
+
+/// The remaining arguments joined as a player name, names may contain spaces.
+fn player_argument<'a>(
+    arguments: impl Iterator<Item = &'a str>,
+    usage: &'static str,
+) -> Result<String, CommandError> {
+    let player = arguments.collect::<Vec<_>>().join(" ");
+    match player.is_empty() {
+        true => Err(CommandError::Usage(usage)),
+        false => Ok(player),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_game_mode_command() {
+        assert_eq!(
+            ChatCommand::parse("gamemode creative"),
+            Ok(ChatCommand::GameMode {
+                game_mode: GameMode::Creative,
+                player: None
+            })
+        );
+        assert_eq!(
+            ChatCommand::parse("gm 2 Cool Steve"),
+            Ok(ChatCommand::GameMode {
+                game_mode: GameMode::Spectator,
+                player: Some("Cool Steve".to_string())
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            ChatCommand::parse("gamemode"),
+            Err(CommandError::Usage(GAME_MODE_USAGE))
+        );
+        assert!(matches!(
+            ChatCommand::parse("gamemode hardcore"),
+            Err(CommandError::InvalidArgument(_))
+        ));
+        assert_eq!(
+            ChatCommand::parse("fly"),
+            Err(CommandError::Unknown("fly".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_moderation_commands() {
+        assert_eq!(
+            ChatCommand::parse("mute Cool Steve"),
+            Ok(ChatCommand::Mute("Cool Steve".to_string()))
+        );
+        assert_eq!(
+            ChatCommand::parse("kick"),
+            Err(CommandError::Usage(KICK_USAGE))
+        );
+        assert_eq!(
+            ChatCommand::parse("banip 10.0.0.7"),
+            Ok(ChatCommand::BanAddress(BanTarget::Address(
+                "10.0.0.7".parse().unwrap()
+            )))
+        );
+        assert_eq!(
+            ChatCommand::parse("banip Steve"),
+            Ok(ChatCommand::BanAddress(BanTarget::Player(
+                "Steve".to_string()
+            )))
+        );
+        assert!(matches!(
+            ChatCommand::parse("unbanip Steve"),
+            Err(CommandError::InvalidArgument(_))
+        ));
+    }
+}
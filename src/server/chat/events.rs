@@ -1,4 +1,4 @@
-use crate::prelude::*;
+use crate::server::prelude::*;
 
 #[derive(Event)]
 pub struct PlayerChatMessageSendEvent {
@@ -10,3 +10,10 @@ pub struct PlayerChatMessageSendEvent {
 pub struct SyncPlayerChatMessagesEvent {
     pub client_id: ClientId,
 }
+
+/// A chat message starting with `/`, without the slash.
+#[derive(Event)]
+pub struct ChatCommandEvent {
+    pub client_id: ClientId,
+    pub command: String,
+}
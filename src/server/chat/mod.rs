@@ -1,5 +1,6 @@
-use crate::prelude::*;
+use crate::server::prelude::*;
 
+pub mod commands;
 pub mod events;
 pub mod resources;
 pub mod systems;
@@ -10,14 +11,27 @@ impl Plugin for ChatPlugin {
     fn build(&self, app: &mut App) {
         info!("Building ChatPlugin");
         app.insert_resource(resources::ChatHistory::new());
+        app.insert_resource(resources::ChatRateLimiter::default());
+
+        if !app.world().contains_resource::<resources::ChatFilter>() {
+            let words = app
+                .world()
+                .resource::<settings::ServerSettings>()
+                .filtered_words
+                .clone();
+            app.insert_resource(resources::ChatFilter::masking_words(words));
+        }
+
         app.add_systems(
             Update,
             (
                 chat_systems::sync_player_chat_messages_event,
                 chat_systems::sync_single_player_chat_messages_system,
+                chat_systems::handle_chat_commands_system,
             ),
         );
         app.add_event::<chat_events::PlayerChatMessageSendEvent>();
         app.add_event::<chat_events::SyncPlayerChatMessagesEvent>();
+        app.add_event::<chat_events::ChatCommandEvent>();
     }
 }
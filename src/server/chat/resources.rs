@@ -1,16 +1,35 @@
-use crate::prelude::*;
+use crate::server::prelude::*;
+
+/// Messages kept for players that join later, older ones are dropped.
+pub const MAX_CHAT_HISTORY: usize = 200;
 
 #[derive(Resource, Debug)]
 pub struct ChatHistory {
     pub messages: Vec<ChatMessage>,
+    next_message_id: usize,
 }
 
 impl ChatHistory {
     pub fn new() -> Self {
         Self {
             messages: Vec::new(),
+            next_message_id: 0,
         }
     }
+
+    /// Id for the next message, unique even after old messages were dropped.
+    pub fn next_message_id(&mut self) -> usize {
+        let message_id = self.next_message_id;
+        self.next_message_id += 1;
+        message_id
+    }
+
+    pub fn push(&mut self, message: ChatMessage) {
+        self.messages.push(message);
+
+        let overflow = self.messages.len().saturating_sub(MAX_CHAT_HISTORY);
+        self.messages.drain(..overflow);
+    }
 }
 
 impl Default for ChatHistory {
@@ -18,3 +37,124 @@ impl Default for ChatHistory {
         Self::new()
     }
 }
+
+/// Times of the recent chat messages of each player.
+#[derive(Resource, Default, Debug)]
+pub struct ChatRateLimiter {
+    pub recent: HashMap<ClientId, VecDeque<Instant>>,
+}
+
+impl ChatRateLimiter {
+    /// Records a message sent at `now` unless the player already sent `limit` messages within
+    /// `window`.
+    pub fn allow(
+        &mut self,
+        client_id: ClientId,
+        now: Instant,
+        limit: usize,
+        window: Duration,
+    ) -> bool {
+        let recent = self.recent.entry(client_id).or_default();
+        while recent
+            .front()
+            .is_some_and(|sent| now.duration_since(*sent) >= window)
+        {
+            recent.pop_front();
+        }
+
+        if recent.len() >= limit {
+            return false;
+        }
+
+        recent.push_back(now);
+        true
+    }
+}
+
+type ChatFilterHook = Box<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
+/// Hook run on every player chat message before it is broadcast, it may rewrite the message or
+/// return `None` to drop it. Replace the resource to plug in a different filter.
+#[derive(Resource)]
+pub struct ChatFilter(pub ChatFilterHook);
+
+impl ChatFilter {
+    /// Replaces the given words with asterisks, ignoring case.
+    pub fn masking_words(words: Vec<String>) -> Self {
+        let words: Vec<String> = words
+            .into_iter()
+            .map(|word| word.to_lowercase())
+            .filter(|word| !word.is_empty())
+            .collect();
+
+        Self(Box::new(move |message| {
+            let masked = message
+                .split(' ')
+                .map(|word| {
+                    let bare = word.trim_matches(|character: char| !character.is_alphanumeric());
+                    match words.contains(&bare.to_lowercase()) {
+                        true => word.replace(bare, &"*".repeat(bare.chars().count())),
+                        false => word.to_string(),
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            Some(masked)
+        }))
+    }
+
+    pub fn apply(&self, message: &str) -> Option<String> {
+        (self.0)(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(message_id: usize) -> ChatMessage {
+        ChatMessage {
+            client_id: 1,
+            message_id,
+            timestamp: 0,
+            message: message_id.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_chat_history_is_capped() {
+        let mut history = ChatHistory::new();
+        for _ in 0..MAX_CHAT_HISTORY + 5 {
+            let message_id = history.next_message_id();
+            history.push(message(message_id));
+        }
+
+        assert_eq!(history.messages.len(), MAX_CHAT_HISTORY);
+        assert_eq!(history.messages[0].message_id, 5);
+        assert_eq!(history.next_message_id(), MAX_CHAT_HISTORY + 5);
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let mut limiter = ChatRateLimiter::default();
+        let window = Duration::from_secs(10);
+        let start = Instant::now();
+
+        assert!(limiter.allow(1, start, 2, window));
+        assert!(limiter.allow(1, start + Duration::from_secs(1), 2, window));
+        assert!(!limiter.allow(1, start + Duration::from_secs(2), 2, window));
+        assert!(limiter.allow(2, start + Duration::from_secs(2), 2, window));
+        assert!(limiter.allow(1, start + Duration::from_secs(10), 2, window));
+    }
+
+    #[test]
+    fn test_filter_masks_words() {
+        let filter = ChatFilter::masking_words(vec!["Heck".to_string()]);
+
+        assert_eq!(
+            filter.apply("what the HECK, heckle"),
+            Some("what the ****, heckle".to_string())
+        );
+    }
+}
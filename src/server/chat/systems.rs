@@ -1,28 +1,48 @@
 use std::time::UNIX_EPOCH;
 
-use crate::prelude::*;
+use crate::server::prelude::*;
 
+#[allow(clippy::too_many_arguments)]
 pub fn sync_single_player_chat_messages_system(
     mut server: ResMut<RenetServer>,
     mut player_send_messages: EventReader<chat_events::PlayerChatMessageSendEvent>,
     mut chat_messages: ResMut<chat_resources::ChatHistory>,
+    chat_filter: Res<chat_resources::ChatFilter>,
+    moderation: Res<moderation_resources::ModerationList>,
+    player_profiles: Res<player_resources::PlayerProfiles>,
+    transport: Option<Res<NetcodeServerTransport>>,
 ) {
     for event in player_send_messages.read() {
-        let message = event.message.clone();
+        let mut message = event.message.clone();
         let client_id = event.client_id;
 
-        info!("Broadcasting message from sender {}", client_id);
-        let message_count = chat_messages.messages.len();
-        let message_id = message_count;
+        // Announcements of the server are not moderated
+        if client_id != SERVER_MESSAGE_ID {
+            let address = moderation_resources::client_address(transport.as_deref(), client_id);
+            let muted = player_profiles
+                .profiles
+                .get(&client_id)
+                .is_some_and(|profile| moderation.is_muted(&profile.name, address));
+            if muted {
+                send_server_reply(&mut server, &mut chat_messages, client_id, "You are muted");
+                continue;
+            }
 
+            match chat_filter.apply(&message) {
+                Some(filtered) => message = filtered,
+                None => continue,
+            }
+        }
+
+        info!("Broadcasting message from sender {}", client_id);
         let chat_message = ChatMessage {
             client_id,
-            message_id,
+            message_id: chat_messages.next_message_id(),
             message,
             timestamp: get_current_time_in_ms(),
         };
 
-        chat_messages.messages.push(chat_message.clone());
+        chat_messages.push(chat_message.clone());
 
         let response_message = NetworkingMessage::SingleChatMessageSync(chat_message);
 
@@ -49,6 +69,155 @@ pub fn sync_player_chat_messages_event(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
+pub fn handle_chat_commands_system(
+    mut server: ResMut<RenetServer>,
+    mut command_events: EventReader<chat_events::ChatCommandEvent>,
+    mut game_mode_events: EventWriter<player_events::GameModeChangeEvent>,
+    mut moderation: ResMut<moderation_resources::ModerationList>,
+    player_profiles: Res<player_resources::PlayerProfiles>,
+    mut chat_messages: ResMut<chat_resources::ChatHistory>,
+    settings: Res<settings::ServerSettings>,
+    transport: Option<Res<NetcodeServerTransport>>,
+) {
+    let find_player = |name: &str| {
+        player_profiles
+            .profiles
+            .iter()
+            .find(|(_, profile)| profile.name == name)
+            .map(|(client_id, _)| *client_id)
+    };
+
+    for event in command_events.read() {
+        let sender = player_profiles.profiles.get(&event.client_id);
+        let identity = player_profiles.identities.get(&event.client_id);
+        let is_operator = sender
+            .zip(identity)
+            .is_some_and(|(profile, identity)| settings.is_operator(&profile.name, identity));
+        info!("Client {} ran command /{}", event.client_id, event.command);
+
+        let reply = match chat_commands::ChatCommand::parse(&event.command) {
+            // Every command changes other players or the world
+            _ if !is_operator => "Only operators may use commands".to_string(),
+            Err(error) => error.to_string(),
+            Ok(chat_commands::ChatCommand::GameMode { game_mode, player }) => {
+                let target = match &player {
+                    Some(name) => find_player(name).map(|client_id| (client_id, name.clone())),
+                    None => sender.map(|profile| (event.client_id, profile.name.clone())),
+                };
+
+                match target {
+                    Some((client_id, name)) => {
+                        game_mode_events.send(player_events::GameModeChangeEvent {
+                            client_id,
+                            game_mode,
+                        });
+                        format!("Set the game mode of {} to {}", name, game_mode)
+                    }
+                    None => format!("No player named {}", player.unwrap_or_default()),
+                }
+            }
+            Ok(chat_commands::ChatCommand::Mute(name)) => {
+                moderation.muted_names.insert(name.clone());
+                let address = find_player(&name).and_then(|client_id| {
+                    moderation_resources::client_address(transport.as_deref(), client_id)
+                });
+                moderation.record_address(&name, address);
+                format!("Muted {}", name)
+            }
+            Ok(chat_commands::ChatCommand::Unmute(name)) => {
+                let unmuted = moderation.muted_names.remove(&name);
+                moderation.remove_unused_addresses();
+                match unmuted {
+                    true => format!("Unmuted {}", name),
+                    false => format!("{} is not muted", name),
+                }
+            }
+            Ok(chat_commands::ChatCommand::Kick(name)) => match find_player(&name) {
+                Some(client_id) => {
+                    info!("Kicking {}", name);
+                    server.disconnect(client_id);
+                    format!("Kicked {}", name)
+                }
+                None => format!("No player named {}", name),
+            },
+            Ok(chat_commands::ChatCommand::Ban(name)) => {
+                moderation.banned_names.insert(name.clone());
+                if let Some(client_id) = find_player(&name) {
+                    let address =
+                        moderation_resources::client_address(transport.as_deref(), client_id);
+                    moderation.record_address(&name, address);
+                    server.disconnect(client_id);
+                }
+                format!("Banned {}", name)
+            }
+            Ok(chat_commands::ChatCommand::Unban(name)) => {
+                let unbanned = moderation.banned_names.remove(&name);
+                moderation.remove_unused_addresses();
+                match unbanned {
+                    true => format!("Unbanned {}", name),
+                    false => format!("{} is not banned", name),
+                }
+            }
+            Ok(chat_commands::ChatCommand::BanAddress(target)) => {
+                let address = match target {
+                    chat_commands::BanTarget::Address(address) => Ok(address),
+                    chat_commands::BanTarget::Player(name) => find_player(&name)
+                        .and_then(|client_id| {
+                            moderation_resources::client_address(transport.as_deref(), client_id)
+                        })
+                        .ok_or(name),
+                };
+
+                match address {
+                    Ok(address) => {
+                        moderation.banned_addresses.insert(address);
+                        for client_id in server.clients_id() {
+                            let client_address = moderation_resources::client_address(
+                                transport.as_deref(),
+                                client_id,
+                            );
+                            if client_address == Some(address) {
+                                server.disconnect(client_id);
+                            }
+                        }
+                        format!("Banned address {}", address)
+                    }
+                    Err(name) => format!("Could not find the address of {}", name),
+                }
+            }
+            Ok(chat_commands::ChatCommand::UnbanAddress(address)) => {
+                match moderation.banned_addresses.remove(&address) {
+                    true => format!("Unbanned address {}", address),
+                    false => format!("{} is not banned", address),
+                }
+            }
+        };
+
+        send_server_reply(&mut server, &mut chat_messages, event.client_id, &reply);
+    }
+}
+
+/// Sends a server message to a single client without keeping it in the history.
+pub fn send_server_reply(
+    server: &mut RenetServer,
+    chat_messages: &mut chat_resources::ChatHistory,
+    client_id: ClientId,
+    message: &str,
+) {
+    let reply = ChatMessage {
+        client_id: SERVER_MESSAGE_ID,
+        message_id: chat_messages.next_message_id(),
+        message: message.to_string(),
+        timestamp: get_current_time_in_ms(),
+    };
+    server.send_message(
+        client_id,
+        DefaultChannel::ReliableOrdered,
+        bincode::serialize(&NetworkingMessage::SingleChatMessageSync(reply)).unwrap(),
+    );
+}
+
 fn get_current_time_in_ms() -> i64 {
     let start = SystemTime::now();
     let since_the_epoch = start.duration_since(UNIX_EPOCH);
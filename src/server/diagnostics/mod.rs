@@ -0,0 +1,23 @@
+use crate::server::prelude::*;
+
+pub mod resources;
+pub mod systems;
+
+pub struct DiagnosticsPlugin;
+
+impl Plugin for DiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        info!("Building DiagnosticsPlugin");
+
+        app.insert_resource(resources::TickTimer::new());
+        app.add_systems(First, diagnostics_systems::start_tick_timer_system);
+        app.add_systems(
+            Last,
+            (
+                diagnostics_systems::finish_tick_timer_system,
+                diagnostics_systems::broadcast_diagnostics_system,
+            )
+                .chain(),
+        );
+    }
+}
@@ -0,0 +1,51 @@
+use crate::server::prelude::*;
+
+#[derive(Resource)]
+pub struct TickTimer {
+    pub tick_started: Option<Instant>,
+    pub tick_times: Vec<Duration>,
+    pub last_report: Instant,
+}
+
+impl Default for TickTimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TickTimer {
+    pub fn new() -> Self {
+        Self {
+            tick_started: None,
+            tick_times: Vec::new(),
+            last_report: Instant::now(),
+        }
+    }
+
+    /// Summarizes and clears the tick times recorded since the last report.
+    pub fn take_diagnostics(&mut self, client_count: usize) -> ServerDiagnostics {
+        let elapsed = self.last_report.elapsed().as_secs_f32();
+        let tick_count = self.tick_times.len();
+
+        let total_tick_time: Duration = self.tick_times.iter().sum();
+        let max_tick_time = self.tick_times.iter().max().copied().unwrap_or_default();
+
+        let diagnostics = ServerDiagnostics {
+            average_tick_time: match tick_count {
+                0 => 0.0,
+                _ => total_tick_time.as_secs_f32() * 1000.0 / tick_count as f32,
+            },
+            max_tick_time: max_tick_time.as_secs_f32() * 1000.0,
+            tick_rate: match elapsed > 0.0 {
+                true => tick_count as f32 / elapsed,
+                false => 0.0,
+            },
+            client_count,
+        };
+
+        self.tick_times.clear();
+        self.last_report = Instant::now();
+
+        diagnostics
+    }
+}
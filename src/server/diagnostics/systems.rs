@@ -0,0 +1,30 @@
+use crate::server::prelude::*;
+
+const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+pub fn start_tick_timer_system(mut tick_timer: ResMut<diagnostics_resources::TickTimer>) {
+    tick_timer.tick_started = Some(Instant::now());
+}
+
+pub fn finish_tick_timer_system(mut tick_timer: ResMut<diagnostics_resources::TickTimer>) {
+    if let Some(tick_started) = tick_timer.tick_started.take() {
+        tick_timer.tick_times.push(tick_started.elapsed());
+    }
+}
+
+pub fn broadcast_diagnostics_system(
+    mut server: ResMut<RenetServer>,
+    mut tick_timer: ResMut<diagnostics_resources::TickTimer>,
+) {
+    if tick_timer.last_report.elapsed() < REPORT_INTERVAL {
+        return;
+    }
+
+    let diagnostics = tick_timer.take_diagnostics(server.clients_id().len());
+    debug!("Server diagnostics: {:?}", diagnostics);
+
+    server.broadcast_message(
+        DefaultChannel::Unreliable,
+        bincode::serialize(&NetworkingMessage::ServerDiagnostics(diagnostics)).unwrap(),
+    );
+}
@@ -0,0 +1,82 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use bevy::app::{PluginGroup, ScheduleRunnerPlugin};
+
+use crate::server::prelude::*;
+use crate::server::ServerPlugins;
+
+#[derive(Resource)]
+pub struct ShutdownSignal(pub Arc<AtomicBool>);
+
+/// A server running on a background thread of the client process, connected over a memory transport.
+pub struct IntegratedServer {
+    shutdown: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl IntegratedServer {
+    /// Starts the server and returns the client end of its connection.
+    pub fn start(settings: settings::ServerSettings) -> (Self, MemoryConnection) {
+        let (client_connection, server_connection) = memory_connection_pair();
+        let client_id: ClientId = rand::random();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let server_shutdown = shutdown.clone();
+
+        let thread = std::thread::Builder::new()
+            .name("integrated-server".to_string())
+            .spawn(move || {
+                info!("Starting integrated server with {:?}", settings);
+
+                let mut app = App::new();
+                app.add_plugins(
+                    MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(settings.tick_duration())),
+                );
+                app.insert_resource(settings);
+                app.insert_resource(MemoryServerTransport::new(client_id, server_connection));
+                app.insert_resource(ShutdownSignal(server_shutdown));
+                app.add_plugins(ServerPlugins);
+                app.add_systems(Update, shutdown_on_signal_system);
+                app.run();
+
+                info!("Integrated server stopped");
+            })
+            .expect("Could not spawn integrated server thread");
+
+        (
+            Self {
+                shutdown,
+                thread: Some(thread),
+            },
+            client_connection,
+        )
+    }
+
+    /// Asks the server to exit and waits until it has saved the world.
+    pub fn stop(mut self) {
+        self.shutdown_and_join();
+    }
+
+    fn shutdown_and_join(&mut self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                error!("Integrated server thread panicked");
+            }
+        }
+    }
+}
+
+impl Drop for IntegratedServer {
+    fn drop(&mut self) {
+        self.shutdown_and_join();
+    }
+}
+
+fn shutdown_on_signal_system(signal: Res<ShutdownSignal>, mut exit_events: EventWriter<AppExit>) {
+    if signal.0.load(Ordering::Relaxed) {
+        exit_events.send(AppExit::Success);
+    }
+}
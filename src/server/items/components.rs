@@ -0,0 +1,72 @@
+use crate::server::prelude::*;
+
+pub const ITEM_DROP_SIZE: f32 = 0.25;
+const GRAVITY: f32 = 20.0;
+const TERMINAL_VELOCITY: f32 = 40.0;
+/// Share of the horizontal velocity lost per second while lying on the ground.
+const GROUND_FRICTION: f32 = 8.0;
+
+/// Server side state of an entity with a `DroppedItem`, its `NetworkPosition` is the center of
+/// the bottom face.
+#[derive(Component)]
+pub struct ItemDrop {
+    pub count: u32,
+    pub velocity: Vec3,
+    pub spawned: Instant,
+}
+
+impl ItemDrop {
+    /// Applies gravity and moves the drop, stopping it at solid blocks. Returns the new position.
+    pub fn step(&mut self, chunk_manager: &ChunkManager, position: Vec3, delta: f32) -> Vec3 {
+        self.velocity.y = (self.velocity.y - GRAVITY * delta).max(-TERMINAL_VELOCITY);
+
+        let aabb = Aabb::from_feet(position, ITEM_DROP_SIZE, ITEM_DROP_SIZE);
+        let sweep = sweep_aabb(chunk_manager, &aabb, self.velocity * delta);
+
+        if sweep.collided.x {
+            self.velocity.x = 0.0;
+        }
+        if sweep.collided.z {
+            self.velocity.z = 0.0;
+        }
+        if sweep.collided.y {
+            self.velocity.y = 0.0;
+
+            let friction = (1.0 - GROUND_FRICTION * delta).max(0.0);
+            self.velocity.x *= friction;
+            self.velocity.z *= friction;
+        }
+
+        position + sweep.offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_item_drop_falls_onto_ground() {
+        let mut chunk_manager = ChunkManager::new();
+        chunk_manager.insert_chunk(Chunk::new(Vec3::ZERO));
+        for x in 0..CHUNK_SIZE {
+            for z in 0..CHUNK_SIZE {
+                chunk_manager.update_block(Vec3::new(x as f32, 4.0, z as f32), BlockId::Stone);
+            }
+        }
+
+        let mut drop = ItemDrop {
+            count: 1,
+            velocity: Vec3::new(2.0, 4.0, 0.0),
+            spawned: Instant::now(),
+        };
+        let mut position = Vec3::new(8.5, 10.0, 8.5);
+
+        for _ in 0..200 {
+            position = drop.step(&chunk_manager, position, 1.0 / 60.0);
+        }
+
+        assert!((position.y - 5.0).abs() < 0.01);
+        assert!(drop.velocity.length() < 0.01);
+    }
+}
@@ -0,0 +1,21 @@
+use crate::server::prelude::*;
+
+pub mod components;
+pub mod systems;
+
+pub struct ItemsPlugin;
+
+impl Plugin for ItemsPlugin {
+    fn build(&self, app: &mut App) {
+        app.replicate::<DroppedItem>();
+        app.add_systems(
+            Update,
+            (
+                items_systems::spawn_item_drops_system,
+                items_systems::simulate_item_drops_system,
+                items_systems::pickup_item_drops_system,
+            )
+                .chain(),
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use crate::server::prelude::*;
+
+/// Drops disappear when nobody picks them up for this long.
+const ITEM_DROP_LIFETIME: Duration = Duration::from_secs(300);
+/// Time before a new drop can be picked up, so it visibly pops out of the block first.
+const PICKUP_DELAY: Duration = Duration::from_millis(500);
+const PICKUP_RADIUS: f32 = 1.5;
+/// Height of the player center above the feet, pickup distance is measured from there.
+const PLAYER_CENTER_HEIGHT: f32 = 0.9;
+/// Smaller movements are not applied, so resting drops are not replicated every frame.
+const MIN_MOVEMENT: f32 = 1e-4;
+
+pub fn spawn_item_drops_system(
+    mut commands: Commands,
+    mut block_broken_events: EventReader<terrain_events::BlockBrokenEvent>,
+) {
+    for event in block_broken_events.read() {
+        let Some(item) = ItemId::dropped_by(event.block) else {
+            continue;
+        };
+
+        let position = event.position.floor() + Vec3::new(0.5, 0.25, 0.5);
+        let velocity = Vec3::new(
+            rand::random::<f32>() * 2.0 - 1.0,
+            4.0,
+            rand::random::<f32>() * 2.0 - 1.0,
+        );
+
+        commands.spawn((
+            Replicated,
+            NetworkPosition(position),
+            DroppedItem { item },
+            items_components::ItemDrop {
+                count: 1,
+                velocity,
+                spawned: Instant::now(),
+            },
+        ));
+    }
+}
+
+pub fn simulate_item_drops_system(
+    mut commands: Commands,
+    mut item_drops: Query<(
+        Entity,
+        &mut items_components::ItemDrop,
+        &mut NetworkPosition,
+    )>,
+    chunk_manager: Res<ChunkManager>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_secs();
+
+    for (entity, mut item_drop, mut position) in item_drops.iter_mut() {
+        if item_drop.spawned.elapsed() > ITEM_DROP_LIFETIME {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let next_position = item_drop.step(&chunk_manager, position.0, delta);
+        if next_position.distance(position.0) > MIN_MOVEMENT {
+            position.0 = next_position;
+        }
+    }
+}
+
+pub fn pickup_item_drops_system(
+    mut commands: Commands,
+    mut server: ResMut<RenetServer>,
+    mut item_drops: Query<(
+        Entity,
+        &DroppedItem,
+        &mut items_components::ItemDrop,
+        &NetworkPosition,
+    )>,
+    mut player_inventories: ResMut<player_resources::PlayerInventories>,
+    player_states: Res<player_resources::PlayerStates>,
+    player_game_modes: Res<player_resources::PlayerGameModes>,
+) {
+    for (client_id, inventory) in player_inventories.inventories.iter_mut() {
+        let Some(player_state) = player_states.players.get(client_id) else {
+            continue;
+        };
+        if !player_game_modes.get(client_id).can_interact() {
+            continue;
+        }
+        let center = player_state.position + Vec3::Y * PLAYER_CENTER_HEIGHT;
+        let mut inventory_changed = false;
+
+        for (entity, dropped_item, mut item_drop, position) in item_drops.iter_mut() {
+            if item_drop.count == 0
+                || item_drop.spawned.elapsed() < PICKUP_DELAY
+                || position.0.distance(center) > PICKUP_RADIUS
+            {
+                continue;
+            }
+
+            let remaining = inventory.add(dropped_item.item, item_drop.count);
+            if remaining == item_drop.count {
+                continue;
+            }
+
+            item_drop.count = remaining;
+            inventory_changed = true;
+            if remaining == 0 {
+                commands.entity(entity).despawn();
+            }
+        }
+
+        if inventory_changed {
+            server.send_message(
+                *client_id,
+                DefaultChannel::ReliableOrdered,
+                bincode::serialize(&NetworkingMessage::InventorySync(inventory.clone())).unwrap(),
+            );
+        }
+    }
+}
@@ -1,22 +1,33 @@
-pub mod chat;
-pub mod networking;
-pub mod player;
-pub mod prelude;
-pub mod terrain;
-
 #[cfg(feature = "egui_layer")]
 use bevy::DefaultPlugins;
 
+#[cfg(not(feature = "egui_layer"))]
+use bevy::app::{PluginGroup, ScheduleRunnerPlugin};
 #[cfg(not(feature = "egui_layer"))]
 use bevy::log::LogPlugin;
 
-use crate::prelude::*;
+use rsmc::server::prelude::*;
+use rsmc::server::ServerPlugins;
 
 fn main() {
+    let settings = match settings::ServerSettings::load(std::env::args().skip(1)) {
+        Ok(settings) => settings,
+        Err(settings::SettingsError::HelpRequested) => {
+            println!("{}", settings::USAGE);
+            return;
+        }
+        Err(error) => {
+            eprintln!("Invalid server configuration: {}", error);
+            std::process::exit(1);
+        }
+    };
+
     let mut app = App::new();
     #[cfg(not(feature = "egui_layer"))]
     {
-        app.add_plugins(MinimalPlugins);
+        app.add_plugins(
+            MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(settings.tick_duration())),
+        );
         app.add_plugins(LogPlugin::default());
     }
 
@@ -27,12 +38,9 @@ fn main() {
         app.add_plugins(EguiPlugin);
     }
 
-    app.add_plugins(player::PlayerPlugin);
-    app.add_plugins(networking::NetworkingPlugin);
-    app.add_plugins(terrain::TerrainPlugin);
-
-    #[cfg(feature = "chat")]
-    app.add_plugins(chat::ChatPlugin);
+    info!("Starting server with {:?}", settings);
+    app.insert_resource(settings);
+    app.add_plugins(ServerPlugins);
 
     app.run();
 }
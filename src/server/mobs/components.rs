@@ -0,0 +1,51 @@
+use crate::server::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MobBehaviour {
+    Idle,
+    Wander,
+    /// Running away from the position of a player.
+    Flee {
+        from: Vec3,
+    },
+    Follow(ClientId),
+}
+
+/// Server side state of an entity with a `Mob`.
+#[derive(Component)]
+pub struct MobBrain {
+    pub behaviour: MobBehaviour,
+    /// Blocks the mob walks through, the feet enter the front block next.
+    pub path: VecDeque<IVec3>,
+    pub velocity: Vec3,
+    pub on_ground: bool,
+    /// When an idle or wandering mob picks its next behaviour, or a fleeing mob calms down.
+    pub next_decision: Instant,
+    /// When a following mob searches a new path to the moving player.
+    pub next_repath: Instant,
+}
+
+impl MobBrain {
+    pub fn new() -> Self {
+        let now = Instant::now();
+
+        Self {
+            behaviour: MobBehaviour::Idle,
+            path: VecDeque::new(),
+            velocity: Vec3::ZERO,
+            on_ground: false,
+            next_decision: now,
+            next_repath: now,
+        }
+    }
+
+    pub fn set_path(&mut self, path: Option<Vec<IVec3>>) {
+        self.path = path.unwrap_or_default().into();
+    }
+}
+
+impl Default for MobBrain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
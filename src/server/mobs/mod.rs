@@ -0,0 +1,24 @@
+use crate::server::prelude::*;
+
+pub mod components;
+pub mod pathfinding;
+pub mod spawning;
+pub mod systems;
+
+pub struct MobsPlugin;
+
+impl Plugin for MobsPlugin {
+    fn build(&self, app: &mut App) {
+        app.replicate::<Mob>();
+        app.add_systems(
+            Update,
+            (
+                mobs_systems::spawn_mobs_system,
+                mobs_systems::despawn_far_mobs_system,
+                mobs_systems::update_mob_behaviour_system,
+                mobs_systems::move_mobs_system,
+            )
+                .chain(),
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::server::prelude::*;
+
+/// Deepest ledge a mob walks down.
+pub const MAX_DROP: i32 = 3;
+/// Nodes expanded before the search gives up, keeps unreachable goals cheap.
+const MAX_SEARCH_NODES: usize = 1024;
+const STEP_UP_COST: f32 = 1.5;
+const DROP_COST: f32 = 0.5;
+
+fn is_solid(chunk_manager: &ChunkManager, position: IVec3) -> bool {
+    chunk_manager
+        .get_block(position.as_vec3())
+        .is_some_and(|block| block.is_solid())
+}
+
+/// Blocks of unloaded chunks are not passable, mobs stay inside the loaded world.
+fn is_passable(chunk_manager: &ChunkManager, position: IVec3) -> bool {
+    chunk_manager
+        .get_block(position.as_vec3())
+        .is_some_and(|block| !block.is_solid())
+}
+
+fn is_clear(chunk_manager: &ChunkManager, position: IVec3, height: i32) -> bool {
+    (0..height).all(|offset| is_passable(chunk_manager, position + IVec3::Y * offset))
+}
+
+/// Whether a mob `height` blocks tall can stand with its feet in the block.
+pub fn is_walkable(chunk_manager: &ChunkManager, position: IVec3, height: i32) -> bool {
+    is_solid(chunk_manager, position - IVec3::Y) && is_clear(chunk_manager, position, height)
+}
+
+/// Positions reachable in one move with their cost: walking, stepping up one block or dropping
+/// down at most `MAX_DROP` blocks.
+fn neighbours(chunk_manager: &ChunkManager, position: IVec3, height: i32) -> Vec<(IVec3, f32)> {
+    let mut neighbours = Vec::new();
+
+    for direction in [IVec3::X, IVec3::NEG_X, IVec3::Z, IVec3::NEG_Z] {
+        let next = position + direction;
+
+        if is_walkable(chunk_manager, next, height) {
+            neighbours.push((next, 1.0));
+            continue;
+        }
+
+        // Stepping up needs room above the head before moving forward
+        let above = next + IVec3::Y;
+        if is_passable(chunk_manager, position + IVec3::Y * height)
+            && is_walkable(chunk_manager, above, height)
+        {
+            neighbours.push((above, STEP_UP_COST));
+            continue;
+        }
+
+        if !is_clear(chunk_manager, next, height) {
+            continue;
+        }
+
+        for drop in 1..=MAX_DROP {
+            let below = next - IVec3::Y * drop;
+            if is_walkable(chunk_manager, below, height) {
+                neighbours.push((below, 1.0 + DROP_COST * drop as f32));
+                break;
+            }
+            if !is_passable(chunk_manager, below) {
+                break;
+            }
+        }
+    }
+
+    neighbours
+}
+
+fn heuristic(position: IVec3, goal: IVec3) -> f32 {
+    let difference = (goal - position).abs();
+    (difference.x + difference.z) as f32 + difference.y as f32 * DROP_COST
+}
+
+#[derive(PartialEq)]
+struct OpenNode {
+    estimate: f32,
+    position: IVec3,
+}
+
+impl Eq for OpenNode {}
+
+impl Ord for OpenNode {
+    /// Reversed so the binary heap pops the lowest estimate first.
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .estimate
+            .total_cmp(&self.estimate)
+            .then_with(|| self.position.to_array().cmp(&other.position.to_array()))
+    }
+}
+
+impl PartialOrd for OpenNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A* search over walkable blocks, returns the blocks to walk through after `start`.
+///
+/// When the goal cannot be reached within the search limit, the path leads to the explored
+/// block closest to the goal. Returns `None` if no block gets closer.
+pub fn find_path(
+    chunk_manager: &ChunkManager,
+    start: IVec3,
+    goal: IVec3,
+    height: i32,
+) -> Option<Vec<IVec3>> {
+    let mut open = BinaryHeap::new();
+    let mut came_from: HashMap<IVec3, IVec3> = HashMap::new();
+    let mut costs: HashMap<IVec3, f32> = HashMap::new();
+    let mut closest = (heuristic(start, goal), start);
+
+    open.push(OpenNode {
+        estimate: closest.0,
+        position: start,
+    });
+    costs.insert(start, 0.0);
+
+    let mut expanded = 0;
+    while let Some(OpenNode { position, .. }) = open.pop() {
+        if position == goal {
+            closest = (0.0, goal);
+            break;
+        }
+
+        expanded += 1;
+        if expanded > MAX_SEARCH_NODES {
+            break;
+        }
+
+        let cost = costs[&position];
+        for (next, step_cost) in neighbours(chunk_manager, position, height) {
+            let next_cost = cost + step_cost;
+            if costs.get(&next).is_some_and(|known| *known <= next_cost) {
+                continue;
+            }
+
+            costs.insert(next, next_cost);
+            came_from.insert(next, position);
+
+            let distance = heuristic(next, goal);
+            if distance < closest.0 {
+                closest = (distance, next);
+            }
+            open.push(OpenNode {
+                estimate: next_cost + distance,
+                position: next,
+            });
+        }
+    }
+
+    let (_, mut position) = closest;
+    if position == start {
+        return None;
+    }
+
+    let mut path = vec![position];
+    while let Some(previous) = came_from.get(&position) {
+        if *previous == start {
+            break;
+        }
+        position = *previous;
+        path.push(position);
+    }
+    path.reverse();
+
+    Some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk_manager_with_floor() -> ChunkManager {
+        let mut chunk_manager = ChunkManager::new();
+        chunk_manager.insert_chunk(Chunk::new(Vec3::ZERO));
+
+        for x in 0..16 {
+            for z in 0..16 {
+                chunk_manager.update_block(Vec3::new(x as f32, 4.0, z as f32), BlockId::Stone);
+            }
+        }
+
+        chunk_manager
+    }
+
+    #[test]
+    fn test_find_path_on_flat_ground() {
+        let chunk_manager = chunk_manager_with_floor();
+        let path = find_path(&chunk_manager, IVec3::new(2, 5, 2), IVec3::new(6, 5, 4), 1).unwrap();
+
+        assert_eq!(path.len(), 6);
+        assert_eq!(path.last(), Some(&IVec3::new(6, 5, 4)));
+    }
+
+    #[test]
+    fn test_find_path_walks_around_walls() {
+        let mut chunk_manager = chunk_manager_with_floor();
+        for z in 0..6 {
+            for y in 5..7 {
+                chunk_manager.update_block(Vec3::new(4.0, y as f32, z as f32), BlockId::Stone);
+            }
+        }
+
+        let path = find_path(&chunk_manager, IVec3::new(2, 5, 2), IVec3::new(6, 5, 2), 1).unwrap();
+
+        assert_eq!(path.last(), Some(&IVec3::new(6, 5, 2)));
+        assert!(path
+            .iter()
+            .all(|position| position.x != 4 || position.z >= 6));
+    }
+
+    #[test]
+    fn test_find_path_steps_up_and_drops_down() {
+        let mut chunk_manager = chunk_manager_with_floor();
+        chunk_manager.update_block(Vec3::new(4.0, 5.0, 2.0), BlockId::Stone);
+        for x in 6..16 {
+            for z in 0..16 {
+                chunk_manager.update_block(Vec3::new(x as f32, 4.0, z as f32), BlockId::Air);
+                chunk_manager.update_block(Vec3::new(x as f32, 2.0, z as f32), BlockId::Stone);
+            }
+        }
+
+        let path = find_path(&chunk_manager, IVec3::new(3, 5, 2), IVec3::new(7, 3, 2), 1).unwrap();
+
+        assert_eq!(
+            path,
+            vec![
+                IVec3::new(4, 6, 2),
+                IVec3::new(5, 5, 2),
+                IVec3::new(6, 3, 2),
+                IVec3::new(7, 3, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_path_respects_mob_height() {
+        let mut chunk_manager = chunk_manager_with_floor();
+        for z in 0..16 {
+            chunk_manager.update_block(Vec3::new(4.0, 6.0, z as f32), BlockId::Stone);
+        }
+
+        let start = IVec3::new(2, 5, 2);
+        let goal = IVec3::new(6, 5, 2);
+
+        assert!(find_path(&chunk_manager, start, goal, 1).is_some());
+        assert!(find_path(&chunk_manager, start, goal, 2).is_none_or(|path| !path.contains(&goal)));
+    }
+}
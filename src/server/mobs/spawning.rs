@@ -0,0 +1,67 @@
+use crate::server::prelude::*;
+
+use super::pathfinding::is_walkable;
+
+/// Blocks above and below the player searched for a surface.
+const SURFACE_SEARCH_RANGE: i32 = 16;
+
+/// Finds the highest block in the column the mob can stand on, if its surface block is one the
+/// mob spawns on.
+pub fn find_spawn_position(
+    chunk_manager: &ChunkManager,
+    kind: MobKind,
+    column: IVec2,
+    around_y: i32,
+) -> Option<IVec3> {
+    let height = kind.size().y.ceil() as i32;
+
+    (around_y - SURFACE_SEARCH_RANGE..=around_y + SURFACE_SEARCH_RANGE)
+        .rev()
+        .map(|y| IVec3::new(column.x, y, column.y))
+        .find(|position| is_walkable(chunk_manager, *position, height))
+        .filter(|position| {
+            chunk_manager
+                .get_block((*position - IVec3::Y).as_vec3())
+                .is_some_and(|block| kind.spawn_blocks().contains(&block))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk_manager_with_surface(surface: BlockId) -> ChunkManager {
+        let mut chunk_manager = ChunkManager::new();
+        chunk_manager.insert_chunk(Chunk::new(Vec3::ZERO));
+
+        for x in 0..8 {
+            for z in 0..8 {
+                chunk_manager.update_block(Vec3::new(x as f32, 3.0, z as f32), BlockId::Stone);
+                chunk_manager.update_block(Vec3::new(x as f32, 4.0, z as f32), surface);
+            }
+        }
+
+        chunk_manager
+    }
+
+    #[test]
+    fn test_mobs_spawn_on_their_surface_blocks() {
+        let chunk_manager = chunk_manager_with_surface(BlockId::Grass);
+
+        assert_eq!(
+            find_spawn_position(&chunk_manager, MobKind::Sheep, IVec2::new(2, 2), 10),
+            Some(IVec3::new(2, 5, 2))
+        );
+    }
+
+    #[test]
+    fn test_mobs_do_not_spawn_on_other_blocks() {
+        let chunk_manager = chunk_manager_with_surface(BlockId::Dirt);
+
+        assert_eq!(
+            find_spawn_position(&chunk_manager, MobKind::Sheep, IVec2::new(2, 2), 10),
+            None
+        );
+        assert!(find_spawn_position(&chunk_manager, MobKind::Pig, IVec2::new(2, 2), 10).is_some());
+    }
+}
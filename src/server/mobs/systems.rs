@@ -0,0 +1,301 @@
+use std::f32::consts::TAU;
+
+use crate::server::prelude::*;
+
+const SPAWN_INTERVAL: Duration = Duration::from_secs(2);
+/// Mobs within this distance of a player count towards its limit.
+const MOBS_PER_PLAYER: usize = 8;
+const MIN_SPAWN_DISTANCE: f32 = 16.0;
+const MAX_SPAWN_DISTANCE: f32 = 40.0;
+/// Horizontal distance to the closest player at which mobs are removed.
+const DESPAWN_DISTANCE: f32 = 64.0;
+
+const FOLLOW_RANGE: f32 = 10.0;
+/// Following mobs stop this close to the player.
+const FOLLOW_DISTANCE: f32 = 2.0;
+const FLEE_RANGE: f32 = 4.0;
+const FLEE_DISTANCE: f32 = 8.0;
+const FLEE_DURATION: Duration = Duration::from_secs(3);
+const FLEE_SPEED_MULTIPLIER: f32 = 1.6;
+const WANDER_RANGE: i32 = 6;
+const REPATH_INTERVAL: Duration = Duration::from_secs(1);
+/// Share of decisions in which a mob starts wandering instead of standing around.
+const WANDER_CHANCE: f32 = 0.6;
+
+const GRAVITY: f32 = 20.0;
+const TERMINAL_VELOCITY: f32 = 40.0;
+/// Rises a bit more than one block.
+const JUMP_VELOCITY: f32 = 7.5;
+const WAYPOINT_RADIUS: f32 = 0.2;
+/// Mobs pushed further than this from their next waypoint give up the path.
+const MAX_WAYPOINT_DISTANCE: f32 = 2.5;
+/// Smaller movements are not applied, so standing mobs are not replicated every frame.
+const MIN_MOVEMENT: f32 = 1e-4;
+const MIN_ROTATION: f32 = 0.05;
+
+/// Random value between `-range` and `range`.
+fn random_offset(range: i32) -> i32 {
+    (rand::random::<u32>() % (range as u32 * 2 + 1)) as i32 - range
+}
+
+pub fn spawn_mobs_system(
+    mut commands: Commands,
+    mobs: Query<&NetworkPosition, With<Mob>>,
+    player_states: Res<player_resources::PlayerStates>,
+    chunk_manager: Res<ChunkManager>,
+    settings: Res<settings::ServerSettings>,
+    mut last_spawn: Local<Option<Instant>>,
+) {
+    if last_spawn.is_some_and(|last_spawn| last_spawn.elapsed() < SPAWN_INTERVAL) {
+        return;
+    }
+    *last_spawn = Some(Instant::now());
+
+    let mut mob_count = mobs.iter().count();
+
+    for player_state in player_states.players.values() {
+        if mob_count >= settings.max_mobs {
+            return;
+        }
+
+        let nearby_mobs = mobs
+            .iter()
+            .filter(|position| position.0.distance(player_state.position) < MAX_SPAWN_DISTANCE)
+            .count();
+        if nearby_mobs >= MOBS_PER_PLAYER {
+            continue;
+        }
+
+        let angle = rand::random::<f32>() * TAU;
+        let distance =
+            MIN_SPAWN_DISTANCE + rand::random::<f32>() * (MAX_SPAWN_DISTANCE - MIN_SPAWN_DISTANCE);
+        let column = (Vec2::new(player_state.position.x, player_state.position.z)
+            + Vec2::from_angle(angle) * distance)
+            .floor()
+            .as_ivec2();
+
+        let kind = MobKind::ALL[rand::random::<u32>() as usize % MobKind::ALL.len()];
+        let Some(position) = mobs_spawning::find_spawn_position(
+            &chunk_manager,
+            kind,
+            column,
+            player_state.position.y.floor() as i32,
+        ) else {
+            continue;
+        };
+
+        debug!("Spawning {:?} at {}", kind, position);
+        commands.spawn((
+            Replicated,
+            Mob { kind },
+            NetworkPosition(position.as_vec3() + Vec3::new(0.5, 0.0, 0.5)),
+            NetworkRotation(Quat::from_rotation_y(rand::random::<f32>() * TAU)),
+            mobs_components::MobBrain::new(),
+        ));
+        mob_count += 1;
+    }
+}
+
+pub fn despawn_far_mobs_system(
+    mut commands: Commands,
+    mobs: Query<(Entity, &NetworkPosition), With<Mob>>,
+    player_states: Res<player_resources::PlayerStates>,
+) {
+    for (entity, position) in mobs.iter() {
+        let near_player = player_states.players.values().any(|player_state| {
+            Vec2::new(
+                player_state.position.x - position.0.x,
+                player_state.position.z - position.0.z,
+            )
+            .length()
+                < DESPAWN_DISTANCE
+        });
+
+        if !near_player {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+pub fn update_mob_behaviour_system(
+    mut mobs: Query<(&Mob, &NetworkPosition, &mut mobs_components::MobBrain)>,
+    player_states: Res<player_resources::PlayerStates>,
+    player_inventories: Res<player_resources::PlayerInventories>,
+    player_game_modes: Res<player_resources::PlayerGameModes>,
+    chunk_manager: Res<ChunkManager>,
+) {
+    let now = Instant::now();
+
+    for (mob, position, mut brain) in mobs.iter_mut() {
+        let kind = mob.kind;
+        let height = kind.size().y.ceil() as i32;
+        let feet = position.0.floor().as_ivec3();
+
+        let holds_lure = |client_id: &ClientId| {
+            kind.lure_item().is_some_and(|lure_item| {
+                player_inventories
+                    .inventories
+                    .get(client_id)
+                    .and_then(|inventory| inventory.selected_item())
+                    == Some(lure_item)
+            })
+        };
+
+        let nearest_player = player_states
+            .players
+            .iter()
+            .filter(|(client_id, _)| player_game_modes.get(client_id).is_visible())
+            .map(|(client_id, player_state)| {
+                (
+                    *client_id,
+                    player_state.position,
+                    player_state.position.distance(position.0),
+                )
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2));
+
+        match nearest_player {
+            Some((client_id, player_position, distance))
+                if distance < FOLLOW_RANGE && holds_lure(&client_id) =>
+            {
+                let behaviour = mobs_components::MobBehaviour::Follow(client_id);
+                if brain.behaviour != behaviour || now >= brain.next_repath {
+                    brain.behaviour = behaviour;
+                    brain.next_repath = now + REPATH_INTERVAL;
+                    brain.next_decision = now;
+
+                    match distance < FOLLOW_DISTANCE {
+                        true => brain.path.clear(),
+                        false => brain.set_path(mobs_pathfinding::find_path(
+                            &chunk_manager,
+                            feet,
+                            player_position.floor().as_ivec3(),
+                            height,
+                        )),
+                    }
+                }
+            }
+            Some((_, player_position, distance)) if kind.is_skittish() && distance < FLEE_RANGE => {
+                let fleeing = matches!(brain.behaviour, mobs_components::MobBehaviour::Flee { .. });
+                if !fleeing || brain.path.is_empty() {
+                    let away = (position.0 - player_position)
+                        .with_y(0.0)
+                        .try_normalize()
+                        .unwrap_or(Vec3::X);
+                    let goal = feet + (away * FLEE_DISTANCE).round().as_ivec3();
+
+                    brain.behaviour = mobs_components::MobBehaviour::Flee {
+                        from: player_position,
+                    };
+                    brain.set_path(mobs_pathfinding::find_path(
+                        &chunk_manager,
+                        feet,
+                        goal,
+                        height,
+                    ));
+                }
+                brain.next_decision = now + FLEE_DURATION;
+            }
+            _ => {
+                if now < brain.next_decision {
+                    continue;
+                }
+
+                brain.next_decision =
+                    now + Duration::from_secs_f32(3.0 + rand::random::<f32>() * 5.0);
+
+                if rand::random::<f32>() < WANDER_CHANCE {
+                    let offset =
+                        IVec3::new(random_offset(WANDER_RANGE), 0, random_offset(WANDER_RANGE));
+
+                    brain.behaviour = mobs_components::MobBehaviour::Wander;
+                    brain.set_path(mobs_pathfinding::find_path(
+                        &chunk_manager,
+                        feet,
+                        feet + offset,
+                        height,
+                    ));
+                } else {
+                    brain.behaviour = mobs_components::MobBehaviour::Idle;
+                    brain.path.clear();
+                }
+            }
+        }
+    }
+}
+
+pub fn move_mobs_system(
+    mut mobs: Query<(
+        &Mob,
+        &mut mobs_components::MobBrain,
+        &mut NetworkPosition,
+        &mut NetworkRotation,
+    )>,
+    chunk_manager: Res<ChunkManager>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_secs();
+
+    for (mob, mut brain, mut position, mut rotation) in mobs.iter_mut() {
+        // Mobs in chunks that are not loaded would fall forever
+        if chunk_manager.get_block(position.0).is_none() {
+            continue;
+        }
+
+        let size = mob.kind.size();
+        let current = position.0;
+
+        while let Some(waypoint) = brain.path.front() {
+            let target = waypoint.as_vec3() + Vec3::new(0.5, 0.0, 0.5);
+            let horizontal_distance =
+                Vec2::new(target.x - current.x, target.z - current.z).length();
+
+            if horizontal_distance < WAYPOINT_RADIUS && (target.y - current.y).abs() < 0.5 {
+                brain.path.pop_front();
+            } else if horizontal_distance > MAX_WAYPOINT_DISTANCE {
+                brain.path.clear();
+            } else {
+                break;
+            }
+        }
+
+        let speed = match brain.behaviour {
+            mobs_components::MobBehaviour::Flee { .. } => mob.kind.speed() * FLEE_SPEED_MULTIPLIER,
+            _ => mob.kind.speed(),
+        };
+
+        let mut walk_direction = Vec3::ZERO;
+        if let Some(waypoint) = brain.path.front() {
+            let target = waypoint.as_vec3() + Vec3::new(0.5, 0.0, 0.5);
+            walk_direction = (target - current).with_y(0.0).normalize_or_zero();
+
+            if target.y > current.y + 0.5 && brain.on_ground {
+                brain.velocity.y = JUMP_VELOCITY;
+            }
+        }
+
+        brain.velocity.x = walk_direction.x * speed;
+        brain.velocity.z = walk_direction.z * speed;
+        brain.velocity.y = (brain.velocity.y - GRAVITY * delta).max(-TERMINAL_VELOCITY);
+
+        let aabb = Aabb::from_feet(current, size.x, size.y);
+        let sweep = sweep_aabb(&chunk_manager, &aabb, brain.velocity * delta);
+
+        brain.on_ground = sweep.collided.y && brain.velocity.y < 0.0;
+        if sweep.collided.y {
+            brain.velocity.y = 0.0;
+        }
+
+        let next = current + sweep.offset;
+        if next.distance(current) > MIN_MOVEMENT {
+            position.0 = next;
+        }
+
+        if walk_direction != Vec3::ZERO {
+            let facing = Quat::from_rotation_y(f32::atan2(-walk_direction.x, -walk_direction.z));
+            if facing.angle_between(rotation.0) > MIN_ROTATION {
+                rotation.0 = facing;
+            }
+        }
+    }
+}
@@ -0,0 +1,38 @@
+pub mod chat;
+pub mod diagnostics;
+pub mod integrated;
+pub mod items;
+pub mod mobs;
+pub mod moderation;
+pub mod networking;
+pub mod player;
+pub mod prelude;
+pub mod replication;
+pub mod settings;
+pub mod terrain;
+
+pub use integrated::IntegratedServer;
+
+use bevy::app::{PluginGroup, PluginGroupBuilder};
+
+/// Every plugin making up the game server, shared by the dedicated and the integrated server.
+pub struct ServerPlugins;
+
+impl PluginGroup for ServerPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        let group = PluginGroupBuilder::start::<Self>()
+            .add(player::PlayerPlugin)
+            .add(moderation::ModerationPlugin)
+            .add(networking::NetworkingPlugin)
+            .add(replication::ReplicationPlugin)
+            .add(terrain::TerrainPlugin)
+            .add(items::ItemsPlugin)
+            .add(mobs::MobsPlugin)
+            .add(diagnostics::DiagnosticsPlugin);
+
+        #[cfg(feature = "chat")]
+        let group = group.add(chat::ChatPlugin);
+
+        group
+    }
+}
@@ -0,0 +1,21 @@
+use crate::server::prelude::*;
+
+pub mod resources;
+pub mod systems;
+
+/// Keeps the banned and muted players of the world and saves them to the world directory.
+pub struct ModerationPlugin;
+
+impl Plugin for ModerationPlugin {
+    fn build(&self, app: &mut App) {
+        info!("Building ModerationPlugin");
+
+        let world_directory = app
+            .world()
+            .resource::<settings::ServerSettings>()
+            .world_directory
+            .clone();
+        app.insert_resource(moderation_resources::ModerationList::load(&world_directory));
+        app.add_systems(Update, moderation_systems::save_moderation_list_system);
+    }
+}
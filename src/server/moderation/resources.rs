@@ -0,0 +1,165 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::server::prelude::*;
+
+const MODERATION_FILE_NAME: &str = "moderation.toml";
+
+/// Players and addresses operators banned or muted, saved in the world directory.
+#[derive(Resource, Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(default)]
+pub struct ModerationList {
+    pub banned_names: BTreeSet<String>,
+    pub banned_addresses: BTreeSet<IpAddr>,
+    pub muted_names: BTreeSet<String>,
+    /// Addresses banned or muted players were connected from, so a new name does not lift the
+    /// ban or mute.
+    pub name_addresses: BTreeMap<String, IpAddr>,
+}
+
+impl ModerationList {
+    pub fn file_path(world_directory: &Path) -> PathBuf {
+        world_directory.join(MODERATION_FILE_NAME)
+    }
+
+    /// Loads the list of a world, falling back to an empty list when the file is missing or
+    /// invalid.
+    pub fn load(world_directory: &Path) -> Self {
+        let path = Self::file_path(world_directory);
+        if !path.exists() {
+            return Self::default();
+        }
+
+        match fs::read_to_string(&path)
+            .map_err(|error| error.to_string())
+            .and_then(|contents| Self::from_toml(&contents).map_err(|error| error.to_string()))
+        {
+            Ok(list) => list,
+            Err(error) => {
+                error!("Could not load {}: {}", path.display(), error);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self, world_directory: &Path) -> io::Result<()> {
+        fs::create_dir_all(world_directory)?;
+        let contents = self.to_toml().map_err(io::Error::other)?;
+        fs::write(Self::file_path(world_directory), contents)
+    }
+
+    pub fn from_toml(contents: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(contents)
+    }
+
+    pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string(self)
+    }
+
+    /// Whether a player with this name or connecting from this address may not join.
+    pub fn is_banned(&self, name: Option<&str>, address: Option<IpAddr>) -> bool {
+        name.is_some_and(|name| self.banned_names.contains(name))
+            || address.is_some_and(|address| {
+                self.banned_addresses.contains(&address)
+                    || self.has_address_of(&self.banned_names, address)
+            })
+    }
+
+    pub fn is_muted(&self, name: &str, address: Option<IpAddr>) -> bool {
+        self.muted_names.contains(name)
+            || address.is_some_and(|address| self.has_address_of(&self.muted_names, address))
+    }
+
+    /// Remembers the address of a banned or muted player, unknown addresses are ignored.
+    pub fn record_address(&mut self, name: &str, address: Option<IpAddr>) {
+        if let Some(address) = address {
+            self.name_addresses.insert(name.to_string(), address);
+        }
+    }
+
+    /// Forgets the addresses of players that are neither banned nor muted anymore.
+    pub fn remove_unused_addresses(&mut self) {
+        let (banned_names, muted_names) = (&self.banned_names, &self.muted_names);
+        self.name_addresses
+            .retain(|name, _| banned_names.contains(name) || muted_names.contains(name));
+    }
+
+    fn has_address_of(&self, names: &BTreeSet<String>, address: IpAddr) -> bool {
+        names
+            .iter()
+            .any(|name| self.name_addresses.get(name) == Some(&address))
+    }
+}
+
+/// Address a client connected from, unknown for clients of the in-memory transport.
+pub fn client_address(
+    transport: Option<&NetcodeServerTransport>,
+    client_id: ClientId,
+) -> Option<IpAddr> {
+    transport
+        .and_then(|transport| transport.client_addr(client_id))
+        .map(|address| address.ip())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_moderation_list_round_trip() {
+        let world_directory =
+            std::env::temp_dir().join(format!("rsmc-world-{}", rand::random::<u64>()));
+
+        let mut list = ModerationList::default();
+        list.banned_names.insert("Griefer".to_string());
+        list.banned_addresses
+            .insert("10.0.0.7".parse::<IpAddr>().unwrap());
+        list.muted_names.insert("Spammer".to_string());
+        list.record_address("Spammer", "10.0.0.9".parse().ok());
+        list.save(&world_directory).unwrap();
+
+        assert_eq!(ModerationList::load(&world_directory), list);
+        assert_eq!(
+            ModerationList::load(&world_directory.join("missing")),
+            ModerationList::default()
+        );
+
+        fs::remove_dir_all(world_directory).unwrap();
+    }
+
+    #[test]
+    fn test_is_banned_by_name_or_address() {
+        let mut list = ModerationList::default();
+        list.banned_names.insert("Griefer".to_string());
+        list.banned_addresses
+            .insert("10.0.0.7".parse::<IpAddr>().unwrap());
+
+        assert!(list.is_banned(Some("Griefer"), None));
+        assert!(list.is_banned(None, "10.0.0.7".parse().ok()));
+        assert!(!list.is_banned(Some("griefer"), "10.0.0.8".parse().ok()));
+        assert!(!list.is_banned(None, None));
+    }
+
+    #[test]
+    fn test_renamed_players_stay_banned_and_muted() {
+        let mut list = ModerationList::default();
+        list.banned_names.insert("Griefer".to_string());
+        list.record_address("Griefer", "10.0.0.7".parse().ok());
+        list.muted_names.insert("Spammer".to_string());
+        list.record_address("Spammer", "10.0.0.9".parse().ok());
+
+        assert!(list.is_banned(Some("Builder"), "10.0.0.7".parse().ok()));
+        assert!(list.is_muted("Chatter", "10.0.0.9".parse().ok()));
+        assert!(!list.is_muted("Chatter", "10.0.0.7".parse().ok()));
+
+        list.muted_names.remove("Spammer");
+        list.remove_unused_addresses();
+        assert!(!list.is_muted("Chatter", "10.0.0.9".parse().ok()));
+        assert_eq!(list.name_addresses.len(), 1);
+    }
+}
@@ -0,0 +1,15 @@
+use crate::server::prelude::*;
+
+pub fn save_moderation_list_system(
+    moderation: Res<moderation_resources::ModerationList>,
+    settings: Res<settings::ServerSettings>,
+) {
+    if !moderation.is_changed() || moderation.is_added() {
+        return;
+    }
+
+    match moderation.save(&settings.world_directory) {
+        Ok(()) => info!("Saved moderation list"),
+        Err(error) => error!("Could not save moderation list: {}", error),
+    }
+}
@@ -2,9 +2,7 @@ pub mod systems;
 
 use crate::connection_config;
 
-use crate::prelude::*;
-
-const SERVER_ADDR: &str = "127.0.0.1:5000";
+use crate::server::prelude::*;
 
 pub struct NetworkingPlugin;
 
@@ -32,22 +30,40 @@ impl Plugin for NetworkingPlugin {
 
         app.insert_resource(server);
 
-        app.add_plugins(NetcodeServerPlugin);
-        let server_addr = SERVER_ADDR.parse().unwrap();
-        let socket = UdpSocket::bind(server_addr).unwrap();
-        let server_config = ServerConfig {
-            current_time: SystemTime::now()
-                .duration_since(SystemTime::UNIX_EPOCH)
-                .unwrap(),
-            max_clients: 64,
-            protocol_id: 0,
-            public_addresses: vec![server_addr],
-            authentication: ServerAuthentication::Unsecure,
-        };
-        let transport = NetcodeServerTransport::new(server_config, socket).unwrap();
-        app.insert_resource(transport);
+        if app.world().contains_resource::<MemoryServerTransport>() {
+            info!("Using memory transport");
+            app.add_plugins(MemoryServerTransportPlugin);
+        } else {
+            add_netcode_transport(app);
+        }
 
         app.add_systems(Update, networking_systems::receive_message_system);
         app.add_systems(Update, networking_systems::handle_events_system);
     }
 }
+
+fn add_netcode_transport(app: &mut App) {
+    let settings = app.world().resource::<settings::ServerSettings>();
+    let bind_addr = settings.bind_socket_address();
+    let public_addr = settings.public_socket_address();
+    let max_clients = settings.max_clients;
+
+    app.add_plugins(NetcodeServerPlugin);
+    let socket = UdpSocket::bind(bind_addr)
+        .unwrap_or_else(|error| panic!("Could not bind to {}: {}", bind_addr, error));
+    info!(
+        "Listening on {} (public address {})",
+        bind_addr, public_addr
+    );
+    let server_config = ServerConfig {
+        current_time: SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap(),
+        max_clients,
+        protocol_id: 0,
+        public_addresses: vec![public_addr],
+        authentication: ServerAuthentication::Unsecure,
+    };
+    let transport = NetcodeServerTransport::new(server_config, socket).unwrap();
+    app.insert_resource(transport);
+}
@@ -1,27 +1,269 @@
-use crate::prelude::*;
+use crate::server::prelude::*;
 
+/// Most chunks answered per request, clients request them in batches of 16.
+const MAX_CHUNK_BATCH_SIZE: usize = 64;
+
+/// Writers for events about players sent by their clients.
+#[derive(SystemParam)]
+pub struct PlayerEventWriters<'w> {
+    moved: EventWriter<'w, player_events::PlayerMoveEvent>,
+    joined: EventWriter<'w, player_events::PlayerJoinedEvent>,
+    respawn: EventWriter<'w, player_events::PlayerRespawnEvent>,
+}
+
+/// Writers for chat messages and commands sent by clients, limited to a rate per client.
+#[cfg(feature = "chat")]
+#[derive(SystemParam)]
+pub struct ChatMessageWriters<'w> {
+    messages: EventWriter<'w, chat_events::PlayerChatMessageSendEvent>,
+    commands: EventWriter<'w, chat_events::ChatCommandEvent>,
+    history: ResMut<'w, chat_resources::ChatHistory>,
+    rate_limiter: ResMut<'w, chat_resources::ChatRateLimiter>,
+    settings: Res<'w, settings::ServerSettings>,
+}
+
+/// The loaded world and what clients may request or change in it.
+#[derive(SystemParam)]
+pub struct TerrainAccess<'w> {
+    chunk_manager: ResMut<'w, ChunkManager>,
+    modified_chunks: ResMut<'w, terrain_resources::ModifiedChunks>,
+    past_block_updates: ResMut<'w, terrain_resources::PastBlockUpdates>,
+    generator: Res<'w, terrain_resources::Generator>,
+    settings: Res<'w, settings::ServerSettings>,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn receive_message_system(
     mut server: ResMut<RenetServer>,
-    mut player_states: ResMut<player_resources::PlayerStates>,
-    mut past_block_updates: ResMut<terrain_resources::PastBlockUpdates>,
-    chunk_manager: ResMut<ChunkManager>,
-    #[cfg(feature = "chat")] mut chat_message_events: EventWriter<
-        chat_events::PlayerChatMessageSendEvent,
-    >,
-    generator: Res<terrain_resources::Generator>,
+    mut player_writers: PlayerEventWriters,
+    moderation: Res<moderation_resources::ModerationList>,
+    mut block_broken_events: EventWriter<terrain_events::BlockBrokenEvent>,
+    player_states: Res<player_resources::PlayerStates>,
+    mut player_profiles: ResMut<player_resources::PlayerProfiles>,
+    mut player_inventories: ResMut<player_resources::PlayerInventories>,
+    mut player_mining: ResMut<player_resources::PlayerMining>,
+    mut player_snapshots: ResMut<player_resources::PlayerSnapshots>,
+    player_game_modes: Res<player_resources::PlayerGameModes>,
+    mut terrain: TerrainAccess,
+    #[cfg(feature = "chat")] mut chat: ChatMessageWriters,
 ) {
-    for client_id in server.clients_id() {
+    let client_ids = server.clients_id();
+
+    #[cfg(feature = "chat")]
+    chat.rate_limiter
+        .recent
+        .retain(|client_id, _| client_ids.contains(client_id));
+
+    'clients: for client_id in client_ids {
         while let Some(message) = server.receive_message(client_id, DefaultChannel::ReliableOrdered)
         {
-            let message = bincode::deserialize(&message).unwrap();
+            // Only a broken or malicious client sends messages that do not deserialize
+            let message = match bincode::deserialize(&message) {
+                Ok(message) => message,
+                Err(error) => {
+                    error!(
+                        "Disconnecting client {}, could not deserialize message {:?}",
+                        client_id, error
+                    );
+                    server.disconnect(client_id);
+                    continue 'clients;
+                }
+            };
 
             match message {
+                NetworkingMessage::JoinGame(profile, identity) => {
+                    if player_profiles.profiles.contains_key(&client_id) {
+                        warn!("Ignoring repeated join from client {}", client_id);
+                        continue;
+                    }
+                    let profile = profile.sanitized();
+                    if moderation.is_banned(Some(&profile.name), None) {
+                        info!("Refusing banned player {}", profile.name);
+                        server.disconnect(client_id);
+                        continue;
+                    }
+                    if player_profiles
+                        .profiles
+                        .values()
+                        .any(|other_profile| other_profile.name == profile.name)
+                        || player_profiles
+                            .identities
+                            .values()
+                            .any(|other_identity| *other_identity == identity)
+                    {
+                        info!(
+                            "Refusing client {} as {} is already connected",
+                            client_id, profile.name
+                        );
+                        server.disconnect(client_id);
+                        continue;
+                    }
+                    if player_persistence::is_claimed_by_other(
+                        &terrain.settings.world_directory,
+                        &profile.name,
+                        &identity,
+                    ) {
+                        info!(
+                            "Refusing client {} as {} was saved by another client",
+                            client_id, profile.name
+                        );
+                        server.disconnect(client_id);
+                        continue;
+                    }
+                    info!("Client {} joined as {}", client_id, profile.name);
+
+                    for (other_client_id, other_profile) in player_profiles.profiles.iter() {
+                        let message = bincode::serialize(&NetworkingMessage::PlayerJoin(
+                            *other_client_id,
+                            other_profile.clone(),
+                        ))
+                        .unwrap();
+                        server.send_message(client_id, DefaultChannel::ReliableOrdered, message);
+                    }
+
+                    let message = bincode::serialize(&NetworkingMessage::PlayerJoin(
+                        client_id,
+                        profile.clone(),
+                    ))
+                    .unwrap();
+                    server.broadcast_message_except(
+                        client_id,
+                        DefaultChannel::ReliableOrdered,
+                        message,
+                    );
+
+                    #[cfg(feature = "chat")]
+                    chat.messages.send(chat_events::PlayerChatMessageSendEvent {
+                        client_id: SERVER_MESSAGE_ID,
+                        message: format!("{} joined the game", profile.name),
+                    });
+
+                    player_profiles.profiles.insert(client_id, profile);
+                    player_profiles.identities.insert(client_id, identity);
+                    player_writers
+                        .joined
+                        .send(player_events::PlayerJoinedEvent { client_id });
+                }
+                NetworkingMessage::RespawnRequest => {
+                    player_writers
+                        .respawn
+                        .send(player_events::PlayerRespawnEvent { client_id });
+                }
+                NetworkingMessage::SelectHotbarSlot(slot) => {
+                    if let Some(inventory) = player_inventories.inventories.get_mut(&client_id) {
+                        inventory.select(slot);
+                    }
+                }
+                NetworkingMessage::MiningTarget(target) => match target {
+                    Some(position) => {
+                        player_mining
+                            .targets
+                            .insert(client_id, (position.floor(), Instant::now()));
+                    }
+                    None => {
+                        player_mining.targets.remove(&client_id);
+                    }
+                },
+                // Inputs are displacements, so each one must arrive and in the order it was sent
+                NetworkingMessage::PlayerUpdate { input, state } => {
+                    debug!(
+                        "Received player update {} from client {} {}",
+                        input.sequence, client_id, state.position
+                    );
+                    player_writers.moved.send(player_events::PlayerMoveEvent {
+                        client_id,
+                        input,
+                        state,
+                    });
+                }
                 NetworkingMessage::BlockUpdate { position, block } => {
                     info!(
                         "Received block update from client {} {} {:?}",
                         client_id, position, block
                     );
-                    past_block_updates
+
+                    let current_block = terrain
+                        .chunk_manager
+                        .get_block(position)
+                        .unwrap_or(BlockId::Air);
+
+                    let game_mode = player_game_modes.get(&client_id);
+                    let mut validation = match player_states.players.get(&client_id) {
+                        Some(_) if !game_mode.can_interact() => {
+                            Err(terrain_util::BlockUpdateViolation::NotAllowed)
+                        }
+                        Some(player_state) => terrain_util::validate_block_update(
+                            &terrain.chunk_manager,
+                            player_state.position,
+                            position,
+                            block,
+                        ),
+                        None => Err(terrain_util::BlockUpdateViolation::OutOfReach),
+                    };
+
+                    if validation.is_ok() && block == BlockId::Air && !game_mode.breaks_instantly()
+                    {
+                        let item = player_inventories
+                            .inventories
+                            .get(&client_id)
+                            .and_then(Inventory::selected_item);
+                        let mining_time = player_mining
+                            .targets
+                            .remove(&client_id)
+                            .filter(|(target, _)| *target == position.floor())
+                            .map(|(_, started)| started.elapsed());
+
+                        validation =
+                            terrain_util::validate_mining(current_block, item, mining_time);
+                    }
+
+                    if validation.is_ok() && block != BlockId::Air && game_mode.consumes_items() {
+                        validation = match player_inventories.inventories.get_mut(&client_id) {
+                            Some(inventory) if inventory.take_selected(ItemId::Block(block)) => {
+                                server.send_message(
+                                    client_id,
+                                    DefaultChannel::ReliableOrdered,
+                                    bincode::serialize(&NetworkingMessage::InventorySync(
+                                        inventory.clone(),
+                                    ))
+                                    .unwrap(),
+                                );
+                                Ok(())
+                            }
+                            _ => Err(terrain_util::BlockUpdateViolation::MissingItem),
+                        };
+                    }
+
+                    if let Err(violation) = validation {
+                        warn!(
+                            "Rejected block update from client {} at {}: {}",
+                            client_id, position, violation
+                        );
+
+                        // Revert the change the client already applied locally
+                        server.send_message(
+                            client_id,
+                            DefaultChannel::ReliableOrdered,
+                            bincode::serialize(&NetworkingMessage::BlockUpdate {
+                                position,
+                                block: current_block,
+                            })
+                            .unwrap(),
+                        );
+                        continue;
+                    }
+
+                    terrain.chunk_manager.update_block(position, block);
+                    terrain.modified_chunks.insert_block(position);
+                    if block == BlockId::Air && current_block != BlockId::Air {
+                        block_broken_events.send(terrain_events::BlockBrokenEvent {
+                            client_id,
+                            position,
+                            block: current_block,
+                        });
+                    }
+                    terrain
+                        .past_block_updates
                         .updates
                         .push(terrain_events::BlockUpdateEvent { position, block });
 
@@ -35,8 +277,40 @@ pub fn receive_message_system(
                 #[cfg(feature = "chat")]
                 NetworkingMessage::ChatMessageSend(message) => {
                     info!("Received chat message from {}", client_id);
-                    chat_message_events
-                        .send(chat_events::PlayerChatMessageSendEvent { client_id, message });
+                    let Some(message) = ChatMessage::sanitize(&message) else {
+                        continue;
+                    };
+
+                    // Commands count towards the limit as well so they cannot flood the server
+                    if !chat.rate_limiter.allow(
+                        client_id,
+                        Instant::now(),
+                        chat.settings.chat_rate_limit,
+                        settings::CHAT_RATE_WINDOW,
+                    ) {
+                        chat_systems::send_server_reply(
+                            &mut server,
+                            &mut chat.history,
+                            client_id,
+                            "You are sending messages too quickly",
+                        );
+                        continue;
+                    }
+
+                    match message.strip_prefix('/') {
+                        Some(command) => {
+                            chat.commands.send(chat_events::ChatCommandEvent {
+                                client_id,
+                                command: command.to_string(),
+                            });
+                        }
+                        None => {
+                            chat.messages.send(chat_events::PlayerChatMessageSendEvent {
+                                client_id,
+                                message,
+                            });
+                        }
+                    }
                 }
                 _ => {
                     warn!("Received unknown message type. (ReliabelOrdered)");
@@ -47,23 +321,58 @@ pub fn receive_message_system(
         while let Some(message) =
             server.receive_message(client_id, DefaultChannel::ReliableUnordered)
         {
-            let message = bincode::deserialize(&message).unwrap();
+            let message = match bincode::deserialize(&message) {
+                Ok(message) => message,
+                Err(error) => {
+                    error!(
+                        "Disconnecting client {}, could not deserialize message {:?}",
+                        client_id, error
+                    );
+                    server.disconnect(client_id);
+                    continue 'clients;
+                }
+            };
             debug!("Received message: {:?}", message);
 
             match message {
-                NetworkingMessage::PlayerUpdate(player) => {
-                    debug!(
-                        "Received player update from client {} {}",
-                        client_id, player.position
-                    );
-                    player_states.players.insert(client_id, player);
-                }
-                NetworkingMessage::ChunkBatchRequest(positions) => {
+                NetworkingMessage::ChunkBatchRequest(mut positions) => {
                     info!(
                         "Received chunk batch request at {:?} from client {}",
                         positions, client_id
                     );
 
+                    if positions.len() > MAX_CHUNK_BATCH_SIZE {
+                        warn!(
+                            "Truncating chunk request of {} chunks from client {}",
+                            positions.len(),
+                            client_id
+                        );
+                        positions.truncate(MAX_CHUNK_BATCH_SIZE);
+                    }
+
+                    let Some(player_state) = player_states.players.get(&client_id) else {
+                        warn!(
+                            "Ignoring chunk request from client {} without a player",
+                            client_id
+                        );
+                        continue;
+                    };
+                    let center = terrain_util::chunk_position(player_state.position);
+                    let view_distance = terrain.settings.view_distance;
+                    let (positions, rejected): (Vec<Vec3>, Vec<Vec3>) =
+                        positions.into_iter().partition(|position| {
+                            terrain_util::is_chunk_in_view(*position, center, view_distance)
+                        });
+                    if !rejected.is_empty() {
+                        warn!(
+                            "Rejected {} chunk requests outside the view distance from client {}",
+                            rejected.len(),
+                            client_id
+                        );
+                    }
+
+                    let chunk_manager: &ChunkManager = &terrain.chunk_manager;
+                    let generator: &terrain_resources::Generator = &terrain.generator;
                     let chunks: Vec<Chunk> = positions
                         .into_par_iter()
                         .map(|position| {
@@ -80,6 +389,14 @@ pub fn receive_message_system(
                         })
                         .collect();
 
+                    // Keep generated chunks so block updates apply to them; unmodified ones
+                    // are evicted once nobody can see them
+                    for chunk in chunks.iter() {
+                        if terrain.chunk_manager.get_chunk(chunk.position).is_none() {
+                            terrain.chunk_manager.insert_chunk(*chunk);
+                        }
+                    }
+
                     let message =
                         bincode::serialize(&NetworkingMessage::ChunkBatchResponse(chunks));
 
@@ -94,13 +411,39 @@ pub fn receive_message_system(
                 }
             }
         }
+
+        while let Some(message) = server.receive_message(client_id, DefaultChannel::Unreliable) {
+            match bincode::deserialize(&message) {
+                Ok(NetworkingMessage::PlayerSnapshotAck(tick)) => {
+                    if let Some(history) = player_snapshots.clients.get_mut(&client_id) {
+                        history.acknowledge(tick);
+                    }
+                }
+                Ok(_) => {
+                    warn!("Received unknown message type. (Unreliable)");
+                }
+                Err(error) => {
+                    error!(
+                        "Disconnecting client {}, could not deserialize message {:?}",
+                        client_id, error
+                    );
+                    server.disconnect(client_id);
+                    continue 'clients;
+                }
+            }
+        }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn handle_events_system(
     mut server: ResMut<RenetServer>,
     mut server_events: EventReader<ServerEvent>,
     mut player_states: ResMut<player_resources::PlayerStates>,
+    mut player_movements: ResMut<player_resources::PlayerMovements>,
+    mut player_profiles: ResMut<player_resources::PlayerProfiles>,
+    mut player_inventories: ResMut<player_resources::PlayerInventories>,
+    mut player_mining: ResMut<player_resources::PlayerMining>,
     past_block_updates: Res<terrain_resources::PastBlockUpdates>,
     #[cfg(feature = "chat")] mut chat_message_events: EventWriter<
         chat_events::PlayerChatMessageSendEvent,
@@ -108,38 +451,36 @@ pub fn handle_events_system(
     #[cfg(feature = "chat")] mut chat_sync_events: EventWriter<
         chat_events::SyncPlayerChatMessagesEvent,
     >,
+    settings: Res<settings::ServerSettings>,
+    moderation: Res<moderation_resources::ModerationList>,
+    transport: Option<Res<NetcodeServerTransport>>,
 ) {
     for event in server_events.read() {
         match event {
             ServerEvent::ClientConnected { client_id } => {
+                let address =
+                    moderation_resources::client_address(transport.as_deref(), *client_id);
+                if moderation.is_banned(None, address) {
+                    info!("Refusing client {} from banned address", client_id);
+                    server.disconnect(*client_id);
+                    continue;
+                }
+
                 println!("Client {client_id} connected");
                 player_states.players.insert(
                     *client_id,
                     PlayerState {
-                        position: Vec3::ZERO,
+                        position: settings.spawn_point,
                         rotation: Quat::IDENTITY,
                     },
                 );
+                player_movements.place(*client_id);
 
                 #[cfg(feature = "chat")]
                 chat_sync_events.send(chat_events::SyncPlayerChatMessagesEvent {
                     client_id: *client_id,
                 });
 
-                #[cfg(feature = "chat")]
-                chat_message_events.send(chat_events::PlayerChatMessageSendEvent {
-                    client_id: SERVER_MESSAGE_ID,
-                    message: format!("Player {} joined the game", *client_id),
-                });
-
-                let message =
-                    bincode::serialize(&NetworkingMessage::PlayerJoin(*client_id)).unwrap();
-                server.broadcast_message_except(
-                    *client_id,
-                    DefaultChannel::ReliableOrdered,
-                    message,
-                );
-
                 for update in past_block_updates.updates.iter() {
                     let message = bincode::serialize(&NetworkingMessage::BlockUpdate {
                         position: update.position,
@@ -153,12 +494,23 @@ pub fn handle_events_system(
                 println!("Client {client_id} disconnected: {reason}");
                 player_states.players.remove(client_id);
 
+                // Clients refused before joining never announced themselves
+                let joined = player_profiles.profiles.contains_key(client_id);
                 #[cfg(feature = "chat")]
-                chat_message_events.send(chat_events::PlayerChatMessageSendEvent {
-                    client_id: SERVER_MESSAGE_ID,
-                    message: format!("Player {} left the game", client_id),
-                });
+                if let Some(profile) = player_profiles.profiles.get(client_id) {
+                    chat_message_events.send(chat_events::PlayerChatMessageSendEvent {
+                        client_id: SERVER_MESSAGE_ID,
+                        message: format!("{} left the game", profile.name),
+                    });
+                }
+                player_profiles.profiles.remove(client_id);
+                player_profiles.identities.remove(client_id);
+                player_inventories.inventories.remove(client_id);
+                player_mining.targets.remove(client_id);
 
+                if !joined {
+                    continue;
+                }
                 let message =
                     bincode::serialize(&NetworkingMessage::PlayerLeave(*client_id)).unwrap();
                 server.broadcast_message(DefaultChannel::ReliableOrdered, message);
@@ -173,7 +525,7 @@ pub use server_visualizer::*;
 #[cfg(feature = "renet_visualizer")]
 pub mod server_visualizer {
 
-    use crate::prelude::*;
+    use crate::server::prelude::*;
     use bevy_inspector_egui::bevy_egui::EguiContexts;
     use renet_visualizer::RenetServerVisualizer;
 
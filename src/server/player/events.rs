@@ -0,0 +1,35 @@
+use crate::server::prelude::*;
+
+/// A client reported a new player state, applied once it passes movement validation.
+#[derive(Event)]
+pub struct PlayerMoveEvent {
+    pub client_id: ClientId,
+    pub input: MovementInput,
+    pub state: PlayerState,
+}
+
+/// A client sent its profile and entered the game.
+#[derive(Event)]
+pub struct PlayerJoinedEvent {
+    pub client_id: ClientId,
+}
+
+#[derive(Event)]
+pub struct PlayerDamageEvent {
+    pub client_id: ClientId,
+    pub amount: u32,
+    pub cause: DamageCause,
+}
+
+/// A dead player asked to respawn.
+#[derive(Event)]
+pub struct PlayerRespawnEvent {
+    pub client_id: ClientId,
+}
+
+/// Switches the game mode of a player, sent by the `/gamemode` command.
+#[derive(Event)]
+pub struct GameModeChangeEvent {
+    pub client_id: ClientId,
+    pub game_mode: GameMode,
+}
@@ -1,5 +1,8 @@
-use crate::prelude::*;
+use crate::server::prelude::*;
 
+pub mod events;
+pub mod movement;
+pub mod persistence;
 pub mod resources;
 pub mod systems;
 
@@ -8,6 +11,43 @@ pub struct PlayerPlugin;
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(player_resources::PlayerStates::new());
-        app.add_systems(Update, player_systems::broadcast_player_attributes_system);
+        app.insert_resource(player_resources::PlayerProfiles::default());
+        app.insert_resource(player_resources::PlayerMovements::default());
+        app.insert_resource(player_resources::PlayerInventories::default());
+        app.insert_resource(player_resources::PlayerMining::default());
+        app.add_event::<player_events::PlayerMoveEvent>();
+        app.add_event::<player_events::PlayerJoinedEvent>();
+        app.add_event::<player_events::PlayerDamageEvent>();
+        app.add_event::<player_events::PlayerRespawnEvent>();
+        app.add_event::<player_events::GameModeChangeEvent>();
+        app.insert_resource(player_resources::PlayerHealths::default());
+        app.insert_resource(player_resources::PlayerGameModes::default());
+        app.insert_resource(player_resources::PlayerSnapshots::default());
+        app.add_systems(
+            Update,
+            (
+                player_systems::load_player_data_system,
+                player_systems::setup_player_game_mode_system,
+                player_systems::handle_game_mode_change_system,
+                player_systems::setup_player_health_system,
+                player_systems::handle_player_respawn_system,
+                player_systems::validate_player_movement_system,
+                player_systems::suffocation_system,
+                player_systems::apply_player_damage_system,
+                player_systems::broadcast_player_snapshots_system,
+            )
+                .chain(),
+        );
+        app.add_systems(
+            Update,
+            (
+                player_systems::save_disconnected_players_system
+                    .before(networking_systems::handle_events_system),
+                player_systems::autosave_players_system,
+                player_systems::broadcast_player_list_system
+                    .after(networking_systems::handle_events_system),
+            ),
+        );
+        app.add_systems(Last, player_systems::save_players_on_exit_system);
     }
 }
@@ -0,0 +1,286 @@
+use std::fmt;
+
+use crate::server::prelude::*;
+
+/// Horizontal speed limit in blocks per second, above the air speed of the client controller.
+pub const MAX_HORIZONTAL_SPEED: f32 = 16.0;
+pub const MAX_RISE_SPEED: f32 = 10.0;
+pub const MAX_FLYING_SPEED: f32 = 32.0;
+/// Distance allowed on top of the speed limits to absorb network jitter.
+pub const MOVEMENT_TOLERANCE: f32 = 1.0;
+/// Elapsed time is capped so a client cannot save up distance by pausing its updates.
+pub const MAX_ELAPSED: Duration = Duration::from_secs(1);
+/// Longest time a player may stay in the air without falling, longer than the rise of a jump.
+pub const MAX_HOVER_TIME: Duration = Duration::from_secs(1);
+
+/// How far below and around the feet a block still counts as ground, covering the rounded
+/// bottom of the client capsule.
+const GROUND_MARGIN: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MovementViolation {
+    TooFast { distance: f32, allowed: f32 },
+    InsideBlock,
+    Hovering,
+}
+
+impl fmt::Display for MovementViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MovementViolation::TooFast { distance, allowed } => {
+                write!(f, "moved {:.2} blocks, allowed {:.2}", distance, allowed)
+            }
+            MovementViolation::InsideBlock => write!(f, "moved into a solid block"),
+            MovementViolation::Hovering => write!(f, "stayed in the air without falling"),
+        }
+    }
+}
+
+pub fn is_on_ground(chunk_manager: &ChunkManager, position: Vec3) -> bool {
+    let aabb = Aabb::player(position).expanded(Vec3::new(GROUND_MARGIN, 0.0, GROUND_MARGIN));
+    is_supported(chunk_manager, &aabb, GROUND_MARGIN)
+}
+
+/// Feet position on the highest solid ground at or below `position` where the player fits, so
+/// players put at the spawn point stand on the terrain instead of falling onto it. `position` is
+/// kept when there is no loaded ground below it.
+pub fn surface_position(chunk_manager: &ChunkManager, position: Vec3) -> Vec3 {
+    let mut feet = position.with_y(position.y.floor());
+
+    while chunk_manager.get_block(feet - Vec3::Y).is_some() {
+        if is_supported(chunk_manager, &Aabb::player(feet), 1.0) {
+            return feet;
+        }
+        feet -= Vec3::Y;
+    }
+
+    position
+}
+
+/// Checks a move from `previous` to `next`, `airborne_time` is how long the player has been
+/// off the ground at `previous`. Players of game modes that cannot fly may still fly when
+/// `allow_flight` is set.
+pub fn validate_movement(
+    chunk_manager: &ChunkManager,
+    previous: Vec3,
+    next: Vec3,
+    elapsed: Duration,
+    airborne_time: Option<Duration>,
+    game_mode: GameMode,
+    allow_flight: bool,
+) -> Result<(), MovementViolation> {
+    if game_mode.collides() && collides_with_blocks(chunk_manager, &Aabb::player(next)) {
+        return Err(MovementViolation::InsideBlock);
+    }
+
+    let allow_flight = allow_flight || game_mode.can_fly();
+
+    let elapsed = elapsed.min(MAX_ELAPSED).as_secs_f32();
+    let (horizontal_speed, rise_speed) = match allow_flight {
+        true => (MAX_FLYING_SPEED, MAX_FLYING_SPEED),
+        false => (MAX_HORIZONTAL_SPEED, MAX_RISE_SPEED),
+    };
+
+    let horizontal_distance = previous.xz().distance(next.xz());
+    let allowed = horizontal_speed * elapsed + MOVEMENT_TOLERANCE;
+    if horizontal_distance > allowed {
+        return Err(MovementViolation::TooFast {
+            distance: horizontal_distance,
+            allowed,
+        });
+    }
+
+    let rise = next.y - previous.y;
+    let allowed = rise_speed * elapsed + MOVEMENT_TOLERANCE;
+    if rise > allowed {
+        return Err(MovementViolation::TooFast {
+            distance: rise,
+            allowed,
+        });
+    }
+
+    let hovering = airborne_time.is_some_and(|airborne_time| airborne_time > MAX_HOVER_TIME);
+    if !allow_flight && hovering && rise >= 0.0 {
+        return Err(MovementViolation::Hovering);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: Duration = Duration::from_millis(16);
+
+    fn chunk_manager_with_floor() -> ChunkManager {
+        let mut chunk_manager = ChunkManager::new();
+        chunk_manager.insert_chunk(Chunk::new(Vec3::ZERO));
+
+        for x in 0..CHUNK_SIZE {
+            for z in 0..CHUNK_SIZE {
+                chunk_manager.update_block(Vec3::new(x as f32, 4.0, z as f32), BlockId::Stone);
+            }
+        }
+
+        chunk_manager
+    }
+
+    #[test]
+    fn test_surface_position_is_on_top_of_the_ground() {
+        let chunk_manager = chunk_manager_with_floor();
+
+        assert_eq!(
+            surface_position(&chunk_manager, Vec3::new(8.5, 20.5, 8.5)),
+            Vec3::new(8.5, 5.0, 8.5)
+        );
+        assert_eq!(
+            surface_position(&chunk_manager, Vec3::new(8.5, 3.0, 8.5)),
+            Vec3::new(8.5, 3.0, 8.5)
+        );
+    }
+
+    #[test]
+    fn test_walking_is_valid() {
+        let chunk_manager = chunk_manager_with_floor();
+        let previous = Vec3::new(8.0, 5.0, 8.0);
+
+        assert!(is_on_ground(&chunk_manager, previous));
+        assert_eq!(
+            validate_movement(
+                &chunk_manager,
+                previous,
+                previous + Vec3::new(0.15, 0.0, 0.0),
+                FRAME,
+                None,
+                GameMode::Survival,
+                false
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_teleporting_is_too_fast() {
+        let chunk_manager = chunk_manager_with_floor();
+        let previous = Vec3::new(8.0, 5.0, 8.0);
+
+        assert!(matches!(
+            validate_movement(
+                &chunk_manager,
+                previous,
+                previous + Vec3::new(10.0, 0.0, 0.0),
+                FRAME,
+                None,
+                GameMode::Survival,
+                false
+            ),
+            Err(MovementViolation::TooFast { .. })
+        ));
+        assert!(matches!(
+            validate_movement(
+                &chunk_manager,
+                previous,
+                previous + Vec3::new(0.0, 5.0, 0.0),
+                FRAME,
+                None,
+                GameMode::Survival,
+                false
+            ),
+            Err(MovementViolation::TooFast { .. })
+        ));
+    }
+
+    #[test]
+    fn test_moving_into_blocks_is_invalid() {
+        let chunk_manager = chunk_manager_with_floor();
+
+        assert_eq!(
+            validate_movement(
+                &chunk_manager,
+                Vec3::new(8.0, 5.0, 8.0),
+                Vec3::new(8.0, 4.5, 8.0),
+                FRAME,
+                None,
+                GameMode::Survival,
+                false
+            ),
+            Err(MovementViolation::InsideBlock)
+        );
+    }
+
+    #[test]
+    fn test_hovering_is_invalid_without_flight() {
+        let chunk_manager = chunk_manager_with_floor();
+        let previous = Vec3::new(8.0, 12.0, 8.0);
+        let airborne_time = Some(Duration::from_secs(2));
+
+        assert!(!is_on_ground(&chunk_manager, previous));
+        assert_eq!(
+            validate_movement(
+                &chunk_manager,
+                previous,
+                previous,
+                FRAME,
+                airborne_time,
+                GameMode::Survival,
+                false
+            ),
+            Err(MovementViolation::Hovering)
+        );
+        assert_eq!(
+            validate_movement(
+                &chunk_manager,
+                previous,
+                previous - Vec3::Y * 0.5,
+                FRAME,
+                airborne_time,
+                GameMode::Survival,
+                false
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            validate_movement(
+                &chunk_manager,
+                previous,
+                previous,
+                FRAME,
+                airborne_time,
+                GameMode::Survival,
+                true
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            validate_movement(
+                &chunk_manager,
+                previous,
+                previous,
+                FRAME,
+                airborne_time,
+                GameMode::Creative,
+                false
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_spectators_may_move_into_blocks() {
+        let chunk_manager = chunk_manager_with_floor();
+
+        assert_eq!(
+            validate_movement(
+                &chunk_manager,
+                Vec3::new(8.0, 5.0, 8.0),
+                Vec3::new(8.0, 4.5, 8.0),
+                FRAME,
+                None,
+                GameMode::Spectator,
+                false
+            ),
+            Ok(())
+        );
+    }
+}
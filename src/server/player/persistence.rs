@@ -0,0 +1,310 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::server::prelude::*;
+
+const PLAYERS_DIRECTORY_NAME: &str = "players";
+
+/// Everything kept about a player between sessions, saved per player name.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PlayerData {
+    pub position: Vec3,
+    pub rotation: Quat,
+    pub inventory: Inventory,
+    pub game_mode: GameMode,
+    pub health: u32,
+    /// Identity of the client that first joined with the name, clients with another identity
+    /// are refused so nobody can take over a player by their name.
+    pub identity: PlayerIdentity,
+}
+
+impl PlayerData {
+    /// Data of a player joining the world for the first time at `spawn_position`.
+    pub fn new(
+        settings: &settings::ServerSettings,
+        identity: PlayerIdentity,
+        spawn_position: Vec3,
+    ) -> Self {
+        Self {
+            position: spawn_position,
+            rotation: Quat::IDENTITY,
+            inventory: Inventory::with_starting_tools(),
+            game_mode: settings.game_mode,
+            health: MAX_HEALTH,
+            identity,
+        }
+    }
+}
+
+/// Player data as saved before players were saved by name, keyed by their identity.
+#[derive(Deserialize)]
+struct LegacyPlayerData {
+    position: Vec3,
+    rotation: Quat,
+    inventory: Inventory,
+    game_mode: GameMode,
+    health: u32,
+}
+
+impl LegacyPlayerData {
+    fn into_player_data(self, identity: PlayerIdentity) -> PlayerData {
+        PlayerData {
+            position: self.position,
+            rotation: self.rotation,
+            inventory: self.inventory,
+            game_mode: self.game_mode,
+            health: self.health,
+            identity,
+        }
+    }
+}
+
+/// Collects the save data of connected players from the player resources.
+#[derive(SystemParam)]
+pub struct PlayerDataQuery<'w> {
+    profiles: Res<'w, player_resources::PlayerProfiles>,
+    states: Res<'w, player_resources::PlayerStates>,
+    inventories: Res<'w, player_resources::PlayerInventories>,
+    game_modes: Res<'w, player_resources::PlayerGameModes>,
+    healths: Res<'w, player_resources::PlayerHealths>,
+    chunk_manager: Res<'w, ChunkManager>,
+    settings: Res<'w, settings::ServerSettings>,
+}
+
+impl PlayerDataQuery<'_> {
+    /// Name and save data of a player, `None` if the client never joined.
+    pub fn get(&self, client_id: &ClientId) -> Option<(&str, PlayerData)> {
+        let profile = self.profiles.profiles.get(client_id)?;
+        let identity = self.profiles.identities.get(client_id)?;
+        let state = self.states.players.get(client_id)?;
+        let health = self.healths.players.get(client_id);
+
+        // Players that logged out while dead come back respawned
+        let dead = health.is_some_and(|health| health.dead);
+        let data = PlayerData {
+            position: match dead {
+                true => player_movement::surface_position(
+                    &self.chunk_manager,
+                    self.settings.spawn_point,
+                ),
+                false => state.position,
+            },
+            rotation: state.rotation,
+            inventory: self
+                .inventories
+                .inventories
+                .get(client_id)
+                .cloned()
+                .unwrap_or_else(Inventory::with_starting_tools),
+            game_mode: self.game_modes.get(client_id),
+            health: match health {
+                Some(health) if !health.dead => health.health,
+                _ => MAX_HEALTH,
+            },
+            identity: *identity,
+        };
+
+        Some((profile.name.as_str(), data))
+    }
+
+    /// Saves a player to the world directory, logging failures.
+    pub fn save(&self, client_id: &ClientId) {
+        let Some((name, data)) = self.get(client_id) else {
+            return;
+        };
+
+        match save_player(&self.settings.world_directory, name, &data) {
+            Ok(()) => debug!("Saved player {}", name),
+            Err(error) => error!("Could not save player {}: {}", name, error),
+        }
+    }
+
+    pub fn save_all(&self) {
+        for client_id in self.profiles.profiles.keys() {
+            self.save(client_id);
+        }
+    }
+}
+
+/// File of a player inside the world directory. Characters other than ASCII letters, digits, `-`
+/// and `_` are percent encoded, so names cannot point outside the players directory.
+pub fn player_file_path(world_directory: &Path, name: &str) -> PathBuf {
+    let mut file_name = String::new();
+    for byte in name.bytes() {
+        match byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_' {
+            true => file_name.push(byte as char),
+            false => file_name.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    world_directory
+        .join(PLAYERS_DIRECTORY_NAME)
+        .join(format!("{}.bin", file_name))
+}
+
+/// File the player of a client was saved to before players were saved by name.
+fn legacy_player_file_path(world_directory: &Path, identity: &PlayerIdentity) -> PathBuf {
+    world_directory
+        .join(PLAYERS_DIRECTORY_NAME)
+        .join(format!("{}.bin", identity.to_hex()))
+}
+
+pub fn save_player(world_directory: &Path, name: &str, data: &PlayerData) -> io::Result<()> {
+    let path = player_file_path(world_directory, name);
+    fs::create_dir_all(world_directory.join(PLAYERS_DIRECTORY_NAME))?;
+
+    let bytes = bincode::serialize(data).map_err(io::Error::other)?;
+
+    // Write to a temporary file first so a crash while saving does not lose the player
+    let temporary_path = path.with_extension("bin.tmp");
+    fs::write(&temporary_path, bytes)?;
+    fs::rename(temporary_path, path)
+}
+
+/// Loads the save data of a player, `None` if the player never played in this world.
+///
+/// A player saved by the identity of the joining client before players were saved by name is
+/// moved to the file of the name.
+pub fn load_player(
+    world_directory: &Path,
+    name: &str,
+    identity: &PlayerIdentity,
+) -> io::Result<Option<PlayerData>> {
+    let path = player_file_path(world_directory, name);
+
+    if !path.exists() {
+        return migrate_legacy_player(world_directory, name, identity);
+    }
+
+    let bytes = fs::read(path)?;
+    bincode::deserialize(&bytes)
+        .map(Some)
+        .map_err(io::Error::other)
+}
+
+/// Whether the name was saved by a client with another identity, joining with it would take over
+/// that player. Unreadable saves are not claimed, the player starts over like when loading them.
+pub fn is_claimed_by_other(world_directory: &Path, name: &str, identity: &PlayerIdentity) -> bool {
+    load_player(world_directory, name, identity)
+        .ok()
+        .flatten()
+        .is_some_and(|data| data.identity != *identity)
+}
+
+fn migrate_legacy_player(
+    world_directory: &Path,
+    name: &str,
+    identity: &PlayerIdentity,
+) -> io::Result<Option<PlayerData>> {
+    let legacy_path = legacy_player_file_path(world_directory, identity);
+
+    if !legacy_path.exists() {
+        return Ok(None);
+    }
+
+    let bytes = fs::read(&legacy_path)?;
+    let data = bincode::deserialize::<LegacyPlayerData>(&bytes)
+        .map_err(io::Error::other)?
+        .into_player_data(*identity);
+
+    info!("Moving the save of player {} to their name", name);
+    save_player(world_directory, name, &data)?;
+    fs::remove_file(legacy_path)?;
+
+    Ok(Some(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load_player() {
+        let world_directory =
+            std::env::temp_dir().join(format!("rsmc-world-{}", rand::random::<u64>()));
+        let mut inventory = Inventory::with_starting_tools();
+        inventory.add(ItemId::Block(BlockId::Dirt), 12);
+        let data = PlayerData {
+            position: Vec3::new(1.5, 70.0, -3.0),
+            rotation: Quat::from_rotation_y(1.0),
+            inventory,
+            game_mode: GameMode::Creative,
+            health: 7,
+            identity: PlayerIdentity::random(),
+        };
+
+        save_player(&world_directory, "alice", &data).unwrap();
+
+        assert_eq!(
+            load_player(&world_directory, "alice", &data.identity).unwrap(),
+            Some(data.clone())
+        );
+        assert_eq!(
+            load_player(&world_directory, "bob", &data.identity).unwrap(),
+            None
+        );
+        assert!(!is_claimed_by_other(
+            &world_directory,
+            "alice",
+            &data.identity
+        ));
+        assert!(is_claimed_by_other(
+            &world_directory,
+            "alice",
+            &PlayerIdentity::random()
+        ));
+        assert!(!is_claimed_by_other(
+            &world_directory,
+            "bob",
+            &PlayerIdentity::random()
+        ));
+
+        fs::remove_dir_all(world_directory).unwrap();
+    }
+
+    #[test]
+    fn test_player_file_is_named_by_encoded_name() {
+        assert_eq!(
+            player_file_path(Path::new("world"), "Steve_2"),
+            Path::new("world/players/Steve_2.bin")
+        );
+        assert_eq!(
+            player_file_path(Path::new("world"), "../Cool Steve"),
+            Path::new("world/players/%2E%2E%2FCool%20Steve.bin")
+        );
+    }
+
+    #[test]
+    fn test_players_saved_by_identity_are_moved_to_their_name() {
+        let world_directory =
+            std::env::temp_dir().join(format!("rsmc-world-{}", rand::random::<u64>()));
+        let identity = PlayerIdentity::random();
+        let data = PlayerData {
+            position: Vec3::new(4.0, 20.0, 4.0),
+            rotation: Quat::IDENTITY,
+            inventory: Inventory::with_starting_tools(),
+            game_mode: GameMode::Survival,
+            health: 3,
+            identity,
+        };
+
+        // The legacy layout is the current one without the trailing identity
+        let mut bytes = bincode::serialize(&data).unwrap();
+        bytes.truncate(bytes.len() - std::mem::size_of::<PlayerIdentity>());
+        let legacy_path = legacy_player_file_path(&world_directory, &identity);
+        fs::create_dir_all(legacy_path.parent().unwrap()).unwrap();
+        fs::write(&legacy_path, bytes).unwrap();
+
+        assert_eq!(
+            load_player(&world_directory, "alice", &identity).unwrap(),
+            Some(data.clone())
+        );
+        assert!(!legacy_path.exists());
+        assert!(player_file_path(&world_directory, "alice").exists());
+
+        fs::remove_dir_all(world_directory).unwrap();
+    }
+}
@@ -1,4 +1,7 @@
-use crate::prelude::*;
+use crate::server::prelude::*;
+
+/// Rejected moves are counted per window, so occasional corrections of legit clients never add up.
+const MOVEMENT_VIOLATION_WINDOW: Duration = Duration::from_secs(10);
 
 #[derive(Resource)]
 pub struct PlayerStates {
@@ -18,3 +21,254 @@ impl Default for PlayerStates {
         Self::new()
     }
 }
+
+#[derive(Debug)]
+pub struct PlayerMovement {
+    /// Time of the last accepted update or of the server placing the player.
+    pub last_update: Instant,
+    /// Sequence number of the last processed input, inputs arrive in order so only duplicates
+    /// are dropped.
+    pub last_sequence: Option<u32>,
+    pub airborne_since: Option<Instant>,
+    /// Highest position of the current fall, `None` while on the ground.
+    pub fall_start: Option<f32>,
+    /// Rejected moves since `violations_since`.
+    pub violations: u32,
+    pub violations_since: Instant,
+}
+
+impl PlayerMovement {
+    pub fn new() -> Self {
+        Self {
+            last_update: Instant::now(),
+            last_sequence: None,
+            airborne_since: None,
+            fall_start: None,
+            violations: 0,
+            violations_since: Instant::now(),
+        }
+    }
+
+    /// Counts a rejected move and returns the violations within the current window.
+    pub fn record_violation(&mut self, now: Instant) -> u32 {
+        if now.duration_since(self.violations_since) >= MOVEMENT_VIOLATION_WINDOW {
+            self.violations = 0;
+            self.violations_since = now;
+        }
+
+        self.violations += 1;
+        self.violations
+    }
+}
+
+impl Default for PlayerMovement {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Movement state of the players the server has placed, updates of other clients are ignored.
+#[derive(Resource, Default)]
+pub struct PlayerMovements {
+    pub players: HashMap<ClientId, PlayerMovement>,
+}
+
+impl PlayerMovements {
+    /// Starts validating movement from a position the server put the player at.
+    pub fn place(&mut self, client_id: ClientId) {
+        let movement = self.players.entry(client_id).or_default();
+        movement.last_update = Instant::now();
+        movement.airborne_since = None;
+        movement.fall_start = None;
+    }
+}
+
+#[derive(Debug)]
+pub struct PlayerHealth {
+    pub health: u32,
+    pub dead: bool,
+    /// Last time the player took suffocation damage, `None` while the head is free.
+    pub last_suffocation: Option<Instant>,
+}
+
+impl Default for PlayerHealth {
+    fn default() -> Self {
+        Self {
+            health: MAX_HEALTH,
+            dead: false,
+            last_suffocation: None,
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct PlayerHealths {
+    pub players: HashMap<ClientId, PlayerHealth>,
+}
+
+impl PlayerHealths {
+    pub fn is_dead(&self, client_id: &ClientId) -> bool {
+        self.players
+            .get(client_id)
+            .is_some_and(|health| health.dead)
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct PlayerGameModes {
+    pub players: HashMap<ClientId, GameMode>,
+}
+
+impl PlayerGameModes {
+    pub fn get(&self, client_id: &ClientId) -> GameMode {
+        self.players.get(client_id).copied().unwrap_or_default()
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct PlayerInventories {
+    pub inventories: HashMap<ClientId, Inventory>,
+}
+
+/// Block each player is currently mining and when it started.
+#[derive(Resource, Default)]
+pub struct PlayerMining {
+    pub targets: HashMap<ClientId, (Vec3, Instant)>,
+}
+
+#[derive(Resource, Default)]
+pub struct PlayerProfiles {
+    pub profiles: HashMap<ClientId, PlayerProfile>,
+    /// Identities the joined clients sent, used to key their saved data and to recognize operators.
+    pub identities: HashMap<ClientId, PlayerIdentity>,
+}
+
+impl PlayerProfiles {
+    /// Entries for every joined player sorted by name, `ping` looks up the round trip time.
+    pub fn player_list(
+        &self,
+        game_modes: &PlayerGameModes,
+        ping: impl Fn(ClientId) -> u32,
+    ) -> Vec<PlayerListEntry> {
+        let mut entries: Vec<PlayerListEntry> = self
+            .profiles
+            .iter()
+            .map(|(client_id, profile)| PlayerListEntry {
+                client_id: *client_id,
+                name: profile.name.clone(),
+                ping: ping(*client_id),
+                game_mode: game_modes.get(client_id),
+            })
+            .collect();
+
+        entries.sort_by(|a, b| a.name.cmp(&b.name).then(a.client_id.cmp(&b.client_id)));
+        entries
+    }
+}
+
+/// Number of sent snapshots kept per client to serve as delta baselines.
+pub const SNAPSHOT_HISTORY_SIZE: usize = 32;
+
+#[derive(Default)]
+pub struct ClientSnapshotHistory {
+    pub sent: VecDeque<(u32, PlayerSnapshotState)>,
+    pub acknowledged_tick: Option<u32>,
+}
+
+impl ClientSnapshotHistory {
+    /// Latest acknowledged snapshot that is still in the history.
+    pub fn baseline(&self) -> Option<(u32, &PlayerSnapshotState)> {
+        let acknowledged_tick = self.acknowledged_tick?;
+
+        self.sent
+            .iter()
+            .find(|(tick, _)| *tick == acknowledged_tick)
+            .map(|(tick, state)| (*tick, state))
+    }
+
+    pub fn acknowledge(&mut self, tick: u32) {
+        if self
+            .acknowledged_tick
+            .is_none_or(|acknowledged| tick > acknowledged)
+        {
+            self.acknowledged_tick = Some(tick);
+        }
+    }
+
+    pub fn push(&mut self, tick: u32, state: PlayerSnapshotState) {
+        self.sent.push_back((tick, state));
+
+        while self.sent.len() > SNAPSHOT_HISTORY_SIZE {
+            self.sent.pop_front();
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct PlayerSnapshots {
+    pub tick: u32,
+    pub last_sent: Option<Instant>,
+    pub clients: HashMap<ClientId, ClientSnapshotHistory>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_movement_violations_are_counted_per_window() {
+        let start = Instant::now();
+        let mut movement = PlayerMovement::new();
+        movement.violations_since = start;
+
+        assert_eq!(movement.record_violation(start), 1);
+        assert_eq!(movement.record_violation(start + Duration::from_secs(5)), 2);
+        assert_eq!(
+            movement.record_violation(start + MOVEMENT_VIOLATION_WINDOW),
+            1
+        );
+    }
+
+    #[test]
+    fn test_baseline_is_latest_acknowledged_snapshot() {
+        let mut history = ClientSnapshotHistory::default();
+        for tick in 1..=3 {
+            history.push(tick, PlayerSnapshotState::new());
+        }
+        assert!(history.baseline().is_none());
+
+        history.acknowledge(2);
+        history.acknowledge(1);
+        assert_eq!(history.baseline().map(|(tick, _)| tick), Some(2));
+
+        for tick in 4..=(SNAPSHOT_HISTORY_SIZE as u32 + 4) {
+            history.push(tick, PlayerSnapshotState::new());
+        }
+        assert!(history.baseline().is_none());
+    }
+
+    #[test]
+    fn test_player_list_is_sorted_by_name() {
+        let mut profiles = PlayerProfiles::default();
+        for (client_id, name) in [(1, "zoe"), (2, "adam"), (3, "mia")] {
+            profiles.profiles.insert(
+                client_id,
+                PlayerProfile {
+                    name: name.to_string(),
+                    ..default()
+                },
+            );
+        }
+
+        let mut game_modes = PlayerGameModes::default();
+        game_modes.players.insert(3, GameMode::Creative);
+
+        let entries = profiles.player_list(&game_modes, |client_id| client_id as u32 * 10);
+
+        let names: Vec<&str> = entries.iter().map(|entry| entry.name.as_str()).collect();
+        assert_eq!(names, vec!["adam", "mia", "zoe"]);
+        assert_eq!(entries[0].ping, 20);
+        assert_eq!(entries[1].game_mode, GameMode::Creative);
+        assert_eq!(entries[2].game_mode, GameMode::Survival);
+    }
+}
@@ -1,17 +1,527 @@
-use crate::prelude::*;
+use crate::server::prelude::*;
 
-pub fn broadcast_player_attributes_system(
+const SUFFOCATION_INTERVAL: Duration = Duration::from_millis(500);
+const SUFFOCATION_DAMAGE: u32 = 1;
+/// Rejected moves within one violation window after which a client is disconnected.
+const MAX_MOVEMENT_VIOLATIONS: u32 = 50;
+/// How often the player list is resent so the ping stays current.
+const PLAYER_LIST_INTERVAL: Duration = Duration::from_secs(2);
+
+#[allow(clippy::too_many_arguments)]
+pub fn validate_player_movement_system(
+    mut server: ResMut<RenetServer>,
+    mut move_events: EventReader<player_events::PlayerMoveEvent>,
+    mut player_states: ResMut<player_resources::PlayerStates>,
+    mut movements: ResMut<player_resources::PlayerMovements>,
+    mut damage_events: EventWriter<player_events::PlayerDamageEvent>,
+    player_healths: Res<player_resources::PlayerHealths>,
+    player_game_modes: Res<player_resources::PlayerGameModes>,
+    chunk_manager: Res<ChunkManager>,
+    settings: Res<settings::ServerSettings>,
+) {
+    movements
+        .players
+        .retain(|client_id, _| player_states.players.contains_key(client_id));
+
+    let mut acknowledgements = HashMap::new();
+
+    for event in move_events.read() {
+        let client_id = event.client_id;
+        let Some(player_state) = player_states.players.get_mut(&client_id) else {
+            continue;
+        };
+        // Dead players stay where they died until they respawn
+        if player_healths.is_dead(&client_id) {
+            continue;
+        }
+
+        // Updates sent before the server placed the player have nothing to be checked against
+        let Some(movement) = movements.players.get_mut(&client_id) else {
+            continue;
+        };
+        if movement
+            .last_sequence
+            .is_some_and(|last_sequence| event.input.sequence <= last_sequence)
+        {
+            continue;
+        }
+        movement.last_sequence = Some(event.input.sequence);
+
+        let now = Instant::now();
+        let game_mode = player_game_modes.get(&client_id);
+        let position = step_movement(
+            &chunk_manager,
+            player_state.position,
+            &event.input,
+            game_mode,
+        );
+
+        let result = player_movement::validate_movement(
+            &chunk_manager,
+            player_state.position,
+            position,
+            now - movement.last_update,
+            movement
+                .airborne_since
+                .map(|airborne_since| now - airborne_since),
+            game_mode,
+            settings.allow_flight,
+        );
+
+        match result {
+            Ok(()) => {
+                movement.last_update = now;
+
+                match player_movement::is_on_ground(&chunk_manager, position) {
+                    true => {
+                        movement.airborne_since = None;
+
+                        // Single updates are too jittery for a landing speed, damage is based on
+                        // the height of the whole fall instead
+                        let fall_height = movement
+                            .fall_start
+                            .take()
+                            .map_or(0.0, |fall_start| fall_start - position.y);
+                        let amount = fall_damage_for_height(fall_height);
+                        if amount > 0 && !settings.allow_flight {
+                            damage_events.send(player_events::PlayerDamageEvent {
+                                client_id,
+                                amount,
+                                cause: DamageCause::Fall,
+                            });
+                        }
+                    }
+                    false => {
+                        movement.airborne_since = movement.airborne_since.or(Some(now));
+                        movement.fall_start = Some(
+                            movement
+                                .fall_start
+                                .map_or(position.y, |fall_start| fall_start.max(position.y)),
+                        );
+                    }
+                }
+
+                player_state.position = position;
+                player_state.rotation = event.state.rotation;
+                acknowledgements.insert(client_id, (event.input.sequence, position));
+            }
+            Err(violation) => {
+                let violations = movement.record_violation(now);
+                warn!(
+                    "Rejected movement of client {} ({} violations): {}",
+                    client_id, violations, violation
+                );
+
+                player_state.rotation = event.state.rotation;
+
+                if violations >= MAX_MOVEMENT_VIOLATIONS {
+                    warn!("Disconnecting client {} for invalid movement", client_id);
+                    server.disconnect(client_id);
+                    continue;
+                }
+
+                server.send_message(
+                    client_id,
+                    DefaultChannel::ReliableOrdered,
+                    bincode::serialize(&NetworkingMessage::PlayerPositionCorrection(
+                        player_state.position,
+                    ))
+                    .unwrap(),
+                );
+            }
+        }
+    }
+
+    for (client_id, (sequence, position)) in acknowledgements {
+        server.send_message(
+            client_id,
+            DefaultChannel::Unreliable,
+            bincode::serialize(&NetworkingMessage::PlayerMovementAck { sequence, position })
+                .unwrap(),
+        );
+    }
+}
+
+/// Restores returning players from the world directory, new players start at the spawn point.
+#[allow(clippy::too_many_arguments)]
+pub fn load_player_data_system(
+    mut server: ResMut<RenetServer>,
+    mut player_joined_events: EventReader<player_events::PlayerJoinedEvent>,
+    player_profiles: Res<player_resources::PlayerProfiles>,
+    mut player_states: ResMut<player_resources::PlayerStates>,
+    mut player_inventories: ResMut<player_resources::PlayerInventories>,
+    mut player_game_modes: ResMut<player_resources::PlayerGameModes>,
+    mut player_healths: ResMut<player_resources::PlayerHealths>,
+    mut movements: ResMut<player_resources::PlayerMovements>,
+    chunk_manager: Res<ChunkManager>,
+    settings: Res<settings::ServerSettings>,
+) {
+    for event in player_joined_events.read() {
+        let client_id = event.client_id;
+        let (Some(profile), Some(identity)) = (
+            player_profiles.profiles.get(&client_id),
+            player_profiles.identities.get(&client_id),
+        ) else {
+            continue;
+        };
+
+        let spawn_position =
+            player_movement::surface_position(&chunk_manager, settings.spawn_point);
+        let data = match player_persistence::load_player(
+            &settings.world_directory,
+            &profile.name,
+            identity,
+        ) {
+            Ok(Some(data)) => {
+                info!("Restoring player {} at {}", profile.name, data.position);
+                data
+            }
+            Ok(None) => player_persistence::PlayerData::new(&settings, *identity, spawn_position),
+            Err(error) => {
+                error!("Could not load player {}: {}", profile.name, error);
+                player_persistence::PlayerData::new(&settings, *identity, spawn_position)
+            }
+        };
+
+        player_states.players.insert(
+            client_id,
+            PlayerState {
+                position: data.position,
+                rotation: data.rotation,
+            },
+        );
+        movements.place(client_id);
+        player_game_modes.players.insert(client_id, data.game_mode);
+        player_healths.players.insert(
+            client_id,
+            player_resources::PlayerHealth {
+                health: data.health,
+                ..default()
+            },
+        );
+
+        server.send_message(
+            client_id,
+            DefaultChannel::ReliableOrdered,
+            bincode::serialize(&NetworkingMessage::InventorySync(data.inventory.clone())).unwrap(),
+        );
+        player_inventories
+            .inventories
+            .insert(client_id, data.inventory);
+    }
+}
+
+pub fn setup_player_game_mode_system(
+    mut player_joined_events: EventReader<player_events::PlayerJoinedEvent>,
+    mut game_mode_events: EventWriter<player_events::GameModeChangeEvent>,
+    mut player_game_modes: ResMut<player_resources::PlayerGameModes>,
+    player_states: Res<player_resources::PlayerStates>,
+) {
+    player_game_modes
+        .players
+        .retain(|client_id, _| player_states.players.contains_key(client_id));
+
+    for event in player_joined_events.read() {
+        game_mode_events.send(player_events::GameModeChangeEvent {
+            client_id: event.client_id,
+            game_mode: player_game_modes.get(&event.client_id),
+        });
+    }
+}
+
+pub fn handle_game_mode_change_system(
+    mut server: ResMut<RenetServer>,
+    mut game_mode_events: EventReader<player_events::GameModeChangeEvent>,
+    mut player_game_modes: ResMut<player_resources::PlayerGameModes>,
+    mut movements: ResMut<player_resources::PlayerMovements>,
+    mut player_mining: ResMut<player_resources::PlayerMining>,
+) {
+    for event in game_mode_events.read() {
+        let client_id = event.client_id;
+        info!("Client {} is now in {} mode", client_id, event.game_mode);
+        player_game_modes.players.insert(client_id, event.game_mode);
+        player_mining.targets.remove(&client_id);
+
+        // Falls and hovering are measured from the position the mode changed at
+        if let Some(movement) = movements.players.get_mut(&client_id) {
+            movement.airborne_since = None;
+            movement.fall_start = None;
+        }
+
+        server.send_message(
+            client_id,
+            DefaultChannel::ReliableOrdered,
+            bincode::serialize(&NetworkingMessage::GameModeSync(event.game_mode)).unwrap(),
+        );
+    }
+}
+
+pub fn setup_player_health_system(
+    mut server: ResMut<RenetServer>,
+    mut player_joined_events: EventReader<player_events::PlayerJoinedEvent>,
+    mut player_healths: ResMut<player_resources::PlayerHealths>,
+    player_states: Res<player_resources::PlayerStates>,
+    settings: Res<settings::ServerSettings>,
+) {
+    for event in player_joined_events.read() {
+        let health = player_healths.players.entry(event.client_id).or_default();
+        let (position, rotation) = match player_states.players.get(&event.client_id) {
+            Some(player_state) => (player_state.position, Some(player_state.rotation)),
+            None => (settings.spawn_point, None),
+        };
+
+        server.send_message(
+            event.client_id,
+            DefaultChannel::ReliableOrdered,
+            bincode::serialize(&NetworkingMessage::PlayerSpawn { position, rotation }).unwrap(),
+        );
+        server.send_message(
+            event.client_id,
+            DefaultChannel::ReliableOrdered,
+            bincode::serialize(&NetworkingMessage::HealthSync(health.health)).unwrap(),
+        );
+    }
+}
+
+pub fn suffocation_system(
+    mut damage_events: EventWriter<player_events::PlayerDamageEvent>,
+    mut player_healths: ResMut<player_resources::PlayerHealths>,
+    player_states: Res<player_resources::PlayerStates>,
+    chunk_manager: Res<ChunkManager>,
+) {
+    player_healths
+        .players
+        .retain(|client_id, _| player_states.players.contains_key(client_id));
+
+    for (client_id, health) in player_healths.players.iter_mut() {
+        let Some(player_state) = player_states.players.get(client_id) else {
+            continue;
+        };
+
+        let eye_position = player_state.position + Vec3::Y * PLAYER_EYE_HEIGHT;
+        let suffocating = !health.dead
+            && chunk_manager
+                .get_block(eye_position)
+                .is_some_and(|block| block.is_solid());
+
+        if !suffocating {
+            health.last_suffocation = None;
+            continue;
+        }
+
+        if health
+            .last_suffocation
+            .is_some_and(|last_suffocation| last_suffocation.elapsed() < SUFFOCATION_INTERVAL)
+        {
+            continue;
+        }
+
+        health.last_suffocation = Some(Instant::now());
+        damage_events.send(player_events::PlayerDamageEvent {
+            client_id: *client_id,
+            amount: SUFFOCATION_DAMAGE,
+            cause: DamageCause::Suffocation,
+        });
+    }
+}
+
+pub fn apply_player_damage_system(
+    mut server: ResMut<RenetServer>,
+    mut damage_events: EventReader<player_events::PlayerDamageEvent>,
+    mut player_healths: ResMut<player_resources::PlayerHealths>,
+    player_game_modes: Res<player_resources::PlayerGameModes>,
+) {
+    for event in damage_events.read() {
+        let Some(health) = player_healths.players.get_mut(&event.client_id) else {
+            continue;
+        };
+        if health.dead || !player_game_modes.get(&event.client_id).takes_damage() {
+            continue;
+        }
+
+        health.health = health.health.saturating_sub(event.amount);
+        server.send_message(
+            event.client_id,
+            DefaultChannel::ReliableOrdered,
+            bincode::serialize(&NetworkingMessage::HealthSync(health.health)).unwrap(),
+        );
+
+        if health.health == 0 {
+            info!("Client {} died: {:?}", event.client_id, event.cause);
+            health.dead = true;
+            server.send_message(
+                event.client_id,
+                DefaultChannel::ReliableOrdered,
+                bincode::serialize(&NetworkingMessage::PlayerDied(event.cause)).unwrap(),
+            );
+        }
+    }
+}
+
+pub fn handle_player_respawn_system(
+    mut server: ResMut<RenetServer>,
+    mut respawn_events: EventReader<player_events::PlayerRespawnEvent>,
+    mut player_healths: ResMut<player_resources::PlayerHealths>,
+    mut player_states: ResMut<player_resources::PlayerStates>,
+    mut movements: ResMut<player_resources::PlayerMovements>,
+    chunk_manager: Res<ChunkManager>,
+    settings: Res<settings::ServerSettings>,
+) {
+    for event in respawn_events.read() {
+        let client_id = event.client_id;
+        let Some(health) = player_healths.players.get_mut(&client_id) else {
+            continue;
+        };
+        if !health.dead {
+            continue;
+        }
+
+        *health = player_resources::PlayerHealth::default();
+        let position = player_movement::surface_position(&chunk_manager, settings.spawn_point);
+        if let Some(player_state) = player_states.players.get_mut(&client_id) {
+            player_state.position = position;
+        }
+
+        // Movement continues from the spawn point
+        movements.place(client_id);
+
+        info!("Client {} respawned", client_id);
+        server.send_message(
+            client_id,
+            DefaultChannel::ReliableOrdered,
+            bincode::serialize(&NetworkingMessage::PlayerSpawn {
+                position,
+                rotation: None,
+            })
+            .unwrap(),
+        );
+        server.send_message(
+            client_id,
+            DefaultChannel::ReliableOrdered,
+            bincode::serialize(&NetworkingMessage::HealthSync(health.health)).unwrap(),
+        );
+    }
+}
+
+pub fn broadcast_player_snapshots_system(
     mut server: ResMut<RenetServer>,
     player_states: Res<player_resources::PlayerStates>,
+    player_game_modes: Res<player_resources::PlayerGameModes>,
+    mut snapshots: ResMut<player_resources::PlayerSnapshots>,
+    settings: Res<settings::ServerSettings>,
+    time: Res<Time>,
 ) {
-    for client_id in server.clients_id() {
-        let mut other_player_states = player_states.players.clone();
-        other_player_states.remove(&client_id);
+    if snapshots
+        .last_sent
+        .is_some_and(|last_sent| last_sent.elapsed() < settings.snapshot_interval())
+    {
+        return;
+    }
+
+    snapshots.last_sent = Some(Instant::now());
+    snapshots.tick += 1;
+    let tick = snapshots.tick;
+    let server_time = time.elapsed().as_millis() as u32;
+
+    let client_ids = server.clients_id();
+    snapshots
+        .clients
+        .retain(|client_id, _| client_ids.contains(client_id));
+
+    let interest_radius = settings.interest_radius();
+
+    for client_id in client_ids {
+        let Some(own_state) = player_states.players.get(&client_id) else {
+            continue;
+        };
+
+        let visible_players: PlayerSnapshotState = player_states
+            .players
+            .iter()
+            .filter(|(other_client_id, other_state)| {
+                **other_client_id != client_id
+                    && player_game_modes.get(other_client_id).is_visible()
+                    && is_within_interest(own_state.position, other_state.position, interest_radius)
+            })
+            .map(|(other_client_id, other_state)| {
+                (*other_client_id, QuantizedPlayerState::from(other_state))
+            })
+            .collect();
+
+        let history = snapshots.clients.entry(client_id).or_default();
+        let snapshot =
+            PlayerSnapshot::encode(tick, server_time, history.baseline(), &visible_players);
+        history.push(tick, visible_players);
 
         server.send_message(
             client_id,
-            DefaultChannel::ReliableUnordered,
-            bincode::serialize(&NetworkingMessage::PlayerSync(other_player_states)).unwrap(),
+            DefaultChannel::Unreliable,
+            bincode::serialize(&NetworkingMessage::PlayerSnapshot(snapshot)).unwrap(),
         );
     }
 }
+
+fn is_within_interest(position: Vec3, other_position: Vec3, radius: f32) -> bool {
+    position.xz().distance_squared(other_position.xz()) <= radius * radius
+}
+
+pub fn save_disconnected_players_system(
+    mut server_events: EventReader<ServerEvent>,
+    player_data: player_persistence::PlayerDataQuery,
+) {
+    for event in server_events.read() {
+        if let ServerEvent::ClientDisconnected { client_id, .. } = event {
+            player_data.save(client_id);
+        }
+    }
+}
+
+pub fn autosave_players_system(
+    mut last_save: Local<Option<Instant>>,
+    player_data: player_persistence::PlayerDataQuery,
+    settings: Res<settings::ServerSettings>,
+) {
+    let last_save = last_save.get_or_insert_with(Instant::now);
+    if last_save.elapsed() < settings.autosave_interval() {
+        return;
+    }
+
+    *last_save = Instant::now();
+    info!("Saving players");
+    player_data.save_all();
+}
+
+pub fn save_players_on_exit_system(
+    mut exit_events: EventReader<AppExit>,
+    player_data: player_persistence::PlayerDataQuery,
+) {
+    if exit_events.read().count() == 0 {
+        return;
+    }
+
+    info!("Saving players");
+    player_data.save_all();
+}
+
+pub fn broadcast_player_list_system(
+    mut server: ResMut<RenetServer>,
+    player_profiles: Res<player_resources::PlayerProfiles>,
+    player_game_modes: Res<player_resources::PlayerGameModes>,
+    mut last_broadcast: Local<Option<Instant>>,
+) {
+    let outdated = last_broadcast
+        .is_none_or(|last_broadcast| last_broadcast.elapsed() >= PLAYER_LIST_INTERVAL);
+    if !outdated && !player_profiles.is_changed() && !player_game_modes.is_changed() {
+        return;
+    }
+    *last_broadcast = Some(Instant::now());
+
+    let entries = player_profiles.player_list(&player_game_modes, |client_id| {
+        server
+            .network_info(client_id)
+            .map_or(0, |network_info| (network_info.rtt * 1000.0) as u32)
+    });
+
+    let message = bincode::serialize(&NetworkingMessage::PlayerList(entries)).unwrap();
+    server.broadcast_message(DefaultChannel::ReliableOrdered, message);
+}
@@ -1,14 +1,21 @@
 // std crates
-pub use std::collections::HashMap;
+pub use std::collections::{HashMap, VecDeque};
 pub use std::net::UdpSocket;
-pub use std::time::SystemTime;
+pub use std::time::{Duration, Instant, SystemTime};
 
 // bevy crates
-pub use bevy::app::{App, Plugin, Startup, Update};
+pub use bevy::app::{App, AppExit, First, Last, Plugin, PostUpdate, Startup, Update};
+pub use bevy::ecs::change_detection::Ref;
+pub use bevy::ecs::component::Component;
+pub use bevy::ecs::entity::Entity;
 pub use bevy::ecs::event::*;
+pub use bevy::ecs::query::{Added, Changed, With, Without};
+pub use bevy::ecs::removal_detection::RemovedComponents;
+pub use bevy::ecs::schedule::{IntoSystemConfigs, IntoSystemSetConfigs, SystemSet};
 pub use bevy::ecs::system::*;
 pub use bevy::log::{debug, error, info, warn};
 pub use bevy::math::*;
+pub use bevy::time::Time;
 pub use bevy::MinimalPlugins;
 
 // networking crates
@@ -26,22 +33,45 @@ pub use rayon::iter::IntoParallelIterator;
 pub use rayon::iter::IntoParallelRefMutIterator;
 pub use rayon::iter::ParallelIterator;
 
-pub use lib::*;
+pub use crate::shared::*;
 pub use noise::NoiseFn;
 pub use noise::Perlin;
-pub use rsmc as lib;
 
 // my crates
-pub use crate::networking::systems as networking_systems;
+pub use crate::server::settings;
 
-pub use crate::player::resources as player_resources;
-pub use crate::player::systems as player_systems;
+pub use crate::server::diagnostics::resources as diagnostics_resources;
+pub use crate::server::diagnostics::systems as diagnostics_systems;
 
-pub use crate::terrain::events as terrain_events;
-pub use crate::terrain::resources as terrain_resources;
-pub use crate::terrain::systems as terrain_systems;
-pub use crate::terrain::util as terrain_util;
+pub use crate::server::items::components as items_components;
+pub use crate::server::items::systems as items_systems;
 
-pub use crate::chat::events as chat_events;
-pub use crate::chat::resources as chat_resources;
-pub use crate::chat::systems as chat_systems;
+pub use crate::server::mobs::components as mobs_components;
+pub use crate::server::mobs::pathfinding as mobs_pathfinding;
+pub use crate::server::mobs::spawning as mobs_spawning;
+pub use crate::server::mobs::systems as mobs_systems;
+
+pub use crate::server::moderation::resources as moderation_resources;
+pub use crate::server::moderation::systems as moderation_systems;
+
+pub use crate::server::networking::systems as networking_systems;
+
+pub use crate::server::replication::resources as replication_resources;
+pub use crate::server::replication::systems as replication_systems;
+pub use crate::server::replication::AppReplicationExt;
+
+pub use crate::server::player::events as player_events;
+pub use crate::server::player::movement as player_movement;
+pub use crate::server::player::persistence as player_persistence;
+pub use crate::server::player::resources as player_resources;
+pub use crate::server::player::systems as player_systems;
+
+pub use crate::server::terrain::events as terrain_events;
+pub use crate::server::terrain::resources as terrain_resources;
+pub use crate::server::terrain::systems as terrain_systems;
+pub use crate::server::terrain::util as terrain_util;
+
+pub use crate::server::chat::commands as chat_commands;
+pub use crate::server::chat::events as chat_events;
+pub use crate::server::chat::resources as chat_resources;
+pub use crate::server::chat::systems as chat_systems;
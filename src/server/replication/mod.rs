@@ -0,0 +1,63 @@
+use crate::server::prelude::*;
+
+pub mod resources;
+pub mod systems;
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ReplicationSet {
+    Prepare,
+    Collect,
+    Send,
+}
+
+/// Sends entities marked as `Replicated` with their registered components to the clients.
+pub struct ReplicationPlugin;
+
+impl Plugin for ReplicationPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(replication_resources::NetworkIds::default());
+        app.insert_resource(replication_resources::ReplicationBuffer::default());
+        app.configure_sets(
+            PostUpdate,
+            (
+                ReplicationSet::Prepare,
+                ReplicationSet::Collect,
+                ReplicationSet::Send,
+            )
+                .chain(),
+        );
+        app.add_systems(
+            PostUpdate,
+            (
+                replication_systems::prepare_replication_system,
+                replication_systems::assign_network_ids_system,
+            )
+                .chain()
+                .in_set(ReplicationSet::Prepare),
+        );
+        app.add_systems(
+            PostUpdate,
+            replication_systems::send_replication_system.in_set(ReplicationSet::Send),
+        );
+        app.replicate::<NetworkPosition>();
+        app.replicate::<NetworkRotation>();
+    }
+}
+
+pub trait AppReplicationExt {
+    /// Sends the component of replicated entities to the clients when it changes.
+    fn replicate<C: ReplicatedComponent>(&mut self) -> &mut Self;
+}
+
+impl AppReplicationExt for App {
+    fn replicate<C: ReplicatedComponent>(&mut self) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_insert_with(ReplicationRegistry::default)
+            .register::<C>();
+        self.add_systems(
+            PostUpdate,
+            replication_systems::collect_component_changes_system::<C>
+                .in_set(ReplicationSet::Collect),
+        )
+    }
+}
@@ -0,0 +1,50 @@
+use crate::server::prelude::*;
+
+#[derive(Resource, Default)]
+pub struct NetworkIds {
+    next_id: u64,
+    /// Needed to find the id of despawned entities.
+    pub entities: HashMap<Entity, NetworkId>,
+}
+
+impl NetworkIds {
+    pub fn allocate(&mut self, entity: Entity) -> NetworkId {
+        let id = NetworkId(self.next_id);
+        self.next_id += 1;
+        self.entities.insert(entity, id);
+        id
+    }
+}
+
+/// Components collected during a frame, waiting to be sent.
+#[derive(Resource, Default)]
+pub struct ReplicationBuffer {
+    pub spawned: HashMap<NetworkId, EntityUpdate>,
+    pub changed: HashMap<NetworkId, EntityUpdate>,
+    /// Every component of every entity, only collected when `full` is `Some`.
+    pub full: Option<HashMap<NetworkId, EntityUpdate>>,
+    /// Clients that joined since the last frame and need all existing entities.
+    pub joined_clients: Vec<ClientId>,
+    pub update_due: bool,
+    /// Send every component instead of the changed ones, so lost updates are repaired.
+    pub refresh_due: bool,
+    pub tick: u64,
+    pub last_update: Option<Instant>,
+    pub last_refresh: Option<Instant>,
+}
+
+impl ReplicationBuffer {
+    pub fn insert(
+        updates: &mut HashMap<NetworkId, EntityUpdate>,
+        id: NetworkId,
+        component: ComponentData,
+    ) {
+        updates
+            .entry(id)
+            .or_insert_with(|| EntityUpdate {
+                id,
+                components: Vec::new(),
+            })
+            .insert(component);
+    }
+}
@@ -0,0 +1,122 @@
+use crate::server::prelude::*;
+
+/// Interval at which all components are resent.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+pub fn prepare_replication_system(
+    mut buffer: ResMut<replication_resources::ReplicationBuffer>,
+    mut player_joined_events: EventReader<player_events::PlayerJoinedEvent>,
+    settings: Res<settings::ServerSettings>,
+) {
+    buffer
+        .joined_clients
+        .extend(player_joined_events.read().map(|event| event.client_id));
+
+    buffer.update_due = buffer
+        .last_update
+        .is_none_or(|last_update| last_update.elapsed() >= settings.snapshot_interval());
+    buffer.refresh_due = buffer.update_due
+        && buffer
+            .last_refresh
+            .is_none_or(|last_refresh| last_refresh.elapsed() >= REFRESH_INTERVAL);
+
+    if buffer.refresh_due || !buffer.joined_clients.is_empty() {
+        buffer.full = Some(HashMap::new());
+    }
+}
+
+pub fn assign_network_ids_system(
+    mut commands: Commands,
+    mut network_ids: ResMut<replication_resources::NetworkIds>,
+    entities: Query<Entity, (With<Replicated>, Without<NetworkId>)>,
+) {
+    for entity in entities.iter() {
+        let id = network_ids.allocate(entity);
+        commands.entity(entity).insert(id);
+    }
+}
+
+pub fn collect_component_changes_system<C: ReplicatedComponent>(
+    mut buffer: ResMut<replication_resources::ReplicationBuffer>,
+    components: Query<(Ref<NetworkId>, Ref<C>), With<Replicated>>,
+) {
+    let buffer = &mut *buffer;
+
+    for (id, component) in components.iter() {
+        let data = ComponentData::encode(&*component);
+
+        if id.is_added() {
+            replication_resources::ReplicationBuffer::insert(&mut buffer.spawned, *id, data);
+            continue;
+        }
+
+        if let Some(full) = buffer.full.as_mut() {
+            replication_resources::ReplicationBuffer::insert(full, *id, data.clone());
+        }
+        if component.is_changed() {
+            replication_resources::ReplicationBuffer::insert(&mut buffer.changed, *id, data);
+        }
+    }
+}
+
+pub fn send_replication_system(
+    mut server: ResMut<RenetServer>,
+    mut buffer: ResMut<replication_resources::ReplicationBuffer>,
+    mut network_ids: ResMut<replication_resources::NetworkIds>,
+    mut removed: RemovedComponents<Replicated>,
+) {
+    let buffer = &mut *buffer;
+
+    for (_, entity) in buffer.spawned.drain() {
+        server.broadcast_message(
+            DefaultChannel::ReliableOrdered,
+            bincode::serialize(&NetworkingMessage::EntitySpawn(entity)).unwrap(),
+        );
+    }
+
+    for entity in removed.read() {
+        let Some(id) = network_ids.entities.remove(&entity) else {
+            continue;
+        };
+
+        buffer.changed.remove(&id);
+        server.broadcast_message(
+            DefaultChannel::ReliableOrdered,
+            bincode::serialize(&NetworkingMessage::EntityDespawn(id)).unwrap(),
+        );
+    }
+
+    if let Some(full) = buffer.full.take() {
+        for client_id in buffer.joined_clients.drain(..) {
+            for entity in full.values() {
+                server.send_message(
+                    client_id,
+                    DefaultChannel::ReliableOrdered,
+                    bincode::serialize(&NetworkingMessage::EntitySpawn(entity.clone())).unwrap(),
+                );
+            }
+        }
+
+        if buffer.refresh_due {
+            buffer.changed.extend(full);
+            buffer.last_refresh = Some(Instant::now());
+        }
+    }
+
+    if !buffer.update_due || buffer.changed.is_empty() {
+        return;
+    }
+
+    buffer.tick += 1;
+    buffer.last_update = Some(Instant::now());
+
+    let entities = buffer.changed.drain().map(|(_, entity)| entity).collect();
+    server.broadcast_message(
+        DefaultChannel::Unreliable,
+        bincode::serialize(&NetworkingMessage::EntityUpdates {
+            tick: buffer.tick,
+            entities,
+        })
+        .unwrap(),
+    );
+}
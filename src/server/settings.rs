@@ -0,0 +1,554 @@
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::server::prelude::*;
+
+pub const DEFAULT_CONFIG_PATH: &str = "server.toml";
+
+const MAX_CLIENTS_LIMIT: usize = 1024;
+const MAX_TICK_RATE: f64 = 1000.0;
+const MAX_VIEW_DISTANCE: u32 = 32;
+pub const CHAT_RATE_WINDOW: Duration = Duration::from_secs(10);
+
+pub const USAGE: &str = "Usage: server [OPTIONS]
+
+Options:
+  --config <PATH>          Config file to load (default: server.toml)
+  --bind <IP>              Address the UDP socket is bound to
+  --public <IP>            Address advertised to connecting clients
+  --port <PORT>            Port used for both the bind and public address
+  --max-clients <COUNT>    Maximum number of connected clients
+  --seed <SEED>            World generation seed
+  --world <DIR>            Directory the world is stored in
+  --tick-rate <HZ>         Server updates per second
+  --view-distance <CHUNKS> Horizontal chunk radius generated around the spawn and
+                           in which players see each other
+  --snapshot-rate <HZ>     Player snapshots sent to each client per second
+  --allow-flight <BOOL>    Whether players may fly instead of being pulled down by gravity
+  --max-mobs <COUNT>       Maximum number of mobs in the world, 0 disables mob spawning
+  --spawn-point <X,Y,Z>    Players join and respawn on the ground below this position
+  --game-mode <MODE>       Game mode of new players: survival, creative or spectator
+  --operator <NAME:ID>     Player allowed to run chat commands, may be repeated. ID is
+                           the hex string in the identity file next to their client,
+                           commands of players joining with the name but another
+                           identity are refused
+  --autosave-interval <S>  Seconds between saves of the players and the world
+  --chat-rate-limit <N>    Chat messages a player may send within ten seconds
+  --filter-word <WORD>     Word masked in chat messages, may be repeated
+  --help                   Print this message";
+
+#[derive(Resource, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerSettings {
+    pub bind_address: IpAddr,
+    pub public_address: IpAddr,
+    pub port: u16,
+    pub max_clients: usize,
+    pub seed: u32,
+    pub world_directory: PathBuf,
+    pub tick_rate: f64,
+    pub view_distance: u32,
+    pub snapshot_rate: f64,
+    pub allow_flight: bool,
+    pub max_mobs: usize,
+    /// Players join and respawn on the highest ground below this position.
+    pub spawn_point: Vec3,
+    pub game_mode: GameMode,
+    /// Players allowed to run chat commands like `/gamemode`.
+    pub operators: Vec<Operator>,
+    pub autosave_interval: f64,
+    /// Chat messages a player may send within `CHAT_RATE_WINDOW`.
+    pub chat_rate_limit: usize,
+    /// Words the default chat filter replaces with asterisks.
+    pub filtered_words: Vec<String>,
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        Self {
+            bind_address: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            public_address: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            port: 5000,
+            max_clients: 64,
+            seed: 0,
+            world_directory: PathBuf::from("world"),
+            tick_rate: 60.0,
+            view_distance: 8,
+            snapshot_rate: 20.0,
+            allow_flight: false,
+            max_mobs: 32,
+            spawn_point: Vec3::new(0.0, 64.0, 0.0),
+            game_mode: GameMode::Survival,
+            operators: Vec::new(),
+            autosave_interval: 60.0,
+            chat_rate_limit: 8,
+            filtered_words: Vec::new(),
+        }
+    }
+}
+
+/// A player allowed to run chat commands, written as `<NAME>:<IDENTITY>`. Names are chosen freely
+/// by clients, so the operator is only recognized by the secret identity of their client.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "String")]
+pub struct Operator {
+    pub name: String,
+    pub identity: PlayerIdentity,
+}
+
+impl FromStr for Operator {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (name, identity) = value
+            .rsplit_once(':')
+            .ok_or_else(|| format!("'{}' is not in the form <NAME>:<IDENTITY>", value))?;
+        if name.is_empty() {
+            return Err(format!("'{}' has no name", value));
+        }
+        let identity = PlayerIdentity::from_hex(identity)
+            .ok_or_else(|| format!("'{}' is not an identity of 32 hex digits", identity))?;
+
+        Ok(Self {
+            name: name.to_string(),
+            identity,
+        })
+    }
+}
+
+impl TryFrom<String> for Operator {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+#[derive(Debug)]
+pub enum SettingsError {
+    HelpRequested,
+    Io(PathBuf, std::io::Error),
+    Parse(PathBuf, toml::de::Error),
+    UnknownArgument(String),
+    MissingValue(String),
+    InvalidValue { argument: String, value: String },
+    Invalid { field: &'static str, reason: String },
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::HelpRequested => write!(f, "{}", USAGE),
+            SettingsError::Io(path, error) => {
+                write!(f, "could not read {}: {}", path.display(), error)
+            }
+            SettingsError::Parse(path, error) => {
+                write!(f, "could not parse {}: {}", path.display(), error)
+            }
+            SettingsError::UnknownArgument(argument) => {
+                write!(f, "unknown argument '{}'", argument)
+            }
+            SettingsError::MissingValue(argument) => {
+                write!(f, "missing value for '{}'", argument)
+            }
+            SettingsError::InvalidValue { argument, value } => {
+                write!(f, "invalid value '{}' for '{}'", value, argument)
+            }
+            SettingsError::Invalid { field, reason } => write!(f, "{}: {}", field, reason),
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+impl ServerSettings {
+    /// Builds the settings from defaults, the config file and command line overrides, in that order.
+    pub fn load<I: IntoIterator<Item = String>>(args: I) -> Result<Self, SettingsError> {
+        let (config_path, overrides) = parse_arguments(args)?;
+
+        let mut settings = match config_path {
+            Some(path) => Self::from_file(&path)?,
+            None if Path::new(DEFAULT_CONFIG_PATH).exists() => {
+                Self::from_file(Path::new(DEFAULT_CONFIG_PATH))?
+            }
+            None => Self::default(),
+        };
+
+        for (argument, value) in overrides {
+            settings.apply_argument(&argument, &value)?;
+        }
+
+        settings.validate()?;
+        Ok(settings)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, SettingsError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|error| SettingsError::Io(path.to_path_buf(), error))?;
+        Self::from_toml(&contents).map_err(|error| SettingsError::Parse(path.to_path_buf(), error))
+    }
+
+    pub fn from_toml(contents: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(contents)
+    }
+
+    pub fn bind_socket_address(&self) -> SocketAddr {
+        SocketAddr::new(self.bind_address, self.port)
+    }
+
+    pub fn public_socket_address(&self) -> SocketAddr {
+        SocketAddr::new(self.public_address, self.port)
+    }
+
+    pub fn tick_duration(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.tick_rate)
+    }
+
+    pub fn snapshot_interval(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.snapshot_rate)
+    }
+
+    pub fn autosave_interval(&self) -> Duration {
+        Duration::from_secs_f64(self.autosave_interval)
+    }
+
+    /// Whether a player joining with this name and identity may run chat commands.
+    pub fn is_operator(&self, name: &str, identity: &PlayerIdentity) -> bool {
+        self.operators
+            .iter()
+            .any(|operator| operator.name == name && operator.identity == *identity)
+    }
+
+    /// Horizontal distance in blocks within which players are sent to each other.
+    pub fn interest_radius(&self) -> f32 {
+        (self.view_distance as usize * CHUNK_SIZE) as f32
+    }
+
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if self.port == 0 {
+            return Err(invalid("port", "must not be 0"));
+        }
+
+        if self.public_address.is_unspecified() {
+            return Err(invalid(
+                "public_address",
+                "must be an address clients can connect to",
+            ));
+        }
+
+        if self.max_clients == 0 || self.max_clients > MAX_CLIENTS_LIMIT {
+            return Err(invalid(
+                "max_clients",
+                format!("must be between 1 and {}", MAX_CLIENTS_LIMIT),
+            ));
+        }
+
+        if !(self.tick_rate > 0.0 && self.tick_rate <= MAX_TICK_RATE) {
+            return Err(invalid(
+                "tick_rate",
+                format!("must be between 0 and {}", MAX_TICK_RATE),
+            ));
+        }
+
+        if !(self.snapshot_rate > 0.0 && self.snapshot_rate <= self.tick_rate) {
+            return Err(invalid(
+                "snapshot_rate",
+                "must be greater than 0 and at most the tick rate",
+            ));
+        }
+
+        if self.view_distance == 0 || self.view_distance > MAX_VIEW_DISTANCE {
+            return Err(invalid(
+                "view_distance",
+                format!("must be between 1 and {}", MAX_VIEW_DISTANCE),
+            ));
+        }
+
+        if !(self.autosave_interval.is_finite() && self.autosave_interval > 0.0) {
+            return Err(invalid("autosave_interval", "must be greater than 0"));
+        }
+
+        if self.chat_rate_limit == 0 {
+            return Err(invalid("chat_rate_limit", "must be greater than 0"));
+        }
+
+        if !self.spawn_point.is_finite() {
+            return Err(invalid("spawn_point", "must be finite"));
+        }
+
+        if self.world_directory.as_os_str().is_empty() {
+            return Err(invalid("world_directory", "must not be empty"));
+        }
+
+        if self.world_directory.exists() && !self.world_directory.is_dir() {
+            return Err(invalid(
+                "world_directory",
+                format!("{} is not a directory", self.world_directory.display()),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn apply_argument(&mut self, argument: &str, value: &str) -> Result<(), SettingsError> {
+        match argument {
+            "--bind" => self.bind_address = parse_value(argument, value)?,
+            "--public" => self.public_address = parse_value(argument, value)?,
+            "--port" => self.port = parse_value(argument, value)?,
+            "--max-clients" => self.max_clients = parse_value(argument, value)?,
+            "--seed" => self.seed = parse_value(argument, value)?,
+            "--world" => self.world_directory = PathBuf::from(value),
+            "--tick-rate" => self.tick_rate = parse_value(argument, value)?,
+            "--view-distance" => self.view_distance = parse_value(argument, value)?,
+            "--snapshot-rate" => self.snapshot_rate = parse_value(argument, value)?,
+            "--allow-flight" => self.allow_flight = parse_value(argument, value)?,
+            "--max-mobs" => self.max_mobs = parse_value(argument, value)?,
+            "--spawn-point" => self.spawn_point = parse_vec3(argument, value)?,
+            "--game-mode" => self.game_mode = parse_value(argument, value)?,
+            "--operator" => self.operators.push(parse_value(argument, value)?),
+            "--autosave-interval" => self.autosave_interval = parse_value(argument, value)?,
+            "--chat-rate-limit" => self.chat_rate_limit = parse_value(argument, value)?,
+            "--filter-word" => self.filtered_words.push(value.to_string()),
+            _ => return Err(SettingsError::UnknownArgument(argument.to_string())),
+        }
+
+        Ok(())
+    }
+}
+
+type ArgumentOverrides = Vec<(String, String)>;
+
+fn parse_arguments<I: IntoIterator<Item = String>>(
+    args: I,
+) -> Result<(Option<PathBuf>, ArgumentOverrides), SettingsError> {
+    let mut config_path = None;
+    let mut overrides = Vec::new();
+    let mut args = args.into_iter();
+
+    while let Some(argument) = args.next() {
+        if argument == "--help" || argument == "-h" {
+            return Err(SettingsError::HelpRequested);
+        }
+
+        if !argument.starts_with("--") {
+            return Err(SettingsError::UnknownArgument(argument));
+        }
+
+        let value = args
+            .next()
+            .ok_or_else(|| SettingsError::MissingValue(argument.clone()))?;
+
+        if argument == "--config" {
+            config_path = Some(PathBuf::from(value));
+        } else {
+            overrides.push((argument, value));
+        }
+    }
+
+    Ok((config_path, overrides))
+}
+
+fn parse_value<T: FromStr>(argument: &str, value: &str) -> Result<T, SettingsError> {
+    value.parse().map_err(|_| SettingsError::InvalidValue {
+        argument: argument.to_string(),
+        value: value.to_string(),
+    })
+}
+
+/// Parses comma separated coordinates like `0,64,0`.
+fn parse_vec3(argument: &str, value: &str) -> Result<Vec3, SettingsError> {
+    let coordinates = value
+        .split(',')
+        .map(|coordinate| parse_value::<f32>(argument, coordinate.trim()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match coordinates[..] {
+        [x, y, z] => Ok(Vec3::new(x, y, z)),
+        _ => Err(SettingsError::InvalidValue {
+            argument: argument.to_string(),
+            value: value.to_string(),
+        }),
+    }
+}
+
+fn invalid(field: &'static str, reason: impl Into<String>) -> SettingsError {
+    SettingsError::Invalid {
+        field,
+        reason: reason.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_default_settings_are_valid() {
+        assert!(ServerSettings::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_from_toml_keeps_defaults_for_missing_fields() {
+        let settings = ServerSettings::from_toml("port = 6000\nseed = 42\n").unwrap();
+
+        assert_eq!(settings.port, 6000);
+        assert_eq!(settings.seed, 42);
+        assert_eq!(settings.max_clients, ServerSettings::default().max_clients);
+    }
+
+    #[test]
+    fn test_from_toml_rejects_unknown_fields() {
+        assert!(ServerSettings::from_toml("prot = 6000\n").is_err());
+    }
+
+    #[test]
+    fn test_arguments_override_settings() {
+        let (config_path, overrides) = parse_arguments(args(&[
+            "--config",
+            "custom.toml",
+            "--port",
+            "7000",
+            "--bind",
+            "0.0.0.0",
+        ]))
+        .unwrap();
+
+        let mut settings = ServerSettings::default();
+        for (argument, value) in overrides {
+            settings.apply_argument(&argument, &value).unwrap();
+        }
+
+        assert_eq!(config_path, Some(PathBuf::from("custom.toml")));
+        assert_eq!(settings.port, 7000);
+        assert!(settings.bind_address.is_unspecified());
+        assert!(settings.validate().is_ok());
+    }
+
+    #[test]
+    fn test_spawn_point_argument() {
+        let mut settings = ServerSettings::default();
+        settings
+            .apply_argument("--spawn-point", "8, 70,-4")
+            .unwrap();
+
+        assert_eq!(settings.spawn_point, Vec3::new(8.0, 70.0, -4.0));
+        assert!(matches!(
+            settings.apply_argument("--spawn-point", "8,70"),
+            Err(SettingsError::InvalidValue { .. })
+        ));
+    }
+
+    #[test]
+    fn test_game_mode_and_operator_arguments() {
+        let alice = PlayerIdentity::random();
+        let bob = PlayerIdentity::random();
+        let mut settings = ServerSettings::default();
+        settings.apply_argument("--game-mode", "creative").unwrap();
+        settings
+            .apply_argument("--operator", &format!("alice:{}", alice.to_hex()))
+            .unwrap();
+        settings
+            .apply_argument("--operator", &format!("bob:{}", bob.to_hex()))
+            .unwrap();
+
+        assert_eq!(settings.game_mode, GameMode::Creative);
+        assert!(settings.is_operator("alice", &alice));
+        assert!(settings.is_operator("bob", &bob));
+        // Taking the name of an operator is not enough
+        assert!(!settings.is_operator("alice", &bob));
+        assert!(matches!(
+            settings.apply_argument("--operator", "alice"),
+            Err(SettingsError::InvalidValue { .. })
+        ));
+        assert_eq!(
+            ServerSettings::from_toml(&format!("operators = [\"alice:{}\"]\n", alice.to_hex()))
+                .unwrap()
+                .operators,
+            vec![Operator {
+                name: "alice".to_string(),
+                identity: alice
+            }]
+        );
+        assert!(ServerSettings::from_toml("operators = [\"alice\"]\n").is_err());
+        assert_eq!(
+            ServerSettings::from_toml("game_mode = \"spectator\"\n")
+                .unwrap()
+                .game_mode,
+            GameMode::Spectator
+        );
+    }
+
+    #[test]
+    fn test_argument_errors() {
+        assert!(matches!(
+            parse_arguments(args(&["--port"])),
+            Err(SettingsError::MissingValue(_))
+        ));
+        assert!(matches!(
+            ServerSettings::default().apply_argument("--port", "abc"),
+            Err(SettingsError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            ServerSettings::default().apply_argument("--colour", "red"),
+            Err(SettingsError::UnknownArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        let settings = ServerSettings {
+            max_clients: 0,
+            ..ServerSettings::default()
+        };
+        assert!(settings.validate().is_err());
+
+        let settings = ServerSettings {
+            tick_rate: 0.0,
+            ..ServerSettings::default()
+        };
+        assert!(settings.validate().is_err());
+
+        let settings = ServerSettings {
+            snapshot_rate: 120.0,
+            ..ServerSettings::default()
+        };
+        assert!(settings.validate().is_err());
+
+        let settings = ServerSettings {
+            public_address: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            ..ServerSettings::default()
+        };
+        assert!(settings.validate().is_err());
+
+        let settings = ServerSettings {
+            autosave_interval: 0.0,
+            ..ServerSettings::default()
+        };
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_chat_arguments() {
+        let mut settings = ServerSettings::default();
+        settings.apply_argument("--chat-rate-limit", "3").unwrap();
+        settings.apply_argument("--filter-word", "heck").unwrap();
+
+        assert_eq!(settings.chat_rate_limit, 3);
+        assert_eq!(settings.filtered_words, vec!["heck"]);
+
+        settings.chat_rate_limit = 0;
+        assert!(matches!(
+            settings.validate(),
+            Err(SettingsError::Invalid {
+                field: "chat_rate_limit",
+                ..
+            })
+        ));
+    }
+}
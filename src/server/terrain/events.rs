@@ -1,4 +1,4 @@
-use crate::prelude::*;
+use crate::server::prelude::*;
 
 #[derive(Event)]
 pub struct BlockUpdateEvent {
@@ -6,6 +6,14 @@ pub struct BlockUpdateEvent {
     pub block: BlockId,
 }
 
+/// A player broke a block, `block` is the block that was removed.
+#[derive(Event)]
+pub struct BlockBrokenEvent {
+    pub client_id: ClientId,
+    pub position: Vec3,
+    pub block: BlockId,
+}
+
 #[cfg(feature = "generator_visualizer")]
 pub use visualizer::*;
 #[cfg(feature = "generator_visualizer")]
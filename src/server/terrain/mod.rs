@@ -1,4 +1,4 @@
-use crate::prelude::*;
+use crate::server::prelude::*;
 
 pub mod events;
 pub mod resources;
@@ -11,9 +11,20 @@ impl Plugin for TerrainPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(ChunkManager::new());
         app.add_event::<terrain_events::BlockUpdateEvent>();
+        app.add_event::<terrain_events::BlockBrokenEvent>();
         app.insert_resource(resources::PastBlockUpdates::new());
+        app.insert_resource(resources::ModifiedChunks::default());
         app.add_systems(Startup, terrain_systems::setup_world_system);
-        app.insert_resource(resources::Generator::default());
+        app.add_systems(
+            Update,
+            (
+                terrain_systems::autosave_world_system,
+                terrain_systems::evict_unused_chunks_system,
+            ),
+        );
+        app.add_systems(Last, terrain_systems::save_world_on_exit_system);
+        let seed = app.world().resource::<settings::ServerSettings>().seed;
+        app.insert_resource(resources::Generator::new(seed));
 
         #[cfg(feature = "generator_visualizer")]
         {
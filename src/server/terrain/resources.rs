@@ -1,7 +1,32 @@
-use crate::prelude::*;
+use std::collections::HashSet;
+
+use crate::server::prelude::*;
 
 use terrain_events::BlockUpdateEvent;
 
+/// Chunks that differ from what the generator produces, they stay loaded and are saved.
+#[derive(Resource, Default)]
+pub struct ModifiedChunks {
+    pub positions: HashSet<[i32; 3]>,
+}
+
+impl ModifiedChunks {
+    /// Marks the chunk containing a changed block.
+    pub fn insert_block(&mut self, world_position: Vec3) {
+        self.positions
+            .insert(ChunkManager::position_to_key(terrain_util::chunk_position(
+                world_position,
+            )));
+    }
+
+    pub fn chunks<'a>(&self, chunk_manager: &'a ChunkManager) -> Vec<&'a Chunk> {
+        self.positions
+            .iter()
+            .filter_map(|key| chunk_manager.chunks.get(key))
+            .collect()
+    }
+}
+
 #[derive(Resource)]
 pub struct PastBlockUpdates {
     pub updates: Vec<BlockUpdateEvent>,
@@ -1,14 +1,45 @@
-use crate::prelude::*;
+use crate::server::prelude::*;
+
+/// How often unmodified chunks that no player can see are dropped.
+const CHUNK_EVICTION_INTERVAL: Duration = Duration::from_secs(5);
 
 pub fn setup_world_system(
     mut chunk_manager: ResMut<ChunkManager>,
+    mut modified_chunks: ResMut<terrain_resources::ModifiedChunks>,
     generator: Res<terrain_resources::Generator>,
+    settings: Res<settings::ServerSettings>,
 ) {
-    let render_distance = Vec3::new(8.0, 3.0, 8.0);
+    let view_distance = settings.view_distance as f32;
+    let render_distance = Vec3::new(view_distance, 3.0, view_distance);
+
+    match terrain_util::persistence::load_chunks(&settings.world_directory) {
+        Ok(chunks) => {
+            info!(
+                "Loaded {} chunks from {}",
+                chunks.len(),
+                settings.world_directory.display()
+            );
+            // Only changed chunks are saved, so everything loaded differs from the generator
+            modified_chunks.positions.extend(
+                chunks
+                    .iter()
+                    .map(|chunk| ChunkManager::position_to_key(chunk.position)),
+            );
+            chunk_manager.insert_chunks(chunks);
+        }
+        Err(error) => {
+            error!(
+                "Could not load world from {}: {}",
+                settings.world_directory.display(),
+                error
+            );
+        }
+    }
 
     info!("Generating chunks");
 
-    let mut chunks = ChunkManager::instantiate_chunks(Vec3::ZERO, render_distance);
+    let spawn_chunk = terrain_util::chunk_position(settings.spawn_point);
+    let mut chunks = chunk_manager.instantiate_new_chunks(spawn_chunk, render_distance);
 
     chunks.par_iter_mut().for_each(|chunk| {
         info!("Generating chunk at {:?}", chunk.position);
@@ -18,6 +49,96 @@ pub fn setup_world_system(
     chunk_manager.insert_chunks(chunks);
 }
 
+pub fn save_world_on_exit_system(
+    mut exit_events: EventReader<AppExit>,
+    chunk_manager: Res<ChunkManager>,
+    modified_chunks: Res<terrain_resources::ModifiedChunks>,
+    settings: Res<settings::ServerSettings>,
+) {
+    if exit_events.read().count() == 0 {
+        return;
+    }
+
+    save_world(&chunk_manager, &modified_chunks, &settings);
+}
+
+/// Saves the modified chunks every autosave interval if any changed since the last save.
+pub fn autosave_world_system(
+    chunk_manager: Res<ChunkManager>,
+    modified_chunks: Res<terrain_resources::ModifiedChunks>,
+    settings: Res<settings::ServerSettings>,
+    mut last_save: Local<Option<Instant>>,
+    mut unsaved: Local<bool>,
+) {
+    let last_save = last_save.get_or_insert_with(Instant::now);
+    *unsaved |= modified_chunks.is_changed() && !modified_chunks.is_added();
+
+    if !*unsaved || last_save.elapsed() < settings.autosave_interval() {
+        return;
+    }
+
+    save_world(&chunk_manager, &modified_chunks, &settings);
+    *last_save = Instant::now();
+    *unsaved = false;
+}
+
+fn save_world(
+    chunk_manager: &ChunkManager,
+    modified_chunks: &terrain_resources::ModifiedChunks,
+    settings: &settings::ServerSettings,
+) {
+    info!(
+        "Saving {} chunks to {}",
+        modified_chunks.positions.len(),
+        settings.world_directory.display()
+    );
+
+    if let Err(error) = terrain_util::persistence::save_chunks(
+        &settings.world_directory,
+        modified_chunks.chunks(chunk_manager),
+    ) {
+        error!("Could not save world: {}", error);
+    }
+}
+
+/// Drops unmodified chunks outside the view distance of every player and the spawn point.
+pub fn evict_unused_chunks_system(
+    mut chunk_manager: ResMut<ChunkManager>,
+    modified_chunks: Res<terrain_resources::ModifiedChunks>,
+    player_states: Res<player_resources::PlayerStates>,
+    settings: Res<settings::ServerSettings>,
+    mut last_eviction: Local<Option<Instant>>,
+) {
+    if last_eviction.is_some_and(|last_eviction| last_eviction.elapsed() < CHUNK_EVICTION_INTERVAL)
+    {
+        return;
+    }
+    *last_eviction = Some(Instant::now());
+
+    let centers: Vec<Vec3> = player_states
+        .players
+        .values()
+        .map(|state| state.position)
+        .chain(std::iter::once(settings.spawn_point))
+        .map(terrain_util::chunk_position)
+        .collect();
+
+    let unused = terrain_util::unused_chunks(
+        &chunk_manager,
+        &modified_chunks.positions,
+        &centers,
+        settings.view_distance,
+    );
+    if unused.is_empty() {
+        return;
+    }
+
+    debug!("Evicting {} unused chunks", unused.len());
+    for key in unused {
+        chunk_manager.chunks.remove(&key);
+    }
+}
+
 #[cfg(feature = "generator_visualizer")]
 pub use visualizer::*;
 
@@ -35,9 +156,9 @@ mod visualizer {
     use egui_plot::{Line, PlotPoint, PlotPoints};
     use rayon::iter::IntoParallelIterator;
 
+    use crate::{Chunk, ChunkManager, NetworkingMessage, CHUNK_SIZE};
     use rayon::iter::ParallelIterator;
     use renet::{DefaultChannel, RenetServer};
-    use rsmc::{Chunk, ChunkManager, NetworkingMessage, CHUNK_SIZE};
 
     use super::{
         terrain_events,
@@ -1,4 +1,4 @@
-use crate::prelude::*;
+use crate::server::prelude::*;
 
 pub struct Block {
     pub id: BlockId,
@@ -0,0 +1,61 @@
+use std::collections::HashSet;
+
+use crate::server::prelude::*;
+
+/// Coordinates of the chunk containing a world position.
+pub fn chunk_position(world_position: Vec3) -> Vec3 {
+    (world_position / CHUNK_SIZE as f32).floor()
+}
+
+/// Whether a chunk lies within `view_distance` chunks of `center` on every axis.
+pub fn is_chunk_in_view(chunk: Vec3, center: Vec3, view_distance: u32) -> bool {
+    (chunk - center).abs().max_element() <= view_distance as f32
+}
+
+/// Loaded chunks that are unmodified and out of view of every center, safe to drop since the
+/// generator recreates them.
+pub fn unused_chunks(
+    chunk_manager: &ChunkManager,
+    modified: &HashSet<[i32; 3]>,
+    centers: &[Vec3],
+    view_distance: u32,
+) -> Vec<[i32; 3]> {
+    chunk_manager
+        .chunks
+        .keys()
+        .filter(|key| !modified.contains(*key))
+        .filter(|key| {
+            let chunk = Vec3::new(key[0] as f32, key[1] as f32, key[2] as f32);
+            !centers
+                .iter()
+                .any(|center| is_chunk_in_view(chunk, *center, view_distance))
+        })
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_position() {
+        assert_eq!(
+            chunk_position(Vec3::new(1.0, 33.0, -1.0)),
+            Vec3::new(0.0, 1.0, -1.0)
+        );
+    }
+
+    #[test]
+    fn test_unused_chunks_keep_modified_and_visible_chunks() {
+        let mut chunk_manager = ChunkManager::new();
+        for x in [0.0, 5.0, 20.0, 30.0] {
+            chunk_manager.insert_chunk(Chunk::new(Vec3::new(x, 0.0, 0.0)));
+        }
+        let modified = HashSet::from([[30, 0, 0]]);
+
+        let unused = unused_chunks(&chunk_manager, &modified, &[Vec3::ZERO], 8);
+
+        assert_eq!(unused, vec![[20, 0, 0]]);
+    }
+}
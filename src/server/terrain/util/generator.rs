@@ -1,6 +1,6 @@
 use terrain_resources::{Generator, NoiseFunctionParams, TerrainGeneratorParams};
 
-use crate::prelude::*;
+use crate::server::prelude::*;
 
 macro_rules! for_each_chunk_coordinate {
     ($chunk:expr, $body:expr) => {
@@ -0,0 +1,216 @@
+use std::fmt;
+
+use crate::server::prelude::*;
+
+/// Reach added on the server since the player may have moved while the update was in flight.
+pub const REACH_TOLERANCE: f32 = 2.0;
+
+/// Share of the mining time that may be missing when a break arrives, covers network jitter.
+pub const MINING_TOLERANCE: f32 = 0.2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BlockUpdateViolation {
+    OutOfReach,
+    NotVisible,
+    Unbreakable,
+    /// A block is placed where a solid block already is.
+    Occupied,
+    MinedTooFast,
+    /// The game mode of the player does not allow changing blocks.
+    NotAllowed,
+    /// The placed block is not held in the selected hotbar slot.
+    MissingItem,
+}
+
+impl fmt::Display for BlockUpdateViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockUpdateViolation::OutOfReach => write!(f, "block is out of reach"),
+            BlockUpdateViolation::NotVisible => write!(f, "block is hidden behind other blocks"),
+            BlockUpdateViolation::Unbreakable => write!(f, "block cannot be broken"),
+            BlockUpdateViolation::Occupied => write!(f, "position is occupied by a block"),
+            BlockUpdateViolation::MinedTooFast => write!(f, "block was not mined long enough"),
+            BlockUpdateViolation::NotAllowed => {
+                write!(f, "game mode does not allow changing blocks")
+            }
+            BlockUpdateViolation::MissingItem => write!(f, "placed block is not held"),
+        }
+    }
+}
+
+/// Checks that a player standing at `player_position` could have placed or broken the block.
+pub fn validate_block_update(
+    chunk_manager: &ChunkManager,
+    player_position: Vec3,
+    position: Vec3,
+    block: BlockId,
+) -> Result<(), BlockUpdateViolation> {
+    let position = position.floor();
+
+    if !is_block_in_reach(player_position, position, REACH_TOLERANCE) {
+        return Err(BlockUpdateViolation::OutOfReach);
+    }
+
+    // Breaking a block requires looking at it
+    if block == BlockId::Air {
+        let eye = player_position + Vec3::Y * PLAYER_EYE_HEIGHT;
+        let hit = raycast_blocks(
+            chunk_manager,
+            eye,
+            position + 0.5 - eye,
+            MAX_REACH + REACH_TOLERANCE,
+        );
+
+        if !hit.is_some_and(|hit| hit.position == position) {
+            return Err(BlockUpdateViolation::NotVisible);
+        }
+    } else if chunk_manager
+        .get_block(position)
+        .is_some_and(|current| current.is_solid())
+    {
+        // Only air and tallgrass may be replaced by a placed block
+        return Err(BlockUpdateViolation::Occupied);
+    }
+
+    Ok(())
+}
+
+/// Checks that a block was mined for long enough with the held item, `mining_time` is how long
+/// the player has been mining this block.
+pub fn validate_mining(
+    block: BlockId,
+    item: Option<ItemId>,
+    mining_time: Option<Duration>,
+) -> Result<(), BlockUpdateViolation> {
+    let required = mining_duration(block, item).ok_or(BlockUpdateViolation::Unbreakable)?;
+    if required.is_zero() {
+        return Ok(());
+    }
+
+    let mining_time = mining_time.ok_or(BlockUpdateViolation::MinedTooFast)?;
+    if mining_time.as_secs_f32() < required.as_secs_f32() * (1.0 - MINING_TOLERANCE) {
+        return Err(BlockUpdateViolation::MinedTooFast);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk_manager_with_wall() -> ChunkManager {
+        let mut chunk_manager = ChunkManager::new();
+        chunk_manager.insert_chunk(Chunk::new(Vec3::ZERO));
+
+        for y in 0..8 {
+            chunk_manager.update_block(Vec3::new(5.0, y as f32, 2.0), BlockId::Stone);
+            chunk_manager.update_block(Vec3::new(6.0, y as f32, 2.0), BlockId::Stone);
+        }
+
+        chunk_manager
+    }
+
+    #[test]
+    fn test_visible_block_can_be_broken() {
+        let chunk_manager = chunk_manager_with_wall();
+
+        assert_eq!(
+            validate_block_update(
+                &chunk_manager,
+                Vec3::new(2.5, 1.0, 2.5),
+                Vec3::new(5.0, 2.0, 2.0),
+                BlockId::Air
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_hidden_block_cannot_be_broken() {
+        let chunk_manager = chunk_manager_with_wall();
+
+        assert_eq!(
+            validate_block_update(
+                &chunk_manager,
+                Vec3::new(2.5, 1.0, 2.5),
+                Vec3::new(6.0, 2.0, 2.0),
+                BlockId::Air
+            ),
+            Err(BlockUpdateViolation::NotVisible)
+        );
+    }
+
+    #[test]
+    fn test_distant_block_cannot_be_placed() {
+        let chunk_manager = chunk_manager_with_wall();
+
+        assert_eq!(
+            validate_block_update(
+                &chunk_manager,
+                Vec3::new(2.5, 1.0, 2.5),
+                Vec3::new(4.0, 2.0, 2.0),
+                BlockId::Dirt
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            validate_block_update(
+                &chunk_manager,
+                Vec3::new(2.5, 1.0, 2.5),
+                Vec3::new(30.0, 2.0, 2.0),
+                BlockId::Dirt
+            ),
+            Err(BlockUpdateViolation::OutOfReach)
+        );
+    }
+
+    #[test]
+    fn test_block_cannot_be_placed_into_solid_block() {
+        let mut chunk_manager = chunk_manager_with_wall();
+        chunk_manager.update_block(Vec3::new(4.0, 2.0, 2.0), BlockId::Bedrock);
+        chunk_manager.update_block(Vec3::new(3.0, 2.0, 2.0), BlockId::Tallgrass);
+
+        assert_eq!(
+            validate_block_update(
+                &chunk_manager,
+                Vec3::new(2.5, 1.0, 2.5),
+                Vec3::new(4.0, 2.0, 2.0),
+                BlockId::Dirt
+            ),
+            Err(BlockUpdateViolation::Occupied)
+        );
+        assert_eq!(
+            validate_block_update(
+                &chunk_manager,
+                Vec3::new(2.5, 1.0, 2.5),
+                Vec3::new(3.0, 2.0, 2.0),
+                BlockId::Dirt
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_mining_duration_is_validated() {
+        let pickaxe = Some(ItemId::Tool(ToolKind::Pickaxe, ToolTier::Wood));
+
+        assert_eq!(
+            validate_mining(BlockId::Stone, pickaxe, Some(Duration::from_secs(4))),
+            Ok(())
+        );
+        assert_eq!(
+            validate_mining(BlockId::Stone, None, Some(Duration::from_secs(4))),
+            Err(BlockUpdateViolation::MinedTooFast)
+        );
+        assert_eq!(
+            validate_mining(BlockId::Stone, pickaxe, None),
+            Err(BlockUpdateViolation::MinedTooFast)
+        );
+        assert_eq!(validate_mining(BlockId::Tallgrass, None, None), Ok(()));
+        assert_eq!(
+            validate_mining(BlockId::Bedrock, pickaxe, Some(Duration::from_secs(60))),
+            Err(BlockUpdateViolation::Unbreakable)
+        );
+    }
+}
@@ -1,4 +1,9 @@
 pub mod blocks;
+pub mod chunks;
 pub mod generator;
+pub mod interaction;
+pub mod persistence;
 
 pub use blocks::*;
+pub use chunks::*;
+pub use interaction::*;
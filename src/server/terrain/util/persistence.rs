@@ -0,0 +1,68 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::server::prelude::*;
+
+const CHUNKS_FILE_NAME: &str = "chunks.bin";
+
+pub fn save_chunks(world_directory: &Path, chunks: Vec<&Chunk>) -> io::Result<()> {
+    fs::create_dir_all(world_directory)?;
+
+    let bytes = bincode::serialize(&chunks).map_err(io::Error::other)?;
+
+    // Write to a temporary file first so a crash while saving does not corrupt the world
+    let path = world_directory.join(CHUNKS_FILE_NAME);
+    let temporary_path = path.with_extension("bin.tmp");
+    fs::write(&temporary_path, bytes)?;
+    fs::rename(temporary_path, path)
+}
+
+pub fn load_chunks(world_directory: &Path) -> io::Result<Vec<Chunk>> {
+    let path = world_directory.join(CHUNKS_FILE_NAME);
+
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let bytes = fs::read(path)?;
+    bincode::deserialize(&bytes).map_err(io::Error::other)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load_chunks() {
+        let world_directory =
+            std::env::temp_dir().join(format!("rsmc-world-{}", rand::random::<u64>()));
+
+        let mut chunk_manager = ChunkManager::new();
+        chunk_manager.insert_chunks(ChunkManager::instantiate_chunks(Vec3::ZERO, Vec3::ONE));
+        chunk_manager.update_block(Vec3::new(1.0, 2.0, 3.0), BlockId::Stone);
+
+        save_chunks(&world_directory, chunk_manager.chunks.values().collect()).unwrap();
+        let chunks = load_chunks(&world_directory).unwrap();
+
+        let mut loaded_chunk_manager = ChunkManager::new();
+        loaded_chunk_manager.insert_chunks(chunks);
+
+        assert_eq!(
+            loaded_chunk_manager.chunks.len(),
+            chunk_manager.chunks.len()
+        );
+        assert_eq!(
+            loaded_chunk_manager.get_block(Vec3::new(1.0, 2.0, 3.0)),
+            Some(BlockId::Stone)
+        );
+
+        fs::remove_dir_all(world_directory).unwrap();
+    }
+
+    #[test]
+    fn test_load_missing_world() {
+        let world_directory = std::env::temp_dir().join("rsmc-world-that-does-not-exist");
+        assert!(load_chunks(&world_directory).unwrap().is_empty());
+    }
+}
@@ -16,6 +16,8 @@ pub enum BlockId {
 use serde::{Deserialize, Serialize};
 use BlockId::*;
 
+use super::ToolKind;
+
 impl From<u8> for BlockId {
     fn from(value: u8) -> Self {
         match value {
@@ -73,4 +75,34 @@ impl BlockId {
     pub fn supports_grass(&self) -> bool {
         *self == Grass || *self == Dirt
     }
+
+    /// Whether entities collide with the block.
+    pub fn is_solid(&self) -> bool {
+        !matches!(self, Air | Tallgrass)
+    }
+
+    /// Seconds needed to break the block by hand, `None` for blocks that cannot be broken.
+    pub fn hardness(&self) -> Option<f32> {
+        match self {
+            Air | Tallgrass => Some(0.0),
+            OakLeaves => Some(0.35),
+            Dirt => Some(0.75),
+            Grass => Some(0.9),
+            OakLog => Some(3.0),
+            Stone => Some(7.5),
+            CobbleStone => Some(10.0),
+            IronOre | CoalOre => Some(15.0),
+            Bedrock => None,
+        }
+    }
+
+    /// Tool that speeds up breaking the block.
+    pub fn preferred_tool(&self) -> Option<ToolKind> {
+        match self {
+            Grass | Dirt => Some(ToolKind::Shovel),
+            Stone | CobbleStone | IronOre | CoalOre => Some(ToolKind::Pickaxe),
+            OakLog => Some(ToolKind::Axe),
+            Air | Bedrock | OakLeaves | Tallgrass => None,
+        }
+    }
 }
@@ -0,0 +1,140 @@
+use bevy::math::{IVec3, Vec3};
+
+use super::ChunkManager;
+
+/// Collision box of a player, smaller than the client capsule so validation stays lenient.
+pub const PLAYER_WIDTH: f32 = 0.6;
+pub const PLAYER_HEIGHT: f32 = 1.8;
+
+/// Distance by which boxes are shrunk so touching a block face does not count as overlap.
+const EPSILON: f32 = 1e-4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self { min, max }
+    }
+
+    /// Box of the given size with its bottom face centered on `position`.
+    pub fn from_feet(position: Vec3, width: f32, height: f32) -> Self {
+        let half_width = width / 2.0;
+
+        Self {
+            min: position - Vec3::new(half_width, 0.0, half_width),
+            max: position + Vec3::new(half_width, height, half_width),
+        }
+    }
+
+    pub fn player(position: Vec3) -> Self {
+        Self::from_feet(position, PLAYER_WIDTH, PLAYER_HEIGHT)
+    }
+
+    pub fn translated(&self, offset: Vec3) -> Self {
+        Self {
+            min: self.min + offset,
+            max: self.max + offset,
+        }
+    }
+
+    pub fn expanded(&self, amount: Vec3) -> Self {
+        Self {
+            min: self.min - amount,
+            max: self.max + amount,
+        }
+    }
+
+    /// Positions of all blocks the box overlaps.
+    pub fn block_positions(&self) -> impl Iterator<Item = IVec3> {
+        let min = (self.min + EPSILON).floor().as_ivec3();
+        let max = (self.max - EPSILON).floor().as_ivec3();
+
+        (min.x..=max.x).flat_map(move |x| {
+            (min.y..=max.y).flat_map(move |y| (min.z..=max.z).map(move |z| IVec3::new(x, y, z)))
+        })
+    }
+}
+
+/// Whether the box overlaps a solid block, blocks of unloaded chunks are not solid.
+pub fn collides_with_blocks(chunk_manager: &ChunkManager, aabb: &Aabb) -> bool {
+    aabb.block_positions().any(|position| {
+        chunk_manager
+            .get_block(position.as_vec3())
+            .is_some_and(|block| block.is_solid())
+    })
+}
+
+/// Whether a solid block is within `depth` below the bottom of the box.
+pub fn is_supported(chunk_manager: &ChunkManager, aabb: &Aabb, depth: f32) -> bool {
+    let below = Aabb::new(
+        Vec3::new(aabb.min.x, aabb.min.y - depth, aabb.min.z),
+        Vec3::new(aabb.max.x, aabb.min.y, aabb.max.z),
+    );
+
+    collides_with_blocks(chunk_manager, &below)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BlockId, Chunk};
+
+    fn chunk_manager_with_floor() -> ChunkManager {
+        let mut chunk_manager = ChunkManager::new();
+        chunk_manager.insert_chunk(Chunk::new(Vec3::ZERO));
+
+        for x in 0..8 {
+            for z in 0..8 {
+                chunk_manager.update_block(Vec3::new(x as f32, 4.0, z as f32), BlockId::Stone);
+            }
+        }
+
+        chunk_manager
+    }
+
+    #[test]
+    fn test_block_positions() {
+        let aabb = Aabb::new(Vec3::new(0.5, 1.0, 0.5), Vec3::new(1.5, 2.0, 1.0));
+        let positions: Vec<IVec3> = aabb.block_positions().collect();
+
+        assert_eq!(positions, vec![IVec3::new(0, 1, 0), IVec3::new(1, 1, 0)]);
+    }
+
+    #[test]
+    fn test_collides_with_blocks() {
+        let chunk_manager = chunk_manager_with_floor();
+
+        assert!(!collides_with_blocks(
+            &chunk_manager,
+            &Aabb::player(Vec3::new(2.5, 5.0, 2.5))
+        ));
+        assert!(collides_with_blocks(
+            &chunk_manager,
+            &Aabb::player(Vec3::new(2.5, 4.5, 2.5))
+        ));
+        assert!(!collides_with_blocks(
+            &chunk_manager,
+            &Aabb::player(Vec3::new(100.0, 4.5, 2.5))
+        ));
+    }
+
+    #[test]
+    fn test_is_supported() {
+        let chunk_manager = chunk_manager_with_floor();
+
+        assert!(is_supported(
+            &chunk_manager,
+            &Aabb::player(Vec3::new(2.5, 5.0, 2.5)),
+            0.1
+        ));
+        assert!(!is_supported(
+            &chunk_manager,
+            &Aabb::player(Vec3::new(2.5, 6.0, 2.5)),
+            0.1
+        ));
+    }
+}
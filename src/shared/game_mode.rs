@@ -0,0 +1,107 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Rules a player is bound to, owned by the server and synced to the client.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum GameMode {
+    /// Blocks take time to mine, placing consumes items and players take damage.
+    #[default]
+    Survival,
+    /// Players may fly, break blocks instantly and place blocks without using them up.
+    Creative,
+    /// Players fly through blocks, are hidden from others and cannot interact with the world.
+    Spectator,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 3] = [GameMode::Survival, GameMode::Creative, GameMode::Spectator];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameMode::Survival => "survival",
+            GameMode::Creative => "creative",
+            GameMode::Spectator => "spectator",
+        }
+    }
+
+    pub fn can_fly(&self) -> bool {
+        matches!(self, GameMode::Creative | GameMode::Spectator)
+    }
+
+    /// Whether the player is stopped by solid blocks.
+    pub fn collides(&self) -> bool {
+        *self != GameMode::Spectator
+    }
+
+    /// Whether the player may break and place blocks or pick up items.
+    pub fn can_interact(&self) -> bool {
+        *self != GameMode::Spectator
+    }
+
+    pub fn is_visible(&self) -> bool {
+        *self != GameMode::Spectator
+    }
+
+    pub fn takes_damage(&self) -> bool {
+        *self == GameMode::Survival
+    }
+
+    pub fn breaks_instantly(&self) -> bool {
+        *self == GameMode::Creative
+    }
+
+    /// Whether placing a block uses up an item of the inventory.
+    pub fn consumes_items(&self) -> bool {
+        *self == GameMode::Survival
+    }
+}
+
+impl fmt::Display for GameMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for GameMode {
+    type Err = String;
+
+    /// Accepts the full name, its first letter or the index of the mode.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim().to_lowercase();
+
+        GameMode::ALL
+            .into_iter()
+            .enumerate()
+            .find(|(index, mode)| {
+                value == mode.name() || value == mode.name()[..1] || value == index.to_string()
+            })
+            .map(|(_, mode)| mode)
+            .ok_or_else(|| format!("unknown game mode '{}'", value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_game_mode() {
+        assert_eq!("creative".parse(), Ok(GameMode::Creative));
+        assert_eq!("Spectator".parse(), Ok(GameMode::Spectator));
+        assert_eq!("s".parse(), Ok(GameMode::Survival));
+        assert_eq!("1".parse(), Ok(GameMode::Creative));
+        assert!("adventure".parse::<GameMode>().is_err());
+    }
+
+    #[test]
+    fn test_only_survival_takes_damage_and_consumes_items() {
+        for mode in GameMode::ALL {
+            assert_eq!(mode.takes_damage(), mode == GameMode::Survival);
+            assert_eq!(mode.consumes_items(), mode == GameMode::Survival);
+            assert_eq!(mode.can_fly(), mode != GameMode::Survival);
+        }
+    }
+}
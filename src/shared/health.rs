@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+pub const MAX_HEALTH: u32 = 20;
+/// Falls from up to this height do not hurt.
+pub const SAFE_FALL_HEIGHT: f32 = 3.0;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DamageCause {
+    Fall,
+    Suffocation,
+}
+
+impl DamageCause {
+    pub fn death_message(&self) -> &'static str {
+        match self {
+            DamageCause::Fall => "You hit the ground too hard",
+            DamageCause::Suffocation => "You suffocated in a wall",
+        }
+    }
+}
+
+/// One point of damage per block fallen beyond the safe height.
+pub fn fall_damage_for_height(height: f32) -> u32 {
+    (height - SAFE_FALL_HEIGHT).floor().max(0.0) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_falls_do_not_hurt() {
+        assert_eq!(fall_damage_for_height(1.0), 0);
+        assert_eq!(fall_damage_for_height(SAFE_FALL_HEIGHT), 0);
+        assert_eq!(fall_damage_for_height(-2.0), 0);
+    }
+
+    #[test]
+    fn test_fall_damage_grows_with_height() {
+        assert_eq!(fall_damage_for_height(5.5), 2);
+        assert_eq!(fall_damage_for_height(30.5), 27);
+    }
+}
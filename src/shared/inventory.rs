@@ -0,0 +1,180 @@
+use serde::{Deserialize, Serialize};
+
+use super::{ItemId, ToolKind, ToolTier};
+
+pub const HOTBAR_SIZE: usize = 9;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ItemStack {
+    pub item: ItemId,
+    pub count: u32,
+}
+
+/// Items carried by a player, the server owns the authoritative copy and syncs it to the client.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Inventory {
+    pub slots: Vec<Option<ItemStack>>,
+    pub selected: usize,
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Self {
+            slots: vec![None; HOTBAR_SIZE],
+            selected: 0,
+        }
+    }
+}
+
+impl Inventory {
+    /// Inventory of a new player, holding one tool of each kind.
+    pub fn with_starting_tools() -> Self {
+        let mut inventory = Self::default();
+
+        for (slot, kind) in [ToolKind::Pickaxe, ToolKind::Shovel, ToolKind::Axe]
+            .into_iter()
+            .enumerate()
+        {
+            inventory.slots[slot] = Some(ItemStack {
+                item: ItemId::Tool(kind, ToolTier::Wood),
+                count: 1,
+            });
+        }
+
+        inventory
+    }
+
+    pub fn select(&mut self, slot: usize) {
+        if slot < self.slots.len() {
+            self.selected = slot;
+        }
+    }
+
+    pub fn selected_item(&self) -> Option<ItemId> {
+        self.slots
+            .get(self.selected)
+            .copied()
+            .flatten()
+            .map(|stack| stack.item)
+    }
+
+    /// Removes one item from the selected slot if it holds `item`, returns whether it did.
+    pub fn take_selected(&mut self, item: ItemId) -> bool {
+        let Some(slot) = self.slots.get_mut(self.selected) else {
+            return false;
+        };
+
+        match slot {
+            Some(stack) if stack.item == item => {
+                stack.count -= 1;
+                if stack.count == 0 {
+                    *slot = None;
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Adds items to matching stacks first and empty slots after, returns the count that did not fit.
+    pub fn add(&mut self, item: ItemId, count: u32) -> u32 {
+        let mut remaining = count;
+
+        for stack in self.slots.iter_mut().flatten() {
+            if stack.item == item {
+                let added = remaining.min(item.max_stack_size().saturating_sub(stack.count));
+                stack.count += added;
+                remaining -= added;
+            }
+        }
+
+        for slot in self.slots.iter_mut() {
+            if remaining == 0 {
+                break;
+            }
+
+            if slot.is_none() {
+                let added = remaining.min(item.max_stack_size());
+                *slot = Some(ItemStack { item, count: added });
+                remaining -= added;
+            }
+        }
+
+        remaining
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BlockId;
+
+    #[test]
+    fn test_selected_item() {
+        let mut inventory = Inventory::with_starting_tools();
+
+        assert_eq!(
+            inventory.selected_item(),
+            Some(ItemId::Tool(ToolKind::Pickaxe, ToolTier::Wood))
+        );
+
+        inventory.select(5);
+        assert_eq!(inventory.selected_item(), None);
+
+        inventory.select(HOTBAR_SIZE);
+        assert_eq!(inventory.selected, 5);
+    }
+
+    #[test]
+    fn test_add_fills_stacks_then_empty_slots() {
+        let mut inventory = Inventory::with_starting_tools();
+        let dirt = ItemId::Block(BlockId::Dirt);
+
+        assert_eq!(inventory.add(dirt, 40), 0);
+        assert_eq!(inventory.add(dirt, 40), 0);
+
+        assert_eq!(
+            inventory.slots[3],
+            Some(ItemStack {
+                item: dirt,
+                count: 64
+            })
+        );
+        assert_eq!(
+            inventory.slots[4],
+            Some(ItemStack {
+                item: dirt,
+                count: 16
+            })
+        );
+    }
+
+    #[test]
+    fn test_take_selected_removes_one_item() {
+        let mut inventory = Inventory::default();
+        let dirt = ItemId::Block(BlockId::Dirt);
+        inventory.add(dirt, 2);
+
+        assert!(!inventory.take_selected(ItemId::Block(BlockId::Stone)));
+        assert!(inventory.take_selected(dirt));
+        assert_eq!(
+            inventory.slots[0],
+            Some(ItemStack {
+                item: dirt,
+                count: 1
+            })
+        );
+        assert!(inventory.take_selected(dirt));
+        assert_eq!(inventory.slots[0], None);
+        assert!(!inventory.take_selected(dirt));
+    }
+
+    #[test]
+    fn test_add_returns_items_that_do_not_fit() {
+        let mut inventory = Inventory::default();
+        let pickaxe = ItemId::Tool(ToolKind::Pickaxe, ToolTier::Stone);
+
+        assert_eq!(inventory.add(pickaxe, HOTBAR_SIZE as u32 + 2), 2);
+        assert!(inventory.slots.iter().all(Option::is_some));
+    }
+}
@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+use bevy::ecs::component::Component;
+use serde::{Deserialize, Serialize};
+
+use super::{BlockId, ReplicatedComponent};
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize, Hash)]
+pub enum ToolKind {
+    Pickaxe,
+    Shovel,
+    Axe,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize, Hash)]
+pub enum ToolTier {
+    Wood,
+    Stone,
+    Iron,
+}
+
+impl ToolTier {
+    /// Factor by which the tool speeds up breaking blocks it is made for.
+    pub fn speed(&self) -> f32 {
+        match self {
+            ToolTier::Wood => 2.0,
+            ToolTier::Stone => 4.0,
+            ToolTier::Iron => 6.0,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize, Hash)]
+pub enum ItemId {
+    Block(BlockId),
+    Tool(ToolKind, ToolTier),
+}
+
+impl ItemId {
+    pub fn name(&self) -> String {
+        match self {
+            ItemId::Block(block) => format!("{:?}", block),
+            ItemId::Tool(kind, tier) => format!("{:?} {:?}", tier, kind),
+        }
+    }
+
+    pub fn max_stack_size(&self) -> u32 {
+        match self {
+            ItemId::Block(_) => 64,
+            ItemId::Tool(_, _) => 1,
+        }
+    }
+
+    /// Item dropped when the block is broken.
+    pub fn dropped_by(block: BlockId) -> Option<ItemId> {
+        match block {
+            BlockId::Air | BlockId::Tallgrass | BlockId::OakLeaves | BlockId::Bedrock => None,
+            BlockId::Grass => Some(ItemId::Block(BlockId::Dirt)),
+            BlockId::Stone => Some(ItemId::Block(BlockId::CobbleStone)),
+            block => Some(ItemId::Block(block)),
+        }
+    }
+}
+
+/// Item lying in the world, waiting to be picked up.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct DroppedItem {
+    pub item: ItemId,
+}
+
+impl ReplicatedComponent for DroppedItem {
+    const KIND: u16 = 1;
+}
+
+/// Time needed to break the block while holding `item`, `None` if it cannot be broken.
+pub fn mining_duration(block: BlockId, item: Option<ItemId>) -> Option<Duration> {
+    let hardness = block.hardness()?;
+
+    let speed = match (item, block.preferred_tool()) {
+        (Some(ItemId::Tool(kind, tier)), Some(preferred)) if kind == preferred => tier.speed(),
+        _ => 1.0,
+    };
+
+    Some(Duration::from_secs_f32(hardness / speed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mining_duration_by_hand() {
+        assert_eq!(
+            mining_duration(BlockId::Stone, None),
+            Some(Duration::from_secs_f32(7.5))
+        );
+        assert_eq!(
+            mining_duration(BlockId::Tallgrass, None),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(mining_duration(BlockId::Bedrock, None), None);
+    }
+
+    #[test]
+    fn test_matching_tools_speed_up_mining() {
+        let pickaxe = ItemId::Tool(ToolKind::Pickaxe, ToolTier::Stone);
+
+        assert_eq!(
+            mining_duration(BlockId::Stone, Some(pickaxe)),
+            Some(Duration::from_secs_f32(7.5 / 4.0))
+        );
+        assert_eq!(
+            mining_duration(BlockId::Dirt, Some(pickaxe)),
+            mining_duration(BlockId::Dirt, None)
+        );
+        assert_eq!(mining_duration(BlockId::Bedrock, Some(pickaxe)), None);
+    }
+}
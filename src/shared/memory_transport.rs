@@ -0,0 +1,267 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use bevy::app::{App, Plugin, PostUpdate, PreUpdate};
+use bevy::ecs::schedule::IntoSystemConfigs;
+use bevy::ecs::system::{ResMut, Resource};
+use bevy::log::info;
+use bevy::prelude::resource_exists;
+use bevy_renet::{RenetClientPlugin, RenetReceive, RenetSend, RenetServerPlugin};
+use renet::{ClientId, RenetClient, RenetServer};
+
+#[derive(Clone, Default)]
+struct PacketQueue(Arc<Mutex<VecDeque<Vec<u8>>>>);
+
+impl PacketQueue {
+    fn push(&self, packet: Vec<u8>) {
+        self.0.lock().unwrap().push_back(packet);
+    }
+
+    fn drain(&self) -> Vec<Vec<u8>> {
+        self.0.lock().unwrap().drain(..).collect()
+    }
+}
+
+/// One end of an in-memory connection, packets pushed on one end are received on the other.
+pub struct MemoryConnection {
+    incoming: PacketQueue,
+    outgoing: PacketQueue,
+    closed: Arc<AtomicBool>,
+}
+
+impl MemoryConnection {
+    pub fn send(&self, packet: Vec<u8>) {
+        self.outgoing.push(packet);
+    }
+
+    pub fn receive(&self) -> Vec<Vec<u8>> {
+        self.incoming.drain()
+    }
+
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for MemoryConnection {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+pub fn memory_connection_pair() -> (MemoryConnection, MemoryConnection) {
+    let first = PacketQueue::default();
+    let second = PacketQueue::default();
+    let closed = Arc::new(AtomicBool::new(false));
+
+    (
+        MemoryConnection {
+            incoming: first.clone(),
+            outgoing: second.clone(),
+            closed: closed.clone(),
+        },
+        MemoryConnection {
+            incoming: second,
+            outgoing: first,
+            closed,
+        },
+    )
+}
+
+#[derive(Resource, Default)]
+pub struct MemoryServerTransport {
+    connections: HashMap<ClientId, MemoryConnection>,
+    pending_connections: Vec<(ClientId, MemoryConnection)>,
+}
+
+impl MemoryServerTransport {
+    pub fn new(client_id: ClientId, connection: MemoryConnection) -> Self {
+        let mut transport = Self::default();
+        transport.connect(client_id, connection);
+        transport
+    }
+
+    /// Queues a connection, the client is added to the server on the next update.
+    pub fn connect(&mut self, client_id: ClientId, connection: MemoryConnection) {
+        self.pending_connections.push((client_id, connection));
+    }
+
+    pub fn update(&mut self, server: &mut RenetServer) {
+        for (client_id, connection) in self.pending_connections.drain(..) {
+            info!("Client {} connected through memory transport", client_id);
+            server.add_connection(client_id);
+            self.connections.insert(client_id, connection);
+        }
+
+        let closed_clients: Vec<ClientId> = self
+            .connections
+            .iter()
+            .filter(|(_, connection)| connection.is_closed())
+            .map(|(client_id, _)| *client_id)
+            .collect();
+
+        for client_id in closed_clients {
+            self.connections.remove(&client_id);
+            server.remove_connection(client_id);
+        }
+
+        for (client_id, connection) in self.connections.iter() {
+            for packet in connection.receive() {
+                let _ = server.process_packet_from(&packet, *client_id);
+            }
+        }
+    }
+
+    pub fn send_packets(&mut self, server: &mut RenetServer) {
+        for (client_id, connection) in self.connections.iter() {
+            if let Ok(packets) = server.get_packets_to_send(*client_id) {
+                for packet in packets {
+                    connection.send(packet);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct MemoryClientTransport {
+    connection: MemoryConnection,
+}
+
+impl MemoryClientTransport {
+    pub fn new(connection: MemoryConnection) -> Self {
+        Self { connection }
+    }
+
+    pub fn update(&mut self, client: &mut RenetClient) {
+        if self.connection.is_closed() {
+            client.disconnect_due_to_transport();
+            return;
+        }
+
+        client.set_connected();
+        for packet in self.connection.receive() {
+            client.process_packet(&packet);
+        }
+    }
+
+    pub fn send_packets(&mut self, client: &mut RenetClient) {
+        if self.connection.is_closed() {
+            return;
+        }
+
+        for packet in client.get_packets_to_send() {
+            self.connection.send(packet);
+        }
+    }
+
+    pub fn disconnect(&mut self) {
+        self.connection.close();
+    }
+}
+
+/// Drives a `MemoryServerTransport` the same way the netcode plugin drives the UDP transport.
+pub struct MemoryServerTransportPlugin;
+
+impl Plugin for MemoryServerTransportPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            receive_server_packets_system
+                .in_set(RenetReceive)
+                .after(RenetServerPlugin::update_system)
+                .run_if(resource_exists::<MemoryServerTransport>)
+                .run_if(resource_exists::<RenetServer>),
+        );
+        app.add_systems(
+            PostUpdate,
+            send_server_packets_system
+                .in_set(RenetSend)
+                .run_if(resource_exists::<MemoryServerTransport>)
+                .run_if(resource_exists::<RenetServer>),
+        );
+    }
+}
+
+/// Drives a `MemoryClientTransport` the same way the netcode plugin drives the UDP transport.
+pub struct MemoryClientTransportPlugin;
+
+impl Plugin for MemoryClientTransportPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            receive_client_packets_system
+                .in_set(RenetReceive)
+                .after(RenetClientPlugin::update_system)
+                .run_if(resource_exists::<MemoryClientTransport>)
+                .run_if(resource_exists::<RenetClient>),
+        );
+        app.add_systems(
+            PostUpdate,
+            send_client_packets_system
+                .in_set(RenetSend)
+                .run_if(resource_exists::<MemoryClientTransport>)
+                .run_if(resource_exists::<RenetClient>),
+        );
+    }
+}
+
+fn receive_server_packets_system(
+    mut transport: ResMut<MemoryServerTransport>,
+    mut server: ResMut<RenetServer>,
+) {
+    transport.update(&mut server);
+}
+
+fn send_server_packets_system(
+    mut transport: ResMut<MemoryServerTransport>,
+    mut server: ResMut<RenetServer>,
+) {
+    transport.send_packets(&mut server);
+}
+
+fn receive_client_packets_system(
+    mut transport: ResMut<MemoryClientTransport>,
+    mut client: ResMut<RenetClient>,
+) {
+    transport.update(&mut client);
+}
+
+fn send_client_packets_system(
+    mut transport: ResMut<MemoryClientTransport>,
+    mut client: ResMut<RenetClient>,
+) {
+    transport.send_packets(&mut client);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_connection_pair_delivers_packets() {
+        let (client, server) = memory_connection_pair();
+
+        client.send(vec![1, 2, 3]);
+        server.send(vec![4]);
+
+        assert_eq!(server.receive(), vec![vec![1, 2, 3]]);
+        assert_eq!(client.receive(), vec![vec![4]]);
+        assert!(server.receive().is_empty());
+    }
+
+    #[test]
+    fn test_dropping_connection_closes_both_ends() {
+        let (client, server) = memory_connection_pair();
+        assert!(!server.is_closed());
+
+        drop(client);
+
+        assert!(server.is_closed());
+    }
+}
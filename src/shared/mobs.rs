@@ -0,0 +1,61 @@
+use bevy::ecs::component::Component;
+use bevy::math::Vec2;
+use serde::{Deserialize, Serialize};
+
+use super::{BlockId, ItemId, ReplicatedComponent};
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize, Hash)]
+pub enum MobKind {
+    Pig,
+    Sheep,
+}
+
+impl MobKind {
+    pub const ALL: [MobKind; 2] = [MobKind::Pig, MobKind::Sheep];
+
+    /// Width and height of the collision box.
+    pub fn size(&self) -> Vec2 {
+        match self {
+            MobKind::Pig => Vec2::new(0.9, 0.9),
+            MobKind::Sheep => Vec2::new(0.9, 1.3),
+        }
+    }
+
+    /// Walking speed in blocks per second.
+    pub fn speed(&self) -> f32 {
+        match self {
+            MobKind::Pig => 2.5,
+            MobKind::Sheep => 2.0,
+        }
+    }
+
+    /// Surface blocks the mob spawns on.
+    pub fn spawn_blocks(&self) -> &'static [BlockId] {
+        match self {
+            MobKind::Pig => &[BlockId::Grass, BlockId::Dirt],
+            MobKind::Sheep => &[BlockId::Grass],
+        }
+    }
+
+    /// Item that makes the mob follow the player holding it.
+    pub fn lure_item(&self) -> Option<ItemId> {
+        match self {
+            MobKind::Pig => Some(ItemId::Block(BlockId::Dirt)),
+            MobKind::Sheep => None,
+        }
+    }
+
+    /// Skittish mobs run away from players coming too close.
+    pub fn is_skittish(&self) -> bool {
+        matches!(self, MobKind::Sheep)
+    }
+}
+
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Mob {
+    pub kind: MobKind,
+}
+
+impl ReplicatedComponent for Mob {
+    const KIND: u16 = 3;
+}
@@ -1,10 +1,32 @@
 pub mod blocks;
 pub mod buffer_serializer;
 pub mod chunk_serializer;
+pub mod collision;
+pub mod game_mode;
+pub mod health;
+pub mod inventory;
+pub mod items;
+pub mod memory_transport;
+pub mod mobs;
+pub mod movement;
 pub mod networking;
+pub mod raycast;
+pub mod replication;
+pub mod snapshot;
 pub mod terrain;
 
 pub use blocks::*;
 pub use buffer_serializer::*;
+pub use collision::*;
+pub use game_mode::*;
+pub use health::*;
+pub use inventory::*;
+pub use items::*;
+pub use memory_transport::*;
+pub use mobs::*;
+pub use movement::*;
 pub use networking::*;
+pub use raycast::*;
+pub use replication::*;
+pub use snapshot::*;
 pub use terrain::*;
@@ -0,0 +1,212 @@
+use bevy::math::{BVec3, Vec3};
+use serde::{Deserialize, Serialize};
+
+use super::{collides_with_blocks, Aabb, ChunkManager, GameMode};
+
+/// Longest distance moved along an axis at once, keeps boxes from tunneling through blocks.
+const MAX_SUBSTEP: f32 = 0.5;
+
+/// Gap left between a box and the block it collided with.
+const SKIN_WIDTH: f32 = 1e-3;
+
+/// Movement of the local player during one frame, tagged with a sequence number so the server
+/// can acknowledge it.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct MovementInput {
+    pub sequence: u32,
+    pub displacement: Vec3,
+}
+
+/// Result of moving a box through the terrain.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sweep {
+    /// Distance the box actually moved.
+    pub offset: Vec3,
+    /// Axes along which the box was stopped by a block.
+    pub collided: BVec3,
+}
+
+/// Moves a box by `displacement`, sliding along solid blocks instead of entering them.
+///
+/// The vertical axis is resolved first so entities land before they slide along the ground.
+pub fn sweep_aabb(chunk_manager: &ChunkManager, aabb: &Aabb, displacement: Vec3) -> Sweep {
+    let substeps = (displacement.abs().max_element() / MAX_SUBSTEP)
+        .ceil()
+        .max(1.0);
+    let substep = displacement / substeps;
+
+    let mut aabb = *aabb;
+    let mut sweep = Sweep {
+        offset: Vec3::ZERO,
+        collided: BVec3::FALSE,
+    };
+
+    for _ in 0..substeps as usize {
+        for axis in [1, 0, 2] {
+            let (distance, collided) = move_along_axis(chunk_manager, &aabb, axis, substep[axis]);
+
+            let mut offset = Vec3::ZERO;
+            offset[axis] = distance;
+            aabb = aabb.translated(offset);
+
+            sweep.offset[axis] += distance;
+            if collided {
+                sweep.collided.set(axis, true);
+            }
+        }
+    }
+
+    sweep
+}
+
+/// Moves a player from `position` by the input displacement, spectators pass through blocks.
+pub fn step_movement(
+    chunk_manager: &ChunkManager,
+    position: Vec3,
+    input: &MovementInput,
+    game_mode: GameMode,
+) -> Vec3 {
+    if !game_mode.collides() {
+        return position + input.displacement;
+    }
+
+    position + sweep_aabb(chunk_manager, &Aabb::player(position), input.displacement).offset
+}
+
+/// Distance the box can move along the axis, and whether it was stopped by a block.
+fn move_along_axis(
+    chunk_manager: &ChunkManager,
+    aabb: &Aabb,
+    axis: usize,
+    distance: f32,
+) -> (f32, bool) {
+    if distance == 0.0 {
+        return (0.0, false);
+    }
+
+    let mut offset = Vec3::ZERO;
+    offset[axis] = distance;
+
+    let moved = aabb.translated(offset);
+    if !collides_with_blocks(chunk_manager, &moved) {
+        return (distance, false);
+    }
+
+    // Snap against the face of the block that was hit
+    let snapped = match distance > 0.0 {
+        true => moved.max[axis].floor() - SKIN_WIDTH - aabb.max[axis],
+        false => moved.min[axis].floor() + 1.0 + SKIN_WIDTH - aabb.min[axis],
+    };
+    offset[axis] = snapped;
+
+    match collides_with_blocks(chunk_manager, &aabb.translated(offset)) {
+        true => (0.0, true),
+        false => (snapped, true),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BlockId, Chunk, CHUNK_SIZE};
+
+    fn chunk_manager_with_floor_and_wall() -> ChunkManager {
+        let mut chunk_manager = ChunkManager::new();
+        chunk_manager.insert_chunk(Chunk::new(Vec3::ZERO));
+
+        for x in 0..CHUNK_SIZE {
+            for z in 0..CHUNK_SIZE {
+                chunk_manager.update_block(Vec3::new(x as f32, 4.0, z as f32), BlockId::Stone);
+            }
+        }
+
+        for y in 5..8 {
+            for z in 0..CHUNK_SIZE {
+                chunk_manager.update_block(Vec3::new(12.0, y as f32, z as f32), BlockId::Stone);
+            }
+        }
+
+        chunk_manager
+    }
+
+    fn input(displacement: Vec3) -> MovementInput {
+        MovementInput {
+            sequence: 0,
+            displacement,
+        }
+    }
+
+    #[test]
+    fn test_free_movement() {
+        let chunk_manager = chunk_manager_with_floor_and_wall();
+        let position = Vec3::new(8.5, 5.0, 8.5);
+        let displacement = Vec3::new(1.0, 0.5, -2.0);
+
+        assert_eq!(
+            step_movement(
+                &chunk_manager,
+                position,
+                &input(displacement),
+                GameMode::Survival
+            ),
+            position + displacement
+        );
+    }
+
+    #[test]
+    fn test_landing_on_floor() {
+        let chunk_manager = chunk_manager_with_floor_and_wall();
+        let position = step_movement(
+            &chunk_manager,
+            Vec3::new(8.5, 7.0, 8.5),
+            &input(Vec3::new(0.0, -5.0, 0.0)),
+            GameMode::Survival,
+        );
+
+        assert!((position.y - 5.0).abs() < 0.01);
+        assert_eq!((position.x, position.z), (8.5, 8.5));
+    }
+
+    #[test]
+    fn test_sliding_along_wall() {
+        let chunk_manager = chunk_manager_with_floor_and_wall();
+        let position = step_movement(
+            &chunk_manager,
+            Vec3::new(10.5, 5.0, 8.5),
+            &input(Vec3::new(3.0, 0.0, 1.0)),
+            GameMode::Survival,
+        );
+
+        assert!((position.x - 11.7).abs() < 0.01);
+        assert!((position.z - 9.5).abs() < 0.01);
+        assert_eq!(position.y, 5.0);
+    }
+
+    #[test]
+    fn test_spectators_pass_through_walls() {
+        let chunk_manager = chunk_manager_with_floor_and_wall();
+        let position = Vec3::new(10.5, 5.0, 8.5);
+        let displacement = Vec3::new(3.0, -2.0, 1.0);
+
+        assert_eq!(
+            step_movement(
+                &chunk_manager,
+                position,
+                &input(displacement),
+                GameMode::Spectator
+            ),
+            position + displacement
+        );
+    }
+
+    #[test]
+    fn test_sweep_reports_collided_axes() {
+        let chunk_manager = chunk_manager_with_floor_and_wall();
+        let aabb = Aabb::from_feet(Vec3::new(10.5, 6.0, 8.5), 0.25, 0.25);
+        let sweep = sweep_aabb(&chunk_manager, &aabb, Vec3::new(2.0, -2.0, 0.5));
+
+        assert_eq!(sweep.collided, BVec3::new(true, true, false));
+        assert!((sweep.offset.y + 1.0).abs() < 0.01);
+        assert!((sweep.offset.z - 0.5).abs() < 0.01);
+    }
+}
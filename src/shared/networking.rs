@@ -1,14 +1,18 @@
 use std::time::Duration;
 
 use bevy::math::{Quat, Vec3};
-use chrono::DateTime;
+use chrono::{DateTime, Local, TimeZone};
 use renet::{ChannelConfig, ClientId, ConnectionConfig, SendType};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 
-use super::{BlockId, Chunk};
+use super::{
+    BlockId, Chunk, DamageCause, EntityUpdate, GameMode, Inventory, MovementInput, NetworkId,
+    PlayerSnapshot,
+};
 
 pub const SERVER_MESSAGE_ID: ClientId = 0;
+pub const MAX_PLAYER_NAME_LENGTH: usize = 16;
+pub const MAX_CHAT_MESSAGE_LENGTH: usize = 256;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct PlayerState {
@@ -16,6 +20,97 @@ pub struct PlayerState {
     pub rotation: Quat,
 }
 
+/// Secret a client generates once and sends when joining, it is never forwarded to other
+/// players. Saved players remember it so nobody can take over a player by their name.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PlayerIdentity(pub [u8; 16]);
+
+impl PlayerIdentity {
+    pub fn random() -> Self {
+        Self(rand::random())
+    }
+
+    pub fn to_hex(&self) -> String {
+        self.0.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        if hex.len() != 32 {
+            return None;
+        }
+
+        let mut bytes = [0; 16];
+        for (index, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(hex.get(index * 2..index * 2 + 2)?, 16).ok()?;
+        }
+
+        Some(Self(bytes))
+    }
+}
+
+/// Sent by a client when it joins and forwarded to the other players.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PlayerProfile {
+    pub name: String,
+    pub color: [u8; 3],
+}
+
+impl Default for PlayerProfile {
+    fn default() -> Self {
+        Self {
+            name: "Player".to_string(),
+            color: [204, 178, 153],
+        }
+    }
+}
+
+impl PlayerProfile {
+    /// Strips control characters and limits the name length, empty names get the default name.
+    pub fn sanitized(self) -> Self {
+        let name: String = self
+            .name
+            .chars()
+            .filter(|character| !character.is_control())
+            .collect();
+        let name: String = name.trim().chars().take(MAX_PLAYER_NAME_LENGTH).collect();
+        let name = name.trim_end().to_string();
+
+        Self {
+            name: match name.is_empty() {
+                true => Self::default().name,
+                false => name,
+            },
+            color: self.color,
+        }
+    }
+
+    /// Parses a hex colour like `ff8800` or `#ff8800`.
+    pub fn parse_color(value: &str) -> Result<[u8; 3], String> {
+        let hex = value.trim_start_matches('#');
+        let invalid = || format!("invalid colour '{}', expected RRGGBB", value);
+
+        if hex.len() != 6 || !hex.is_ascii() {
+            return Err(invalid());
+        }
+
+        let channel = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16);
+        match (channel(0), channel(2), channel(4)) {
+            (Ok(red), Ok(green), Ok(blue)) => Ok([red, green, blue]),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// A connected player as shown in the player list.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PlayerListEntry {
+    pub client_id: ClientId,
+    pub name: String,
+    /// Round trip time in milliseconds.
+    pub ping: u32,
+    pub game_mode: GameMode,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ChatMessage {
     pub client_id: ClientId,
@@ -25,32 +120,109 @@ pub struct ChatMessage {
 }
 
 impl ChatMessage {
-    pub fn format_string(&self) -> String {
-        let dt = DateTime::from_timestamp_millis(self.timestamp).expect("invalid timestamp");
-        let timestamp_string = dt.to_string();
+    /// Formats the message with the time it was sent in the local time zone.
+    pub fn format_string(&self, sender: &str) -> String {
+        self.format_in_time_zone(sender, &Local)
+    }
 
-        let client_name = match self.client_id {
-            SERVER_MESSAGE_ID => "SERVER".to_string(),
-            _ => self.client_id.to_string(),
+    pub fn format_in_time_zone<Tz: TimeZone>(&self, sender: &str, time_zone: &Tz) -> String
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        let time = match DateTime::from_timestamp_millis(self.timestamp) {
+            Some(time) => time.with_timezone(time_zone).format("%H:%M").to_string(),
+            None => "--:--".to_string(),
         };
 
-        format!("[{}] {}: {}", timestamp_string, client_name, self.message)
+        format!("[{}] {}: {}", time, sender, self.message)
+    }
+
+    /// Turns tabs and line breaks into spaces, strips other control characters and surrounding
+    /// whitespace and limits the length, `None` when nothing is left to send.
+    pub fn sanitize(message: &str) -> Option<String> {
+        let message: String = message
+            .chars()
+            .map(|character| match character.is_whitespace() {
+                true => ' ',
+                false => character,
+            })
+            .filter(|character| !character.is_control())
+            .collect();
+        let message: String = message
+            .trim()
+            .chars()
+            .take(MAX_CHAT_MESSAGE_LENGTH)
+            .collect();
+
+        match message.trim_end() {
+            "" => None,
+            message => Some(message.to_string()),
+        }
     }
 }
 
+/// Server performance numbers, tick times are in milliseconds.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct ServerDiagnostics {
+    pub average_tick_time: f32,
+    pub max_tick_time: f32,
+    pub tick_rate: f32,
+    pub client_count: usize,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub enum NetworkingMessage {
-    PlayerJoin(ClientId),
+    JoinGame(PlayerProfile, PlayerIdentity),
+    PlayerJoin(ClientId, PlayerProfile),
     PlayerLeave(ClientId),
-    PlayerUpdate(PlayerState),
-    PlayerSync(HashMap<ClientId, PlayerState>),
+    PlayerUpdate {
+        input: MovementInput,
+        state: PlayerState,
+    },
+    /// Last movement input the server applied and the position it resulted in.
+    PlayerMovementAck {
+        sequence: u32,
+        position: Vec3,
+    },
+    /// Sent when the server rejected a movement, the client moves back to the position.
+    PlayerPositionCorrection(Vec3),
+    PlayerSnapshot(PlayerSnapshot),
+    /// Position the client places its player at, sent when joining and respawning. The rotation
+    /// is only set when a returning player resumes where they left.
+    PlayerSpawn {
+        position: Vec3,
+        rotation: Option<Quat>,
+    },
+    HealthSync(u32),
+    PlayerDied(DamageCause),
+    RespawnRequest,
+    GameModeSync(GameMode),
+    /// Everyone connected, sent when players join or leave and refreshed for the ping.
+    PlayerList(Vec<PlayerListEntry>),
+    InventorySync(Inventory),
+    SelectHotbarSlot(usize),
+    /// Block the client started mining, `None` when it stopped.
+    MiningTarget(Option<Vec3>),
+    /// All replicated components of an entity that is new to the client.
+    EntitySpawn(EntityUpdate),
+    EntityDespawn(NetworkId),
+    /// Changed components of replicated entities, older ticks are outdated.
+    EntityUpdates {
+        tick: u64,
+        entities: Vec<EntityUpdate>,
+    },
+    PlayerSnapshotAck(u32),
     ChunkBatchRequest(Vec<Vec3>),
     ChunkBatchResponse(Vec<Chunk>),
     ChatMessageSend(String),
     SingleChatMessageSync(ChatMessage),
     ChatMessageSync(Vec<ChatMessage>),
-    BlockUpdate { position: Vec3, block: BlockId },
+    BlockUpdate {
+        position: Vec3,
+        block: BlockId,
+    },
     ServerAsksClientNicelyToRerequestChunkBatch(),
+    ServerDiagnostics(ServerDiagnostics),
 }
 
 const CHANNELS: [ChannelConfig; 3] = [
@@ -81,3 +253,78 @@ pub fn connection_config() -> ConnectionConfig {
         ..Default::default()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_sanitized() {
+        let profile = PlayerProfile {
+            name: "  a\nvery long player name  ".to_string(),
+            color: [1, 2, 3],
+        }
+        .sanitized();
+        assert_eq!(profile.name, "avery long playe");
+        assert_eq!(profile.color, [1, 2, 3]);
+
+        let profile = PlayerProfile {
+            name: " \t ".to_string(),
+            color: [0, 0, 0],
+        }
+        .sanitized();
+        assert_eq!(profile.name, PlayerProfile::default().name);
+    }
+
+    #[test]
+    fn test_player_identity_hex_round_trip() {
+        let identity = PlayerIdentity::random();
+
+        assert_eq!(PlayerIdentity::from_hex(&identity.to_hex()), Some(identity));
+        assert_eq!(PlayerIdentity::from_hex("abc"), None);
+        assert_eq!(PlayerIdentity::from_hex(&"zz".repeat(16)), None);
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(PlayerProfile::parse_color("#ff8800"), Ok([255, 136, 0]));
+        assert_eq!(PlayerProfile::parse_color("0a0B0c"), Ok([10, 11, 12]));
+        assert!(PlayerProfile::parse_color("fff").is_err());
+        assert!(PlayerProfile::parse_color("gg0000").is_err());
+    }
+
+    #[test]
+    fn test_chat_message_format_string() {
+        let message = ChatMessage {
+            client_id: 1,
+            message_id: 0,
+            timestamp: 1_700_000_000_000,
+            message: "hello".to_string(),
+        };
+        let time_zone = chrono::FixedOffset::east_opt(2 * 3600).unwrap();
+
+        assert_eq!(
+            message.format_in_time_zone("alice", &time_zone),
+            "[00:13] alice: hello"
+        );
+    }
+
+    #[test]
+    fn test_chat_message_sanitize() {
+        assert_eq!(
+            ChatMessage::sanitize("  hi\tthere \n"),
+            Some("hi there".to_string())
+        );
+        assert_eq!(
+            ChatMessage::sanitize("line\r\nbreak\u{7}"),
+            Some("line  break".to_string())
+        );
+        assert_eq!(ChatMessage::sanitize(" \n "), None);
+
+        let long_message = "a".repeat(MAX_CHAT_MESSAGE_LENGTH + 10);
+        assert_eq!(
+            ChatMessage::sanitize(&long_message).map(|message| message.len()),
+            Some(MAX_CHAT_MESSAGE_LENGTH)
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use bevy::math::{IVec3, Vec3};
+
+use super::{BlockId, ChunkManager};
+
+/// Distance from which players can break and place blocks.
+pub const MAX_REACH: f32 = 8.0;
+
+/// Height of the player camera above the feet.
+pub const PLAYER_EYE_HEIGHT: f32 = 1.6;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RaycastHit {
+    /// Minimum corner of the block that was hit.
+    pub position: Vec3,
+    /// Normal of the face the ray entered through, zero if the ray started inside the block.
+    pub normal: Vec3,
+    pub distance: f32,
+    pub block: BlockId,
+}
+
+/// Walks the blocks along the ray (Amanatides & Woo DDA) and returns the first block that is not
+/// air. Blocks of unloaded chunks are skipped.
+pub fn raycast_blocks(
+    chunk_manager: &ChunkManager,
+    origin: Vec3,
+    direction: Vec3,
+    max_distance: f32,
+) -> Option<RaycastHit> {
+    let direction = direction.normalize_or_zero();
+    if direction == Vec3::ZERO {
+        return None;
+    }
+
+    let step = IVec3::from_array(direction.to_array().map(|value| match value {
+        value if value > 0.0 => 1,
+        value if value < 0.0 => -1,
+        _ => 0,
+    }));
+    let mut block = origin.floor().as_ivec3();
+
+    let t_delta = direction.recip().abs();
+    let mut t_max = Vec3::ZERO;
+    for axis in 0..3 {
+        t_max[axis] = match step[axis] {
+            1 => (block[axis] as f32 + 1.0 - origin[axis]) / direction[axis],
+            -1 => (origin[axis] - block[axis] as f32) / -direction[axis],
+            _ => f32::INFINITY,
+        };
+    }
+
+    let mut distance = 0.0;
+    let mut normal = Vec3::ZERO;
+
+    loop {
+        if let Some(hit_block) = chunk_manager.get_block(block.as_vec3()) {
+            if hit_block != BlockId::Air {
+                return Some(RaycastHit {
+                    position: block.as_vec3(),
+                    normal,
+                    distance,
+                    block: hit_block,
+                });
+            }
+        }
+
+        let axis = match (t_max.x < t_max.y, t_max.x < t_max.z, t_max.y < t_max.z) {
+            (true, true, _) => 0,
+            (false, _, true) => 1,
+            _ => 2,
+        };
+
+        distance = t_max[axis];
+        if distance > max_distance {
+            return None;
+        }
+
+        block[axis] += step[axis];
+        t_max[axis] += t_delta[axis];
+        normal = Vec3::ZERO;
+        normal[axis] = -step[axis] as f32;
+    }
+}
+
+/// Whether a player standing at `position` can reach the block, with `tolerance` added to
+/// [`MAX_REACH`] to account for movement during latency.
+pub fn is_block_in_reach(player_position: Vec3, block_position: Vec3, tolerance: f32) -> bool {
+    let eye = player_position + Vec3::Y * PLAYER_EYE_HEIGHT;
+    let closest_point = eye.clamp(block_position, block_position + Vec3::ONE);
+
+    eye.distance(closest_point) <= MAX_REACH + tolerance
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Chunk;
+
+    fn chunk_manager_with_blocks(blocks: &[(Vec3, BlockId)]) -> ChunkManager {
+        let mut chunk_manager = ChunkManager::new();
+        chunk_manager.insert_chunk(Chunk::new(Vec3::ZERO));
+
+        for (position, block) in blocks {
+            chunk_manager.update_block(*position, *block);
+        }
+
+        chunk_manager
+    }
+
+    #[test]
+    fn test_raycast_hits_block_face() {
+        let chunk_manager =
+            chunk_manager_with_blocks(&[(Vec3::new(5.0, 2.0, 2.0), BlockId::Stone)]);
+
+        let hit = raycast_blocks(&chunk_manager, Vec3::new(1.5, 2.5, 2.5), Vec3::X, 10.0).unwrap();
+
+        assert_eq!(hit.position, Vec3::new(5.0, 2.0, 2.0));
+        assert_eq!(hit.normal, Vec3::NEG_X);
+        assert!((hit.distance - 3.5).abs() < 1e-5);
+        assert_eq!(hit.block, BlockId::Stone);
+    }
+
+    #[test]
+    fn test_raycast_diagonal_and_negative_directions() {
+        let chunk_manager =
+            chunk_manager_with_blocks(&[(Vec3::new(2.0, 2.0, 2.0), BlockId::Tallgrass)]);
+
+        let hit = raycast_blocks(
+            &chunk_manager,
+            Vec3::new(5.5, 5.5, 5.5),
+            Vec3::new(-1.0, -1.0, -1.0),
+            10.0,
+        )
+        .unwrap();
+
+        assert_eq!(hit.position, Vec3::new(2.0, 2.0, 2.0));
+        assert_eq!(hit.block, BlockId::Tallgrass);
+        assert_eq!(hit.normal.length(), 1.0);
+    }
+
+    #[test]
+    fn test_raycast_respects_max_distance() {
+        let chunk_manager =
+            chunk_manager_with_blocks(&[(Vec3::new(10.0, 2.0, 2.0), BlockId::Stone)]);
+
+        assert!(raycast_blocks(&chunk_manager, Vec3::new(1.5, 2.5, 2.5), Vec3::X, 5.0).is_none());
+        assert!(
+            raycast_blocks(&chunk_manager, Vec3::new(1.5, 2.5, 2.5), Vec3::ZERO, 5.0).is_none()
+        );
+    }
+
+    #[test]
+    fn test_block_in_reach() {
+        assert!(is_block_in_reach(Vec3::ZERO, Vec3::new(5.0, 0.0, 0.0), 0.0));
+        assert!(!is_block_in_reach(
+            Vec3::ZERO,
+            Vec3::new(20.0, 0.0, 0.0),
+            0.0
+        ));
+        assert!(is_block_in_reach(Vec3::ZERO, Vec3::new(9.0, 0.0, 0.0), 2.0));
+    }
+}
@@ -0,0 +1,162 @@
+use std::any::type_name;
+use std::collections::HashMap;
+
+use bevy::ecs::component::Component;
+use bevy::ecs::system::Resource;
+use bevy::math::{Quat, Vec3};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Marks server entities that are sent to the clients.
+#[derive(Component, Default, Clone, Copy, Debug)]
+pub struct Replicated;
+
+/// Id of a replicated entity, the same on the server and on every client.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NetworkId(pub u64);
+
+/// Component that is sent to the clients whenever it changes on a replicated entity.
+///
+/// Removing a replicated component is not replicated, despawn the entity instead.
+pub trait ReplicatedComponent: Component + Serialize + DeserializeOwned + Clone {
+    /// Identifies the component type in messages, unique per component.
+    const KIND: u16;
+}
+
+/// Serialized replicated component.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ComponentData {
+    pub kind: u16,
+    pub data: Vec<u8>,
+}
+
+impl ComponentData {
+    pub fn encode<C: ReplicatedComponent>(component: &C) -> Self {
+        Self {
+            kind: C::KIND,
+            data: bincode::serialize(component).unwrap(),
+        }
+    }
+
+    /// Returns `None` for other component kinds and malformed data.
+    pub fn decode<C: ReplicatedComponent>(&self) -> Option<C> {
+        if self.kind != C::KIND {
+            return None;
+        }
+
+        bincode::deserialize(&self.data).ok()
+    }
+}
+
+/// Components of one replicated entity.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct EntityUpdate {
+    pub id: NetworkId,
+    pub components: Vec<ComponentData>,
+}
+
+impl EntityUpdate {
+    /// Replaces an earlier component of the same kind.
+    pub fn insert(&mut self, component: ComponentData) {
+        match self
+            .components
+            .iter_mut()
+            .find(|existing| existing.kind == component.kind)
+        {
+            Some(existing) => *existing = component,
+            None => self.components.push(component),
+        }
+    }
+}
+
+/// Component types registered for replication on this side of the connection.
+#[derive(Resource, Default)]
+pub struct ReplicationRegistry {
+    kinds: HashMap<u16, &'static str>,
+}
+
+impl ReplicationRegistry {
+    /// Panics when two component types use the same kind.
+    pub fn register<C: ReplicatedComponent>(&mut self) {
+        if let Some(existing) = self.kinds.insert(C::KIND, type_name::<C>()) {
+            assert_eq!(
+                existing,
+                type_name::<C>(),
+                "replicated component kind {} is used twice",
+                C::KIND
+            );
+        }
+    }
+
+    pub fn is_registered(&self, kind: u16) -> bool {
+        self.kinds.contains_key(&kind)
+    }
+}
+
+/// Position of a replicated entity, for entities with a body it is the bottom center.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct NetworkPosition(pub Vec3);
+
+impl ReplicatedComponent for NetworkPosition {
+    const KIND: u16 = 0;
+}
+
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct NetworkRotation(pub Quat);
+
+impl ReplicatedComponent for NetworkRotation {
+    const KIND: u16 = 2;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq)]
+    struct Health(u32);
+
+    impl ReplicatedComponent for Health {
+        const KIND: u16 = 100;
+    }
+
+    #[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq)]
+    struct Armor(u32);
+
+    impl ReplicatedComponent for Armor {
+        const KIND: u16 = 100;
+    }
+
+    #[test]
+    fn test_component_data_round_trip() {
+        let data = ComponentData::encode(&NetworkPosition(Vec3::new(1.0, 2.0, 3.0)));
+
+        assert_eq!(
+            data.decode::<NetworkPosition>(),
+            Some(NetworkPosition(Vec3::new(1.0, 2.0, 3.0)))
+        );
+        assert_eq!(data.decode::<Health>(), None);
+    }
+
+    #[test]
+    fn test_entity_update_keeps_latest_component() {
+        let mut update = EntityUpdate {
+            id: NetworkId(1),
+            components: Vec::new(),
+        };
+        update.insert(ComponentData::encode(&Health(10)));
+        update.insert(ComponentData::encode(&NetworkPosition(Vec3::ZERO)));
+        update.insert(ComponentData::encode(&Health(5)));
+
+        assert_eq!(update.components.len(), 2);
+        assert_eq!(update.components[0].decode::<Health>(), Some(Health(5)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_registering_duplicate_kinds_panics() {
+        let mut registry = ReplicationRegistry::default();
+        registry.register::<Health>();
+        registry.register::<Health>();
+        registry.register::<Armor>();
+    }
+}
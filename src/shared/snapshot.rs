@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+
+use bevy::math::{Quat, Vec3};
+use renet::ClientId;
+use serde::{Deserialize, Serialize};
+
+use super::PlayerState;
+
+/// Positions are sent in fixed point with this many steps per block.
+pub const POSITION_SCALE: f32 = 64.0;
+const ROTATION_SCALE: f32 = i16::MAX as f32;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QuantizedPlayerState {
+    pub position: [i32; 3],
+    pub rotation: [i16; 4],
+}
+
+impl From<&PlayerState> for QuantizedPlayerState {
+    fn from(state: &PlayerState) -> Self {
+        let position = (state.position * POSITION_SCALE).round().as_ivec3();
+        let rotation = state.rotation.normalize().to_array();
+
+        Self {
+            position: position.to_array(),
+            rotation: rotation.map(|value| (value * ROTATION_SCALE).round() as i16),
+        }
+    }
+}
+
+impl From<&QuantizedPlayerState> for PlayerState {
+    fn from(state: &QuantizedPlayerState) -> Self {
+        let [x, y, z] = state.position;
+        let [qx, qy, qz, qw] = state.rotation.map(|value| value as f32 / ROTATION_SCALE);
+
+        PlayerState {
+            position: Vec3::new(x as f32, y as f32, z as f32) / POSITION_SCALE,
+            rotation: Quat::from_xyzw(qx, qy, qz, qw).normalize(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PositionDelta {
+    /// Offset from the baseline position, used while the player moved less than `i16` steps.
+    Offset([i16; 3]),
+    Absolute([i32; 3]),
+}
+
+/// Fields of a player that changed since the baseline, unchanged fields are `None`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlayerStateDelta {
+    pub position: Option<PositionDelta>,
+    pub rotation: Option<[i16; 4]>,
+}
+
+impl PlayerStateDelta {
+    /// Returns `None` when the state equals the baseline.
+    pub fn encode(
+        baseline: Option<&QuantizedPlayerState>,
+        current: &QuantizedPlayerState,
+    ) -> Option<Self> {
+        let Some(baseline) = baseline else {
+            return Some(Self {
+                position: Some(PositionDelta::Absolute(current.position)),
+                rotation: Some(current.rotation),
+            });
+        };
+
+        if baseline == current {
+            return None;
+        }
+
+        let position = match baseline.position == current.position {
+            true => None,
+            false => {
+                let offset: Option<Vec<i16>> = (0..3)
+                    .map(|axis| {
+                        i16::try_from(current.position[axis] - baseline.position[axis]).ok()
+                    })
+                    .collect();
+
+                Some(match offset {
+                    Some(offset) => PositionDelta::Offset([offset[0], offset[1], offset[2]]),
+                    None => PositionDelta::Absolute(current.position),
+                })
+            }
+        };
+
+        let rotation = match baseline.rotation == current.rotation {
+            true => None,
+            false => Some(current.rotation),
+        };
+
+        Some(Self { position, rotation })
+    }
+
+    /// Returns `None` when the delta needs a baseline that is missing.
+    pub fn apply(&self, baseline: Option<&QuantizedPlayerState>) -> Option<QuantizedPlayerState> {
+        let position = match (self.position, baseline) {
+            (Some(PositionDelta::Absolute(position)), _) => position,
+            (Some(PositionDelta::Offset(offset)), Some(baseline)) => {
+                [0, 1, 2].map(|axis| baseline.position[axis] + offset[axis] as i32)
+            }
+            (None, Some(baseline)) => baseline.position,
+            _ => return None,
+        };
+
+        let rotation = match (self.rotation, baseline) {
+            (Some(rotation), _) => rotation,
+            (None, Some(baseline)) => baseline.rotation,
+            (None, None) => return None,
+        };
+
+        Some(QuantizedPlayerState { position, rotation })
+    }
+}
+
+pub type PlayerSnapshotState = HashMap<ClientId, QuantizedPlayerState>;
+
+/// The players visible to one client at a server tick, delta encoded against a snapshot
+/// the client acknowledged.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PlayerSnapshot {
+    pub tick: u32,
+    /// Milliseconds since the server started, used to place the states on a timeline.
+    pub time: u32,
+    /// Tick of the snapshot the deltas are relative to, `None` when every player is sent in full.
+    pub baseline_tick: Option<u32>,
+    pub players: Vec<(ClientId, PlayerStateDelta)>,
+    /// Players of the baseline that are no longer visible.
+    pub removed: Vec<ClientId>,
+}
+
+impl PlayerSnapshot {
+    pub fn encode(
+        tick: u32,
+        time: u32,
+        baseline: Option<(u32, &PlayerSnapshotState)>,
+        current: &PlayerSnapshotState,
+    ) -> Self {
+        let baseline_state = baseline.map(|(_, state)| state);
+
+        let players = current
+            .iter()
+            .filter_map(|(client_id, state)| {
+                let baseline_player = baseline_state.and_then(|players| players.get(client_id));
+                PlayerStateDelta::encode(baseline_player, state).map(|delta| (*client_id, delta))
+            })
+            .collect();
+
+        let removed = baseline_state
+            .map(|players| {
+                players
+                    .keys()
+                    .filter(|client_id| !current.contains_key(client_id))
+                    .copied()
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            tick,
+            time,
+            baseline_tick: baseline.map(|(tick, _)| tick),
+            players,
+            removed,
+        }
+    }
+
+    /// Rebuilds the full state, `baseline` has to be the state of `baseline_tick`.
+    pub fn decode(&self, baseline: Option<&PlayerSnapshotState>) -> Option<PlayerSnapshotState> {
+        let mut players = match (self.baseline_tick, baseline) {
+            (Some(_), Some(baseline)) => baseline.clone(),
+            (Some(_), None) => return None,
+            (None, _) => HashMap::new(),
+        };
+
+        for client_id in self.removed.iter() {
+            players.remove(client_id);
+        }
+
+        for (client_id, delta) in self.players.iter() {
+            let state = delta.apply(players.get(client_id))?;
+            players.insert(*client_id, state);
+        }
+
+        Some(players)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(x: f32, yaw: f32) -> QuantizedPlayerState {
+        QuantizedPlayerState::from(&PlayerState {
+            position: Vec3::new(x, 10.0, -4.0),
+            rotation: Quat::from_rotation_y(yaw),
+        })
+    }
+
+    #[test]
+    fn test_quantization_roundtrip() {
+        let state = PlayerState {
+            position: Vec3::new(12.3, -45.6, 789.01),
+            rotation: Quat::from_rotation_y(1.2) * Quat::from_rotation_x(-0.4),
+        };
+
+        let restored = PlayerState::from(&QuantizedPlayerState::from(&state));
+
+        assert!(state.position.distance(restored.position) <= 1.0 / POSITION_SCALE);
+        assert!(state.rotation.angle_between(restored.rotation) < 0.001);
+    }
+
+    #[test]
+    fn test_delta_roundtrip() {
+        let baseline = player(0.0, 0.0);
+
+        for current in [player(0.0, 0.0), player(3.5, 0.0), player(5000.0, 1.0)] {
+            let decoded = match PlayerStateDelta::encode(Some(&baseline), &current) {
+                Some(delta) => delta.apply(Some(&baseline)).unwrap(),
+                None => baseline,
+            };
+            assert_eq!(decoded, current);
+        }
+    }
+
+    #[test]
+    fn test_delta_uses_offsets_for_small_moves() {
+        let delta = PlayerStateDelta::encode(Some(&player(0.0, 0.0)), &player(1.0, 0.0)).unwrap();
+
+        assert_eq!(delta.position, Some(PositionDelta::Offset([64, 0, 0])));
+        assert_eq!(delta.rotation, None);
+    }
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        let baseline: PlayerSnapshotState = [(1, player(0.0, 0.0)), (2, player(4.0, 0.5))].into();
+        let current: PlayerSnapshotState = [(1, player(0.0, 0.0)), (3, player(-8.0, 2.0))].into();
+
+        let snapshot = PlayerSnapshot::encode(7, 350, Some((5, &baseline)), &current);
+
+        assert_eq!(snapshot.baseline_tick, Some(5));
+        assert_eq!(snapshot.removed, vec![2]);
+        assert_eq!(snapshot.players.len(), 1);
+        assert_eq!(snapshot.decode(Some(&baseline)), Some(current.clone()));
+        assert_eq!(snapshot.decode(None), None);
+
+        let full_snapshot = PlayerSnapshot::encode(7, 350, None, &current);
+        assert_eq!(full_snapshot.decode(None), Some(current));
+    }
+}
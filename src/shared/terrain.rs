@@ -187,8 +187,8 @@ impl ChunkManager {
         }
     }
 
-    pub fn get_block(&mut self, position: Vec3) -> Option<BlockId> {
-        match self.chunk_from_selection(position) {
+    pub fn get_block(&self, position: Vec3) -> Option<BlockId> {
+        match self.get_chunk(position / CHUNK_SIZE as f32) {
             Some(chunk) => {
                 let chunk_position = Vec3::new(
                     chunk.position[0] * CHUNK_SIZE as f32,
@@ -0,0 +1,268 @@
+//! Runs a server and headless clients in one process, connected through the memory transport.
+
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use bevy::app::{App, Update};
+use bevy::ecs::system::{ResMut, Resource};
+use bevy::math::{Quat, Vec3};
+use bevy::MinimalPlugins;
+use bevy_renet::RenetClientPlugin;
+use renet::{ClientId, DefaultChannel, RenetClient};
+
+use rsmc::server::player::resources::{PlayerMovements, PlayerStates};
+use rsmc::server::settings::{Operator, ServerSettings};
+use rsmc::server::ServerPlugins;
+use rsmc::*;
+
+/// Upper bound of lockstep updates a test waits for a condition before failing.
+pub const MAX_STEPS: usize = 200;
+
+/// Real time between steps, the server sends snapshots based on elapsed time.
+pub const STEP_DURATION: Duration = Duration::from_millis(5);
+
+/// Every message a test client received, in order of arrival.
+#[derive(Resource, Default)]
+pub struct ReceivedMessages(pub Vec<NetworkingMessage>);
+
+pub struct TestClient {
+    pub client_id: ClientId,
+    pub app: App,
+}
+
+impl TestClient {
+    pub fn send(&mut self, channel: DefaultChannel, message: &NetworkingMessage) {
+        self.app
+            .world_mut()
+            .resource_mut::<RenetClient>()
+            .send_message(channel, bincode::serialize(message).unwrap());
+    }
+
+    pub fn received(&self) -> &[NetworkingMessage] {
+        &self.app.world().resource::<ReceivedMessages>().0
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.app.world().resource::<RenetClient>().is_connected()
+    }
+
+    pub fn clear_received(&mut self) {
+        self.app
+            .world_mut()
+            .resource_mut::<ReceivedMessages>()
+            .0
+            .clear();
+    }
+}
+
+pub struct TestHarness {
+    pub server: App,
+    pub clients: Vec<TestClient>,
+    /// Identity of every name that joined, so a returning name is the same player.
+    identities: HashMap<String, PlayerIdentity>,
+    world_directory: PathBuf,
+}
+
+impl TestHarness {
+    pub fn new() -> Self {
+        let world_directory =
+            std::env::temp_dir().join(format!("rsmc-test-{}", rand::random::<u64>()));
+
+        // The first client is an operator
+        let operator = Operator {
+            name: "client0".to_string(),
+            identity: PlayerIdentity::random(),
+        };
+        let identities = HashMap::from([(operator.name.clone(), operator.identity)]);
+
+        let mut server = App::new();
+        server.add_plugins(MinimalPlugins);
+        server.insert_resource(ServerSettings {
+            world_directory: world_directory.clone(),
+            view_distance: 1,
+            snapshot_rate: ServerSettings::default().tick_rate,
+            max_mobs: 0,
+            operators: vec![operator],
+            ..ServerSettings::default()
+        });
+        server.insert_resource(MemoryServerTransport::default());
+        server.add_plugins(ServerPlugins);
+        server.finish();
+        server.cleanup();
+
+        Self {
+            server,
+            clients: Vec::new(),
+            identities,
+            world_directory,
+        }
+    }
+
+    /// Adds a client and returns its index, the connection is established on the next steps.
+    pub fn connect_client(&mut self) -> usize {
+        let name = format!("client{}", self.clients.len());
+        self.connect_client_as(&name)
+    }
+
+    /// Adds a client joining with the given player name.
+    pub fn connect_client_as(&mut self, name: &str) -> usize {
+        let identity = *self
+            .identities
+            .entry(name.to_string())
+            .or_insert_with(PlayerIdentity::random);
+        self.connect_client_with_identity(name, identity)
+    }
+
+    /// Adds a client joining with the given name and identity, e.g. to impersonate a player.
+    pub fn connect_client_with_identity(&mut self, name: &str, identity: PlayerIdentity) -> usize {
+        let (client_connection, server_connection) = memory_connection_pair();
+        let client_id: ClientId = rand::random();
+
+        self.server
+            .world_mut()
+            .resource_mut::<MemoryServerTransport>()
+            .connect(client_id, server_connection);
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins((RenetClientPlugin, MemoryClientTransportPlugin));
+        app.insert_resource(RenetClient::new(connection_config()));
+        app.insert_resource(MemoryClientTransport::new(client_connection));
+        app.insert_resource(ReceivedMessages::default());
+        app.add_systems(Update, receive_messages_system);
+        app.finish();
+        app.cleanup();
+
+        let index = self.clients.len();
+        let mut client = TestClient { client_id, app };
+        client.send(
+            DefaultChannel::ReliableOrdered,
+            &NetworkingMessage::JoinGame(
+                PlayerProfile {
+                    name: name.to_string(),
+                    ..PlayerProfile::default()
+                },
+                identity,
+            ),
+        );
+
+        self.clients.push(client);
+        index
+    }
+
+    /// Moves the player of a client on the server once it spawned, skipping movement
+    /// validation. Movement sent afterwards is validated from this position.
+    pub fn place_player(&mut self, index: usize, position: Vec3) {
+        assert!(
+            self.step_until(|harness| {
+                harness.clients[index]
+                    .received()
+                    .iter()
+                    .any(|message| matches!(message, NetworkingMessage::PlayerSpawn { .. }))
+            }),
+            "client {} did not spawn",
+            index
+        );
+
+        let client_id = self.clients[index].client_id;
+        self.server
+            .world_mut()
+            .resource_mut::<PlayerStates>()
+            .players
+            .insert(
+                client_id,
+                PlayerState {
+                    position,
+                    rotation: Quat::IDENTITY,
+                },
+            );
+        self.server
+            .world_mut()
+            .resource_mut::<PlayerMovements>()
+            .place(client_id);
+    }
+
+    /// Sets a block on the server directly, e.g. to clear the generated terrain before a
+    /// block is placed there.
+    pub fn set_block(&mut self, position: Vec3, block: BlockId) {
+        self.server
+            .world_mut()
+            .resource_mut::<ChunkManager>()
+            .update_block(position, block);
+    }
+
+    pub fn disconnect_client(&mut self, index: usize) {
+        self.clients[index]
+            .app
+            .world_mut()
+            .resource_mut::<MemoryClientTransport>()
+            .disconnect();
+    }
+
+    /// Updates the server and then every client once.
+    pub fn step(&mut self) {
+        std::thread::sleep(STEP_DURATION);
+        self.server.update();
+        for client in self.clients.iter_mut() {
+            client.app.update();
+        }
+    }
+
+    /// Steps until the condition holds, returns false if it did not within `MAX_STEPS`.
+    pub fn step_until(&mut self, mut condition: impl FnMut(&Self) -> bool) -> bool {
+        for _ in 0..MAX_STEPS {
+            if condition(self) {
+                return true;
+            }
+            self.step();
+        }
+
+        condition(self)
+    }
+
+    /// Connects a client and steps until it has received its first message from the server.
+    pub fn connect_and_wait(&mut self) -> usize {
+        let index = self.connect_client();
+        assert!(
+            self.step_until(|harness| {
+                let client = &harness.clients[index];
+                client.is_connected() && !client.received().is_empty()
+            }),
+            "client {} did not connect",
+            index
+        );
+        index
+    }
+}
+
+impl Default for TestHarness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for TestHarness {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.world_directory);
+    }
+}
+
+fn receive_messages_system(
+    mut client: ResMut<RenetClient>,
+    mut received_messages: ResMut<ReceivedMessages>,
+) {
+    for channel in [
+        DefaultChannel::ReliableOrdered,
+        DefaultChannel::ReliableUnordered,
+        DefaultChannel::Unreliable,
+    ] {
+        while let Some(message) = client.receive_message(channel) {
+            received_messages
+                .0
+                .push(bincode::deserialize(&message).unwrap());
+        }
+    }
+}
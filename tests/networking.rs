@@ -0,0 +1,723 @@
+mod common;
+
+use bevy::math::{Quat, Vec3};
+use renet::{ClientId, DefaultChannel, RenetClient};
+use rsmc::*;
+
+use rsmc::server::player::resources::{
+    PlayerGameModes, PlayerHealths, PlayerInventories, PlayerStates,
+};
+use rsmc::server::settings::ServerSettings;
+
+use common::TestHarness;
+
+#[test]
+fn test_client_receives_requested_chunks() {
+    let mut harness = TestHarness::new();
+    let client = harness.connect_and_wait();
+    assert!(harness.step_until(|harness| {
+        harness.clients[client]
+            .received()
+            .iter()
+            .any(|message| matches!(message, NetworkingMessage::PlayerSpawn { .. }))
+    }));
+
+    // The spawn point is in chunk (0, 2, 0) and the test server only sees one chunk around it
+    let positions = vec![Vec3::new(0.0, 2.0, 0.0), Vec3::new(1.0, 1.0, -1.0)];
+    let mut requested = positions.clone();
+    requested.push(Vec3::new(4.0, 0.0, -3.0));
+    harness.clients[client].send(
+        DefaultChannel::ReliableUnordered,
+        &NetworkingMessage::ChunkBatchRequest(requested),
+    );
+
+    assert!(harness.step_until(|harness| {
+        harness.clients[client].received().iter().any(|message| {
+            matches!(message, NetworkingMessage::ChunkBatchResponse(chunks)
+                if chunks.iter().map(|chunk| chunk.position).collect::<Vec<_>>() == positions)
+        })
+    }));
+}
+
+/// Puts items into the selected hotbar slot of a client once the server created its inventory.
+fn give_selected_item(harness: &mut TestHarness, client: usize, item: ItemId, count: u32) {
+    assert!(harness.step_until(|harness| {
+        harness.clients[client]
+            .received()
+            .iter()
+            .any(|message| matches!(message, NetworkingMessage::InventorySync(_)))
+    }));
+
+    let client_id = harness.clients[client].client_id;
+    let mut inventories = harness
+        .server
+        .world_mut()
+        .resource_mut::<PlayerInventories>();
+    let inventory = inventories.inventories.get_mut(&client_id).unwrap();
+    inventory.slots[inventory.selected] = Some(ItemStack { item, count });
+}
+
+/// Overrides the game mode of a client after the server assigned the default one.
+fn set_game_mode(harness: &mut TestHarness, client: usize, game_mode: GameMode) {
+    let client_id = harness.clients[client].client_id;
+    assert!(harness.step_until(|harness| {
+        harness
+            .server
+            .world()
+            .resource::<PlayerGameModes>()
+            .players
+            .contains_key(&client_id)
+    }));
+
+    harness
+        .server
+        .world_mut()
+        .resource_mut::<PlayerGameModes>()
+        .players
+        .insert(client_id, game_mode);
+}
+
+#[test]
+fn test_block_updates_propagate_to_other_clients() {
+    let mut harness = TestHarness::new();
+    let sender = harness.connect_and_wait();
+    let receiver = harness.connect_and_wait();
+
+    let position = Vec3::new(3.0, 10.0, 5.0);
+    give_selected_item(&mut harness, sender, ItemId::Block(BlockId::Stone), 1);
+    harness.place_player(sender, position + Vec3::new(0.0, 2.0, 2.0));
+    harness.set_block(position, BlockId::Air);
+    harness.clients[sender].send(
+        DefaultChannel::ReliableOrdered,
+        &NetworkingMessage::BlockUpdate {
+            position,
+            block: BlockId::Stone,
+        },
+    );
+
+    assert!(harness.step_until(|harness| {
+        harness.clients[receiver].received().iter().any(|message| {
+            matches!(message, NetworkingMessage::BlockUpdate { position: p, block: BlockId::Stone }
+                if *p == position)
+        })
+    }));
+
+    let server_block = harness
+        .server
+        .world_mut()
+        .resource_mut::<ChunkManager>()
+        .get_block(position);
+    assert_eq!(server_block, Some(BlockId::Stone));
+
+    // The sender already applied the update locally and must not get it echoed back
+    assert!(!harness.clients[sender]
+        .received()
+        .iter()
+        .any(|message| matches!(message, NetworkingMessage::BlockUpdate { .. })));
+
+    // Survival players use up the placed block
+    assert!(harness.step_until(|harness| {
+        harness.clients[sender].received().iter().any(|message| {
+            matches!(message, NetworkingMessage::InventorySync(inventory)
+                if inventory.selected_item().is_none())
+        })
+    }));
+}
+
+#[test]
+fn test_spectators_cannot_change_blocks() {
+    let mut harness = TestHarness::new();
+    let sender = harness.connect_and_wait();
+
+    let position = Vec3::new(3.0, 10.0, 5.0);
+    give_selected_item(&mut harness, sender, ItemId::Block(BlockId::Stone), 1);
+    set_game_mode(&mut harness, sender, GameMode::Spectator);
+    harness.place_player(sender, position + Vec3::new(0.0, 2.0, 2.0));
+    let original_block = harness
+        .server
+        .world_mut()
+        .resource_mut::<ChunkManager>()
+        .get_block(position)
+        .unwrap();
+
+    harness.clients[sender].send(
+        DefaultChannel::ReliableOrdered,
+        &NetworkingMessage::BlockUpdate {
+            position,
+            block: BlockId::Stone,
+        },
+    );
+
+    assert!(harness.step_until(|harness| {
+        harness.clients[sender].received().iter().any(|message| {
+            matches!(message, NetworkingMessage::BlockUpdate { position: p, block }
+                if *p == position && *block == original_block)
+        })
+    }));
+}
+
+#[test]
+fn test_block_updates_out_of_reach_are_reverted() {
+    let mut harness = TestHarness::new();
+    let sender = harness.connect_and_wait();
+    let receiver = harness.connect_and_wait();
+
+    let position = Vec3::new(3.0, 10.0, 5.0);
+    harness.place_player(sender, position + Vec3::new(30.0, 0.0, 0.0));
+    let original_block = harness
+        .server
+        .world_mut()
+        .resource_mut::<ChunkManager>()
+        .get_block(position)
+        .unwrap();
+
+    harness.clients[sender].send(
+        DefaultChannel::ReliableOrdered,
+        &NetworkingMessage::BlockUpdate {
+            position,
+            block: BlockId::CobbleStone,
+        },
+    );
+
+    assert!(harness.step_until(|harness| {
+        harness.clients[sender].received().iter().any(|message| {
+            matches!(message, NetworkingMessage::BlockUpdate { position: p, block }
+                if *p == position && *block == original_block)
+        })
+    }));
+    assert!(!harness.clients[receiver]
+        .received()
+        .iter()
+        .any(|message| matches!(message, NetworkingMessage::BlockUpdate { .. })));
+}
+
+#[test]
+fn test_late_joining_client_receives_past_block_updates() {
+    let mut harness = TestHarness::new();
+    let sender = harness.connect_and_wait();
+
+    let position = Vec3::new(1.0, 12.0, 1.0);
+    harness.place_player(sender, position + Vec3::new(0.0, 2.0, 2.0));
+    harness.set_block(position, BlockId::Air);
+    harness.clients[sender].send(
+        DefaultChannel::ReliableOrdered,
+        &NetworkingMessage::BlockUpdate {
+            position,
+            block: BlockId::Dirt,
+        },
+    );
+    for _ in 0..5 {
+        harness.step();
+    }
+
+    let late_client = harness.connect_client();
+    assert!(harness.step_until(|harness| {
+        harness.clients[late_client].received().iter().any(|message| {
+            matches!(message, NetworkingMessage::BlockUpdate { position: p, block: BlockId::Dirt }
+                if *p == position)
+        })
+    }));
+}
+
+#[test]
+fn test_join_and_leave_are_broadcast() {
+    let mut harness = TestHarness::new();
+    let observer = harness.connect_and_wait();
+    let player = harness.connect_and_wait();
+    let player_id = harness.clients[player].client_id;
+
+    assert!(harness.step_until(|harness| {
+        harness.clients[observer].received().iter().any(|message| {
+            matches!(message, NetworkingMessage::PlayerJoin(id, profile)
+                if *id == player_id && profile.name == "client1")
+        })
+    }));
+
+    // Players joining later learn about everyone already connected
+    let observer_id = harness.clients[observer].client_id;
+    assert!(harness.step_until(|harness| {
+        harness.clients[player].received().iter().any(|message| {
+            matches!(message, NetworkingMessage::PlayerJoin(id, profile)
+                if *id == observer_id && profile.name == "client0")
+        })
+    }));
+
+    harness.disconnect_client(player);
+
+    assert!(harness.step_until(|harness| {
+        harness.clients[observer].received().iter().any(
+            |message| matches!(message, NetworkingMessage::PlayerLeave(id) if *id == player_id),
+        )
+    }));
+}
+
+#[test]
+fn test_refused_clients_are_not_announced_leaving() {
+    let mut harness = TestHarness::new();
+    let observer = harness.connect_and_wait();
+    let duplicate = harness.connect_client_as("client0");
+    let duplicate_id = harness.clients[duplicate].client_id;
+
+    assert!(harness.step_until(|harness| {
+        harness.clients[duplicate]
+            .app
+            .world()
+            .resource::<RenetClient>()
+            .is_disconnected()
+    }));
+    for _ in 0..10 {
+        harness.step();
+    }
+
+    assert!(!harness.clients[observer].received().iter().any(|message| {
+        matches!(message, NetworkingMessage::PlayerJoin(id, _) | NetworkingMessage::PlayerLeave(id)
+            if *id == duplicate_id)
+    }));
+}
+
+#[test]
+fn test_clients_sending_malformed_messages_are_disconnected() {
+    let mut harness = TestHarness::new();
+    let client = harness.connect_and_wait();
+
+    harness.clients[client]
+        .app
+        .world_mut()
+        .resource_mut::<RenetClient>()
+        .send_message(DefaultChannel::ReliableOrdered, vec![0xff; 3]);
+
+    assert!(harness.step_until(|harness| !harness.clients[client].is_connected()));
+}
+
+/// State of the player in a snapshot, test clients never acknowledge so snapshots are not deltas.
+fn snapshot_player(message: &NetworkingMessage, client_id: ClientId) -> Option<PlayerState> {
+    match message {
+        NetworkingMessage::PlayerSnapshot(snapshot) => snapshot
+            .decode(None)
+            .and_then(|players| players.get(&client_id).map(PlayerState::from)),
+        _ => None,
+    }
+}
+
+fn send_player_movement(
+    harness: &mut TestHarness,
+    client: usize,
+    sequence: u32,
+    position: Vec3,
+    displacement: Vec3,
+) {
+    harness.clients[client].send(
+        DefaultChannel::ReliableOrdered,
+        &NetworkingMessage::PlayerUpdate {
+            input: MovementInput {
+                sequence,
+                displacement,
+            },
+            state: PlayerState {
+                position: position + displacement,
+                rotation: Quat::IDENTITY,
+            },
+        },
+    );
+}
+
+#[test]
+fn test_player_updates_are_synced() {
+    let mut harness = TestHarness::new();
+    let observer = harness.connect_and_wait();
+    let player = harness.connect_and_wait();
+    let player_id = harness.clients[player].client_id;
+
+    let position = Vec3::new(8.0, 200.0, -2.0);
+    harness.place_player(player, position);
+
+    assert!(harness.step_until(|harness| {
+        harness.clients[observer].received().iter().any(|message| {
+            snapshot_player(message, player_id).is_some_and(|state| state.position == position)
+        })
+    }));
+}
+
+#[test]
+fn test_players_outside_view_distance_are_not_synced() {
+    let mut harness = TestHarness::new();
+    let observer = harness.connect_and_wait();
+    let player = harness.connect_and_wait();
+    let player_id = harness.clients[player].client_id;
+
+    harness.place_player(player, Vec3::new(1000.0, 200.0, 0.0));
+    for _ in 0..20 {
+        harness.step();
+    }
+    harness.clients[observer].clear_received();
+
+    assert!(harness.step_until(|harness| {
+        harness.clients[observer]
+            .received()
+            .iter()
+            .any(|message| matches!(message, NetworkingMessage::PlayerSnapshot(_)))
+    }));
+    assert!(!harness.clients[observer]
+        .received()
+        .iter()
+        .any(|message| snapshot_player(message, player_id).is_some()));
+}
+
+#[test]
+fn test_teleporting_players_are_corrected() {
+    let mut harness = TestHarness::new();
+    let player = harness.connect_and_wait();
+
+    let position = Vec3::new(8.0, 200.0, -2.0);
+    harness.place_player(player, position);
+
+    send_player_movement(&mut harness, player, 1, position, Vec3::X * 100.0);
+
+    assert!(harness.step_until(|harness| {
+        harness.clients[player].received().iter().any(|message| {
+            matches!(message, NetworkingMessage::PlayerPositionCorrection(corrected)
+                if *corrected == position)
+        })
+    }));
+}
+
+#[test]
+fn test_player_movement_is_acknowledged() {
+    let mut harness = TestHarness::new();
+    let player = harness.connect_and_wait();
+
+    let position = Vec3::new(8.0, 200.0, -2.0);
+    harness.place_player(player, position);
+
+    let displacement = Vec3::new(0.2, 0.0, 0.1);
+    send_player_movement(&mut harness, player, 1, position, displacement);
+
+    assert!(harness.step_until(|harness| {
+        harness.clients[player].received().iter().any(|message| {
+            matches!(message, NetworkingMessage::PlayerMovementAck { sequence: 1, position: acknowledged }
+                if acknowledged.distance(position + displacement) < 1e-4)
+        })
+    }));
+}
+
+#[test]
+fn test_joining_players_receive_spawn_point_and_health() {
+    let mut harness = TestHarness::new();
+    let client = harness.connect_and_wait();
+
+    assert!(harness.step_until(|harness| {
+        let received = harness.clients[client].received();
+        received
+            .iter()
+            .any(|message| matches!(message, NetworkingMessage::PlayerSpawn { .. }))
+            && received
+                .iter()
+                .any(|message| matches!(message, NetworkingMessage::HealthSync(MAX_HEALTH)))
+    }));
+}
+
+#[test]
+fn test_players_joining_at_the_default_spawn_survive_landing() {
+    let mut harness = TestHarness::new();
+    let client = harness.connect_and_wait();
+    let client_id = harness.clients[client].client_id;
+
+    assert!(harness.step_until(|harness| {
+        harness.clients[client]
+            .received()
+            .iter()
+            .any(|message| matches!(message, NetworkingMessage::PlayerSpawn { .. }))
+    }));
+    let position = harness.clients[client]
+        .received()
+        .iter()
+        .find_map(|message| match message {
+            NetworkingMessage::PlayerSpawn { position, .. } => Some(*position),
+            _ => None,
+        })
+        .unwrap();
+    assert!(position.y < ServerSettings::default().spawn_point.y);
+
+    // Gravity pulls the player onto the ground it already stands on
+    let displacement = Vec3::new(0.0, -0.1, 0.0);
+    for sequence in 1..=5 {
+        send_player_movement(&mut harness, client, sequence, position, displacement);
+    }
+    assert!(harness.step_until(|harness| {
+        harness.clients[client].received().iter().any(|message| {
+            matches!(message, NetworkingMessage::PlayerMovementAck { sequence: 5, position: acknowledged }
+                if acknowledged.distance(position) < 0.01)
+        })
+    }));
+    for _ in 0..20 {
+        harness.step();
+    }
+
+    let health = harness.server.world().resource::<PlayerHealths>().players[&client_id].health;
+    assert_eq!(health, MAX_HEALTH);
+    assert!(harness.clients[client].is_connected());
+}
+
+#[test]
+fn test_players_inside_blocks_suffocate() {
+    let mut harness = TestHarness::new();
+    let client = harness.connect_and_wait();
+
+    let position = Vec3::new(3.0, 10.0, 5.0);
+    harness
+        .server
+        .world_mut()
+        .resource_mut::<ChunkManager>()
+        .update_block(position + Vec3::Y * PLAYER_EYE_HEIGHT, BlockId::Stone);
+    harness.place_player(client, position);
+
+    assert!(harness.step_until(|harness| {
+        harness.clients[client].received().iter().any(|message| {
+            matches!(message, NetworkingMessage::HealthSync(health) if *health < MAX_HEALTH)
+        })
+    }));
+}
+
+#[test]
+fn test_returning_players_resume_where_they_left() {
+    let mut harness = TestHarness::new();
+    let client = harness.connect_and_wait();
+    let client_id = harness.clients[client].client_id;
+
+    let position = Vec3::new(3.5, 12.0, -7.5);
+    give_selected_item(&mut harness, client, ItemId::Block(BlockId::Stone), 5);
+    harness.place_player(client, position);
+    harness.disconnect_client(client);
+    assert!(harness.step_until(|harness| {
+        !harness
+            .server
+            .world()
+            .resource::<PlayerStates>()
+            .players
+            .contains_key(&client_id)
+    }));
+
+    let returning = harness.connect_client_as("client0");
+    assert!(harness.step_until(|harness| {
+        let received = harness.clients[returning].received();
+        received.iter().any(|message| {
+            matches!(message, NetworkingMessage::PlayerSpawn { position: p, rotation: Some(_) }
+                if *p == position)
+        }) && received.iter().any(|message| {
+            matches!(message, NetworkingMessage::InventorySync(inventory)
+                if inventory.selected_item() == Some(ItemId::Block(BlockId::Stone)))
+        })
+    }));
+
+    // Other players start over at the spawn point
+    let newcomer = harness.connect_client_as("client2");
+    assert!(harness.step_until(|harness| {
+        harness.clients[newcomer].received().iter().any(|message| {
+            matches!(
+                message,
+                NetworkingMessage::PlayerSpawn { rotation: None, .. }
+            )
+        })
+    }));
+}
+
+#[test]
+fn test_saved_players_cannot_be_taken_over_by_name() {
+    let mut harness = TestHarness::new();
+    let client = harness.connect_and_wait();
+    let client_id = harness.clients[client].client_id;
+    assert!(harness.step_until(|harness| {
+        harness.clients[client]
+            .received()
+            .iter()
+            .any(|message| matches!(message, NetworkingMessage::PlayerSpawn { .. }))
+    }));
+
+    harness.disconnect_client(client);
+    assert!(harness.step_until(|harness| {
+        !harness
+            .server
+            .world()
+            .resource::<PlayerStates>()
+            .players
+            .contains_key(&client_id)
+    }));
+
+    let impostor = harness.connect_client_with_identity("client0", PlayerIdentity::random());
+    assert!(harness.step_until(|harness| {
+        harness.clients[impostor]
+            .app
+            .world()
+            .resource::<RenetClient>()
+            .is_disconnected()
+    }));
+    assert!(!harness.clients[impostor]
+        .received()
+        .iter()
+        .any(|message| matches!(message, NetworkingMessage::PlayerSpawn { .. })));
+}
+
+fn replicated_position(message: &NetworkingMessage) -> Option<(NetworkId, Vec3)> {
+    let update = match message {
+        NetworkingMessage::EntitySpawn(update) => update,
+        NetworkingMessage::EntityUpdates { entities, .. } => entities.first()?,
+        _ => return None,
+    };
+
+    update
+        .components
+        .iter()
+        .find_map(|component| component.decode::<NetworkPosition>())
+        .map(|position| (update.id, position.0))
+}
+
+#[test]
+fn test_replicated_entities_are_spawned_updated_and_despawned() {
+    let mut harness = TestHarness::new();
+    let client = harness.connect_and_wait();
+
+    let entity = harness
+        .server
+        .world_mut()
+        .spawn((Replicated, NetworkPosition(Vec3::new(1.0, 2.0, 3.0))))
+        .id();
+
+    assert!(harness.step_until(|harness| {
+        harness.clients[client].received().iter().any(|message| {
+            matches!(message, NetworkingMessage::EntitySpawn(_))
+                && replicated_position(message).is_some_and(|(_, position)| position.x == 1.0)
+        })
+    }));
+
+    harness
+        .server
+        .world_mut()
+        .get_mut::<NetworkPosition>(entity)
+        .unwrap()
+        .0 = Vec3::new(5.0, 2.0, 3.0);
+
+    assert!(harness.step_until(|harness| {
+        harness.clients[client].received().iter().any(|message| {
+            matches!(message, NetworkingMessage::EntityUpdates { .. })
+                && replicated_position(message).is_some_and(|(_, position)| position.x == 5.0)
+        })
+    }));
+
+    harness.server.world_mut().despawn(entity);
+
+    assert!(harness.step_until(|harness| {
+        harness.clients[client]
+            .received()
+            .iter()
+            .any(|message| matches!(message, NetworkingMessage::EntityDespawn(_)))
+    }));
+}
+
+#[test]
+fn test_joining_clients_receive_existing_entities() {
+    let mut harness = TestHarness::new();
+    harness.connect_and_wait();
+
+    harness
+        .server
+        .world_mut()
+        .spawn((Replicated, NetworkPosition(Vec3::new(4.0, 5.0, 6.0))));
+    harness.step();
+
+    let late_client = harness.connect_and_wait();
+
+    assert!(harness.step_until(|harness| {
+        harness.clients[late_client]
+            .received()
+            .iter()
+            .any(|message| {
+                matches!(message, NetworkingMessage::EntitySpawn(_))
+                    && replicated_position(message)
+                        .is_some_and(|(_, position)| position == Vec3::new(4.0, 5.0, 6.0))
+            })
+    }));
+}
+
+#[cfg(feature = "chat")]
+#[test]
+fn test_chat_messages_are_synced() {
+    let mut harness = TestHarness::new();
+    let sender = harness.connect_and_wait();
+    harness.connect_and_wait();
+    let sender_id = harness.clients[sender].client_id;
+
+    harness.clients[sender].send(
+        DefaultChannel::ReliableOrdered,
+        &NetworkingMessage::ChatMessageSend("hello there".to_string()),
+    );
+
+    assert!(harness.step_until(|harness| {
+        harness.clients.iter().all(|client| {
+            client.received().iter().any(|message| {
+                matches!(message, NetworkingMessage::SingleChatMessageSync(chat_message)
+                    if chat_message.client_id == sender_id && chat_message.message == "hello there")
+            })
+        })
+    }));
+
+    // Clients joining later get the whole history
+    let late_client = harness.connect_client();
+    assert!(harness.step_until(|harness| {
+        harness.clients[late_client]
+            .received()
+            .iter()
+            .any(|message| match message {
+                NetworkingMessage::ChatMessageSync(messages) => messages
+                    .iter()
+                    .any(|chat_message| chat_message.message == "hello there"),
+                _ => false,
+            })
+    }));
+}
+
+#[cfg(feature = "chat")]
+#[test]
+fn test_operators_can_switch_game_modes() {
+    let mut harness = TestHarness::new();
+    let operator = harness.connect_and_wait();
+    let player = harness.connect_and_wait();
+
+    assert!(harness.step_until(|harness| {
+        harness.clients[player]
+            .received()
+            .iter()
+            .any(|message| matches!(message, NetworkingMessage::GameModeSync(GameMode::Survival)))
+    }));
+
+    harness.clients[player].send(
+        DefaultChannel::ReliableOrdered,
+        &NetworkingMessage::ChatMessageSend("/gamemode creative".to_string()),
+    );
+    assert!(harness.step_until(|harness| {
+        harness.clients[player].received().iter().any(|message| {
+            matches!(message, NetworkingMessage::SingleChatMessageSync(chat_message)
+                if chat_message.client_id == SERVER_MESSAGE_ID
+                    && chat_message.message.contains("operators"))
+        })
+    }));
+
+    harness.clients[operator].send(
+        DefaultChannel::ReliableOrdered,
+        &NetworkingMessage::ChatMessageSend("/gamemode spectator client1".to_string()),
+    );
+    assert!(harness.step_until(|harness| {
+        harness.clients[player].received().iter().any(|message| {
+            matches!(
+                message,
+                NetworkingMessage::GameModeSync(GameMode::Spectator)
+            )
+        })
+    }));
+
+    // Commands are not broadcast as chat messages
+    assert!(!harness.clients[player].received().iter().any(|message| {
+        matches!(message, NetworkingMessage::GameModeSync(GameMode::Creative))
+            || matches!(message, NetworkingMessage::SingleChatMessageSync(chat_message)
+            if chat_message.message.starts_with('/'))
+    }));
+}